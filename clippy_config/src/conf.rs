@@ -1,8 +1,8 @@
 use crate::ClippyConfiguration;
 use crate::types::{
-    DisallowedPath, MacroMatcher, MatchLintBehaviour, PubUnderscoreFieldsBehaviour, Rename, SourceItemOrdering,
-    SourceItemOrderingCategory, SourceItemOrderingModuleItemGroupings, SourceItemOrderingModuleItemKind,
-    SourceItemOrderingTraitAssocItemKind, SourceItemOrderingTraitAssocItemKinds,
+    CustomLint, DisallowedPath, MacroMatcher, MatchLintBehaviour, PubUnderscoreFieldsBehaviour, Rename,
+    SourceItemOrdering, SourceItemOrderingCategory, SourceItemOrderingModuleItemGroupings,
+    SourceItemOrderingModuleItemKind, SourceItemOrderingTraitAssocItemKind, SourceItemOrderingTraitAssocItemKinds,
 };
 use clippy_utils::msrvs::Msrv;
 use rustc_errors::Applicability;
@@ -460,6 +460,19 @@ define_Conf! {
     /// The maximum cognitive complexity a function can have
     #[lints(cognitive_complexity)]
     cognitive_complexity_threshold: u64 = 25,
+    /// Lints matching the expressions of a pattern, see the "Custom lints" chapter of the book.
+    ///
+    /// #### Example
+    ///
+    /// ```toml
+    /// [[custom-lints]]
+    /// name = "unwrap_or_new_vec"
+    /// message = "use of `unwrap_or(Vec::new())`"
+    /// pattern = "$x.unwrap_or(Vec::new())"
+    /// replacement = "$x.unwrap_or_default()"
+    /// types = { x = "Option<_>" }
+    /// ```
+    custom_lints: Vec<CustomLint> = Vec::new(),
     /// DEPRECATED LINT: CYCLOMATIC_COMPLEXITY.
     ///
     /// Use the Cognitive Complexity lint instead.
@@ -3,7 +3,7 @@ use rustc_hir::def_id::DefIdMap;
 use rustc_middle::ty::TyCtxt;
 use serde::de::{self, Deserializer, Visitor};
 use serde::{Deserialize, Serialize, ser};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +46,32 @@ pub fn create_disallowed_map(
         .collect()
 }
 
+/// A lint defined in the `custom-lints` configuration
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomLint {
+    pub name: String,
+    pub message: String,
+    #[serde(default)]
+    pub level: CustomLintLevel,
+    pub pattern: String,
+    pub replacement: Option<String>,
+    pub help: Option<String>,
+    /// The types of the metavariables, by name without the `$`
+    #[serde(default)]
+    pub types: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomLintLevel {
+    Allow,
+    #[default]
+    Warn,
+    Deny,
+    Forbid,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum MatchLintBehaviour {
     AllTypes,
@@ -436,6 +462,7 @@ macro_rules! unimplemented_serialize {
 }
 
 unimplemented_serialize! {
+    CustomLint,
     DisallowedPath,
     Rename,
     MacroMatcher,
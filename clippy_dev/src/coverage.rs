@@ -0,0 +1,547 @@
+use crate::update_lints::lint_sources;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Lint groups, which may be enabled in the tests like lints
+const GROUPS: &[&str] = &[
+    "all",
+    "cargo",
+    "complexity",
+    "correctness",
+    "nursery",
+    "pedantic",
+    "perf",
+    "restriction",
+    "style",
+    "suspicious",
+];
+
+/// A test of the `tests/ui`, `tests/ui-internal`, `tests/ui-toml` or `tests/ui-cargo` suites
+struct Test {
+    /// The sources of the test, those of the whole package for `ui-cargo` tests
+    sources: String,
+    /// The contents of the `.stderr` files, one per revision
+    stderr: Vec<String>,
+    /// Whether the test has a `.fixed` file
+    fixed: bool,
+    /// Whether the test has a `clippy.toml`
+    config: bool,
+    /// Whether the test sets a MSRV, with `#[clippy::msrv]`, `msrv` or `rust-version`
+    msrv: bool,
+    /// The names of the test file and of its directories, a test named after a lint tests it
+    names: Vec<String>,
+}
+
+impl Test {
+    /// The lints the test enables, or is named after
+    fn tested_lints(&self, lints: &HashMap<String, PathBuf>) -> HashSet<String> {
+        let mut tested: HashSet<String> = enabled_lints(&self.sources).into_iter().collect();
+        tested.extend(self.names.iter().filter(|name| lints.contains_key(*name)).cloned());
+        tested
+    }
+
+    /// The number of emissions of each lint
+    fn emissions(&self, tested: &HashSet<String>) -> HashMap<String, usize> {
+        let mut emissions = HashMap::new();
+        // Only the `//~` annotations are left if the test has no `.stderr` file
+        let emitted = if self.stderr.is_empty() {
+            let fallback = (tested.len() == 1).then(|| tested.iter().next().unwrap().clone());
+            annotation_lints(&self.sources)
+                .into_iter()
+                .map(|lint| lint.or_else(|| fallback.clone()))
+                .collect()
+        } else {
+            self.stderr
+                .iter()
+                .flat_map(|stderr| stderr_lints(stderr))
+                .collect::<Vec<_>>()
+        };
+        for lint in emitted.into_iter().flatten() {
+            *emissions.entry(lint).or_insert(0) += 1;
+        }
+        emissions
+    }
+}
+
+#[derive(Default)]
+struct Coverage {
+    /// The number of emissions in the tests
+    positive: usize,
+    /// The number of tests for the lint without any of its emissions
+    negative: usize,
+    /// Whether a test emitting the lint has a `.fixed` file
+    fixed: bool,
+    /// Whether a test for the lint sets a MSRV
+    msrv: bool,
+    /// Whether a test for the lint has a `clippy.toml`
+    config: bool,
+}
+
+/// Runs the `coverage` command
+///
+/// Reports how well each lint is covered by the ui tests, based on the `.stderr` files, or on the
+/// `//~` annotations if a test has no `.stderr` file. The diagnostics which don't name their lint
+/// are attributed to the lint of a previous diagnostic of the test, and a lint is assumed to have
+/// machine applicable suggestions if the file it's implemented in has some, so the report is an
+/// approximation.
+///
+/// # Panics
+///
+/// Panics if a test could not be read.
+pub fn run(filter: &[String], gaps: bool) {
+    let lints: HashMap<String, PathBuf> = lint_sources().into_iter().collect();
+    let (msrv_lints, config_lints) = configured_lints();
+
+    let mut coverage: HashMap<&str, Coverage> = lints.keys().map(|lint| (&**lint, Coverage::default())).collect();
+    for test in ui_tests().chain(ui_cargo_tests()) {
+        let tested = test.tested_lints(&lints);
+        let emissions = test.emissions(&tested);
+        for lint in tested.iter().chain(emissions.keys()) {
+            let Some(coverage) = coverage.get_mut(&**lint) else {
+                continue;
+            };
+            coverage.msrv |= test.msrv;
+            coverage.config |= test.config;
+        }
+        for (lint, &count) in &emissions {
+            if let Some(coverage) = coverage.get_mut(&**lint) {
+                coverage.positive += count;
+                coverage.fixed |= test.fixed;
+            }
+        }
+        for lint in tested.iter().filter(|lint| !emissions.contains_key(*lint)) {
+            if let Some(coverage) = coverage.get_mut(&**lint) {
+                coverage.negative += 1;
+            }
+        }
+    }
+
+    let mut rows: Vec<Row<'_>> = coverage
+        .into_iter()
+        .filter(|(lint, _)| filter.is_empty() || filter.iter().any(|name| name == lint))
+        .map(|(lint, coverage)| {
+            let machine_applicable = fs::read_to_string(&lints[lint]).is_ok_and(|s| s.contains("MachineApplicable"));
+            Row {
+                lint,
+                positive: coverage.positive,
+                negative: coverage.negative,
+                fixed: machine_applicable.then_some(coverage.fixed),
+                msrv: msrv_lints.contains(lint).then_some(coverage.msrv),
+                config: config_lints.contains(lint).then_some(coverage.config),
+            }
+        })
+        .collect();
+    rows.sort_unstable_by_key(|row| row.lint);
+
+    println!(
+        "{:<50} {:>8} {:>8} {:>6} {:>6} {:>6}",
+        "lint", "positive", "negative", "fixed", "msrv", "config"
+    );
+    for row in rows.iter().filter(|row| !gaps || row.has_gaps()) {
+        println!(
+            "{:<50} {:>8} {:>8} {:>6} {:>6} {:>6}",
+            row.lint,
+            row.positive,
+            row.negative,
+            yes_no(row.fixed),
+            yes_no(row.msrv),
+            yes_no(row.config)
+        );
+    }
+
+    let count = |f: fn(&Row<'_>) -> bool| rows.iter().filter(|row| f(row)).count();
+    println!();
+    println!("{} lints", rows.len());
+    println!("{} without positive cases", count(|row| row.positive == 0));
+    println!("{} without negative cases", count(|row| row.negative == 0));
+    println!(
+        "{} with machine applicable suggestions but no `.fixed` file",
+        count(|row| row.fixed == Some(false))
+    );
+    println!("{} with a MSRV but no MSRV test", count(|row| row.msrv == Some(false)));
+    println!(
+        "{} with configuration options but no `clippy.toml` test",
+        count(|row| row.config == Some(false))
+    );
+}
+
+/// The coverage of a lint, the kinds of tests which don't apply to the lint are `None`
+struct Row<'a> {
+    lint: &'a str,
+    positive: usize,
+    negative: usize,
+    fixed: Option<bool>,
+    msrv: Option<bool>,
+    config: Option<bool>,
+}
+
+impl Row<'_> {
+    /// Most lints test their negative cases in the same files as their positive cases, so tests
+    /// without emissions aren't counted as missing
+    fn has_gaps(&self) -> bool {
+        self.positive == 0 || [self.fixed, self.msrv, self.config].contains(&Some(false))
+    }
+}
+
+fn yes_no(covered: Option<bool>) -> &'static str {
+    match covered {
+        Some(true) => "yes",
+        Some(false) => "no",
+        None => "-",
+    }
+}
+
+/// The tests of `tests/ui`, `tests/ui-internal` and `tests/ui-toml`
+fn ui_tests() -> impl Iterator<Item = Test> {
+    ["tests/ui", "tests/ui-internal", "tests/ui-toml"]
+        .into_iter()
+        .flat_map(|dir| {
+            WalkDir::new(dir)
+                .into_iter()
+                .filter_entry(|e| e.file_name() != "auxiliary")
+        })
+        .map(Result::unwrap)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+        .map(|e| {
+            let path = e.path();
+            let sources = read(path);
+            let stem = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let siblings = |ext: &str| -> Vec<PathBuf> {
+                fs::read_dir(path.parent().unwrap())
+                    .unwrap()
+                    .map(|e| e.unwrap().path())
+                    .filter(|p| {
+                        p.extension().is_some_and(|e| e == ext)
+                            && p.file_stem().unwrap().to_str().is_some_and(|s| {
+                                // `test.stderr`, or `test.revision.stderr`
+                                s == stem || s.strip_prefix(&*stem).is_some_and(|rev| rev.starts_with('.'))
+                            })
+                    })
+                    .collect()
+            };
+
+            // The `clippy.toml` of the test's directory, or of its revisions
+            let conf_dirs = sources
+                .match_indices("CLIPPY_CONF_DIR=")
+                .map(|(pos, var)| {
+                    let dir = &sources[pos + var.len()..];
+                    Path::new(dir[..dir.find(char::is_whitespace).unwrap_or(dir.len())].trim_matches('"'))
+                })
+                .chain(
+                    path.ancestors()
+                        .skip(1)
+                        .take_while(|dir| dir.starts_with("tests/ui-toml") && *dir != Path::new("tests/ui-toml")),
+                );
+            let clippy_tomls: Vec<String> = conf_dirs
+                .map(|dir| dir.join("clippy.toml"))
+                .filter(|toml| toml.exists())
+                .map(|toml| read(&toml))
+                .collect();
+            let mut names = vec![stem.clone()];
+            if let Ok(dir) = path.strip_prefix("tests/ui-toml") {
+                names.extend(
+                    dir.parent()
+                        .into_iter()
+                        .flat_map(Path::iter)
+                        .map(|name| name.to_string_lossy().into_owned()),
+                );
+            }
+
+            Test {
+                msrv: sources.contains("clippy::msrv")
+                    || clippy_tomls
+                        .iter()
+                        .any(|toml| toml.contains("msrv") || toml.contains("rust-version")),
+                stderr: siblings("stderr").iter().map(|p| read(p)).collect(),
+                fixed: !siblings("fixed").is_empty(),
+                config: !clippy_tomls.is_empty(),
+                sources,
+                names,
+            }
+        })
+}
+
+/// The packages of `tests/ui-cargo`, in `tests/ui-cargo/<lint>/<case>`
+fn ui_cargo_tests() -> impl Iterator<Item = Test> {
+    WalkDir::new("tests/ui-cargo")
+        .min_depth(2)
+        .max_depth(2)
+        .into_iter()
+        .map(Result::unwrap)
+        .filter(|e| e.path().join("Cargo.toml").exists())
+        .map(|e| {
+            let dir = e.path();
+            let mut sources = read(&dir.join("Cargo.toml"));
+            for file in WalkDir::new(dir.join("src")).into_iter().map(Result::unwrap) {
+                if file.path().extension().is_some_and(|ext| ext == "rs") {
+                    sources.push_str(&read(file.path()));
+                }
+            }
+            let clippy_toml = [dir.join("clippy.toml"), dir.join(".clippy.toml")]
+                .into_iter()
+                .find(|toml| toml.exists())
+                .map(|toml| read(&toml));
+            let stderr = dir.join("Cargo.stderr");
+
+            Test {
+                msrv: sources.contains("rust-version")
+                    || sources.contains("clippy::msrv")
+                    || clippy_toml.as_ref().is_some_and(|toml| toml.contains("msrv")),
+                stderr: stderr.exists().then(|| read(&stderr)).into_iter().collect(),
+                fixed: false,
+                config: clippy_toml.is_some(),
+                sources,
+                names: dir
+                    .strip_prefix("tests/ui-cargo")
+                    .unwrap()
+                    .iter()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .collect(),
+            }
+        })
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read `{}`: {e}", path.display()))
+}
+
+/// The lints which have a MSRV, and those with other configuration options
+fn configured_lints() -> (HashSet<String>, HashSet<String>) {
+    let conf = read(Path::new("clippy_config/src/conf.rs"));
+    let mut msrv = HashSet::new();
+    let mut config = HashSet::new();
+    let mut rest = &*conf;
+    while let Some(start) = rest.find("#[lints(") {
+        rest = &rest[start + "#[lints(".len()..];
+        let Some(end) = rest.find(")]") else { break };
+        let lints = rest[..end].split(',').map(str::trim).filter(|lint| !lint.is_empty());
+        let option = rest[end + ")]".len()..]
+            .trim_start()
+            .split(':')
+            .next()
+            .unwrap_or_default();
+        if option == "msrv" {
+            msrv.extend(lints.map(String::from));
+        } else {
+            config.extend(lints.map(String::from));
+        }
+        rest = &rest[end..];
+    }
+    (msrv, config)
+}
+
+/// Returns the `clippy::lint_name` at the start of `s`, if it isn't a lint group
+fn lint_name(s: &str) -> Option<String> {
+    let name = s.strip_prefix("clippy::")?;
+    let end = name
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+        .unwrap_or(name.len());
+    let name = name[..end].replace('-', "_");
+    (!name.is_empty() && !GROUPS.contains(&&*name)).then_some(name)
+}
+
+/// Returns the `clippy::lint_name`s in `s`
+fn lint_names(s: &str) -> impl Iterator<Item = String> + '_ {
+    s.match_indices("clippy::").filter_map(|(pos, _)| lint_name(&s[pos..]))
+}
+
+/// The lints enabled in the sources by lint level attributes, `compile-flags` or `[lints]` tables
+fn enabled_lints(sources: &str) -> Vec<String> {
+    let mut lints = Vec::new();
+    let mut in_attr = false;
+    let mut in_lints_table = false;
+    for line in sources.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_lints_table = line == "[lints.clippy]" || line == "[workspace.lints.clippy]";
+        } else if in_lints_table && !line.contains("allow") {
+            // `lint_name = "warn"`
+            if let Some((name, _)) = line.split_once('=') {
+                lints.extend(lint_name(&format!("clippy::{}", name.trim())));
+            }
+        }
+
+        let enables = ["warn(", "deny(", "forbid(", "expect("]
+            .iter()
+            .any(|attr| line.contains(attr));
+        if in_attr || enables || line.starts_with("//@compile-flags") {
+            lints.extend(lint_names(line));
+            // A multi-line attribute
+            in_attr = (in_attr || enables) && !line.contains(')');
+        }
+    }
+    lints
+}
+
+/// The lint emitted by each diagnostic of a `.stderr` file, `None` for the diagnostics which aren't
+/// emitted by a Clippy lint
+///
+/// Only the first emission of a lint names it, the following diagnostics are attributed to the
+/// lint of a previous diagnostic with the same message, or else to the lint of the one before.
+fn stderr_lints(stderr: &str) -> Vec<Option<String>> {
+    let mut diagnostics: Vec<(&str, Vec<&str>)> = Vec::new();
+    for line in stderr.lines() {
+        if let Some(message) = line.strip_prefix("error").or_else(|| line.strip_prefix("warning")) {
+            diagnostics.push((message, Vec::new()));
+        } else if let Some((_, lines)) = diagnostics.last_mut() {
+            lines.push(line);
+        }
+    }
+
+    let mut by_message: HashMap<&str, String> = HashMap::new();
+    let mut previous: Option<String> = None;
+    let mut lints = Vec::new();
+    for (message, lines) in diagnostics {
+        // Compiler errors have a code, e.g. `error[E0308]`
+        let Some(message) = message.strip_prefix(": ") else {
+            lints.push(None);
+            continue;
+        };
+        if message.starts_with("aborting due to")
+            || message.starts_with("could not compile")
+            || message.ends_with("emitted")
+        {
+            continue;
+        }
+
+        let lint = named_lint(&lines);
+        let lint = match lint {
+            Some(Some(lint)) => {
+                by_message.insert(message, lint.clone());
+                previous = Some(lint.clone());
+                Some(lint)
+            },
+            // Named a lint, but not a Clippy lint
+            Some(None) => None,
+            None => by_message.get(message).cloned().or_else(|| previous.clone()),
+        };
+        lints.push(lint);
+    }
+    lints
+}
+
+/// The lint named by the notes of a diagnostic, `Some(None)` if it names a lint which isn't a
+/// Clippy lint
+#[expect(
+    clippy::option_option,
+    reason = "the diagnostic may name a lint that isn't a Clippy lint"
+)]
+fn named_lint(lines: &[&str]) -> Option<Option<String>> {
+    for line in lines {
+        let line = line.trim_start();
+        // `= note: `-D clippy::lint-name` implied by `-D warnings``
+        // `= note: `#[warn(clippy::lint_name)]` on by default`
+        if let Some(note) = line.strip_prefix("= note: `")
+            && (note.contains("` implied by `") || note.ends_with("` on by default"))
+        {
+            let flag_or_attr = note.trim_start_matches("-D ").trim_start_matches("-W ");
+            let path = flag_or_attr.split_once('(').map_or(flag_or_attr, |(_, path)| path);
+            return Some(lint_name(path));
+        }
+    }
+
+    // The lint level attribute is underlined in:
+    // note: the lint level is defined here
+    //   --> tests/ui/test.rs:1:9
+    //    |
+    // LL | #![deny(clippy::lint_name)]
+    //    |         ^^^^^^^^^^^^^^^^^
+    let pos = lines
+        .iter()
+        .position(|line| line.contains("the lint level is defined here"))?;
+    let mut lines = lines[pos..].iter().skip_while(|line| !line.starts_with("LL |"));
+    let code = lines.next()?;
+    let underline = lines.next()?;
+    let start = underline.find('^')?;
+    let end = start + underline[start..].find(|c| c != '^').unwrap_or(underline.len() - start);
+    Some(lint_name(code.get(start..end)?))
+}
+
+/// The lint named by the `//~` annotations of each diagnostic, an annotation followed by its `//~|`
+/// continuations
+fn annotation_lints(sources: &str) -> Vec<Option<String>> {
+    let mut lints: Vec<Option<String>> = Vec::new();
+    for line in sources.lines() {
+        let Some(pos) = line.find("//~") else { continue };
+        let annotation = &line[pos + "//~".len()..];
+        let lint = lint_names(annotation).next();
+        if annotation.starts_with('|')
+            && let Some(last) = lints.last_mut()
+        {
+            *last = last.take().or(lint);
+        } else {
+            lints.push(lint);
+        }
+    }
+    lints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stderr_lints() {
+        static STDERR: &str = "error: first
+  --> tests/ui/test.rs:5:5
+   |
+LL |     a;
+   |     ^
+   |
+   = note: `-D clippy::foo-bar` implied by `-D warnings`
+   = help: to override `-D warnings` add `#[allow(clippy::foo_bar)]`
+
+error: second
+  --> tests/ui/test.rs:6:5
+   |
+LL |     b;
+   |     ^
+   |
+note: the lint level is defined here
+  --> tests/ui/test.rs:1:9
+   |
+LL | #![deny(clippy::baz)]
+   |         ^^^^^^^^^^^
+
+error[E0308]: mismatched types
+  --> tests/ui/test.rs:7:5
+
+warning: unused variable: `c`
+  --> tests/ui/test.rs:8:9
+   |
+   = note: `#[warn(unused_variables)]` on by default
+
+error: first
+  --> tests/ui/test.rs:9:5
+
+error: third
+  --> tests/ui/test.rs:10:5
+
+error: aborting due to 5 previous errors; 1 warning emitted
+
+";
+        assert_eq!(stderr_lints(STDERR), [
+            Some("foo_bar".into()),
+            Some("baz".into()),
+            None,
+            None,
+            Some("foo_bar".into()),
+            Some("baz".into()),
+        ]);
+    }
+
+    #[test]
+    fn test_enabled_lints() {
+        static SOURCES: &str = "//@compile-flags: -W clippy::foo
+#![deny(clippy::bar, clippy::pedantic)]
+#![warn(
+    clippy::baz,
+    clippy::qux_quux
+)]
+#![allow(clippy::allowed)]
+";
+        assert_eq!(enabled_lints(SOURCES), ["foo", "bar", "baz", "qux_quux"]);
+    }
+}
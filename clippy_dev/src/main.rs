@@ -3,7 +3,7 @@
 #![warn(rust_2018_idioms, unused_lifetimes)]
 
 use clap::{Args, Parser, Subcommand};
-use clippy_dev::{dogfood, fmt, lint, new_lint, serve, setup, update_lints};
+use clippy_dev::{coverage, dogfood, fmt, lint, new_lint, serve, setup, update_lints};
 use std::convert::Infallible;
 
 fn main() {
@@ -34,7 +34,16 @@ fn main() {
             category,
             r#type,
             msrv,
-        } => match new_lint::create(&pass, &name, &category, r#type.as_deref(), msrv) {
+            config,
+        } => match new_lint::create(
+            &pass,
+            &name,
+            &category,
+            r#type.as_deref(),
+            msrv.is_some(),
+            msrv.flatten(),
+            &config,
+        ) {
             Ok(()) => update_lints::update(update_lints::UpdateMode::Change),
             Err(e) => eprintln!("Unable to create lint: {e}"),
         },
@@ -75,6 +84,8 @@ fn main() {
             uplift,
         } => update_lints::rename(&old_name, new_name.as_ref().unwrap_or(&old_name), uplift),
         DevCommand::Deprecate { name, reason } => update_lints::deprecate(&name, &reason),
+        DevCommand::MergeLint { old_name, into } => update_lints::merge(&old_name, &into),
+        DevCommand::Coverage { lints, gaps } => coverage::run(&lints, gaps),
     }
 }
 
@@ -164,9 +175,15 @@ enum DevCommand {
         #[arg(long)]
         /// What directory the lint belongs in
         r#type: Option<String>,
-        #[arg(long)]
-        /// Add MSRV config code to the lint
-        msrv: bool,
+        #[arg(long, num_args = 0..=1, value_name = "VERSION", value_parser = new_lint::parse_msrv)]
+        /// Add MSRV config code to the lint. If a version is given, e.g. `1.70.0`, it is also
+        /// added to `clippy_utils/src/msrvs.rs`
+        #[expect(clippy::option_option, reason = "`--msrv` may be given without a version")]
+        msrv: Option<Option<[u32; 3]>>,
+        #[arg(long, value_name = "NAME:TYPE:DEFAULT")]
+        /// Add a configuration option to the lint, e.g. `max_depth:u64:3`. May be used multiple
+        /// times
+        config: Vec<new_lint::ConfOption>,
     },
     /// Support for setting up your personal development environment
     Setup(SetupCommand),
@@ -225,6 +242,28 @@ enum DevCommand {
         /// The reason for deprecation
         reason: String,
     },
+    #[command(name = "merge_lint", alias = "merge-lint")]
+    /// Merge a lint into another one, which now covers its cases
+    MergeLint {
+        /// The name of the lint to merge
+        old_name: String,
+        /// The name of the lint to merge it into
+        into: String,
+    },
+    /// Report how well each lint is covered by the ui tests
+    ///
+    /// For each lint, shows the number of emissions in the tests (positive), the number of tests
+    /// for the lint without any emission (negative), and whether there is a `.fixed` file (if the
+    /// lint has machine applicable suggestions), a MSRV test (if the lint has a MSRV) and a
+    /// `clippy.toml` test (if the lint has configuration options).
+    Coverage {
+        #[arg(long = "lint", value_name = "LINT")]
+        /// Only report the given lints
+        lints: Vec<String>,
+        #[arg(long)]
+        /// Only report the lints missing some kind of test
+        gaps: bool,
+    },
 }
 
 #[derive(Args)]
@@ -6,6 +6,7 @@ use std::fs::{self, OpenOptions};
 use std::io::prelude::*;
 use std::io::{self, ErrorKind};
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 struct LintData<'a> {
     pass: &'a str,
@@ -13,6 +14,117 @@ struct LintData<'a> {
     category: &'a str,
     ty: Option<&'a str>,
     project_root: PathBuf,
+    /// The version added to `clippy_utils/src/msrvs.rs` with `--msrv X.Y.Z`
+    msrv_version: Option<[u32; 3]>,
+    config: &'a [ConfOption],
+}
+
+/// A configuration option of the new lint, given as `name:type:default` e.g. `max_depth:u64:3`
+#[derive(Clone, Debug)]
+pub struct ConfOption {
+    name: String,
+    ty: String,
+    default: String,
+}
+
+impl FromStr for ConfOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The default value may contain a `::`, the type and name can't
+        let mut parts = s.splitn(3, ':').map(str::trim);
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(name), Some(ty), Some(default)) if !name.is_empty() && !ty.is_empty() && !default.is_empty() => {
+                Ok(Self {
+                    name: name.replace('-', "_"),
+                    ty: ty.to_string(),
+                    default: default.to_string(),
+                })
+            },
+            _ => Err(format!("expected `name:type:default`, found `{s}`")),
+        }
+    }
+}
+
+impl ConfOption {
+    /// Whether the lint copies the value out of the `Conf`, otherwise it keeps a reference to it
+    fn is_copy(&self) -> bool {
+        matches!(
+            self.ty.as_str(),
+            "bool"
+                | "char"
+                | "u8"
+                | "u16"
+                | "u32"
+                | "u64"
+                | "u128"
+                | "usize"
+                | "i8"
+                | "i16"
+                | "i32"
+                | "i64"
+                | "i128"
+                | "isize"
+                | "f32"
+                | "f64"
+        )
+    }
+
+    fn field_ty(&self) -> String {
+        if self.is_copy() {
+            self.ty.clone()
+        } else {
+            format!("&'static {}", self.ty)
+        }
+    }
+
+    fn param_ty(&self) -> String {
+        if self.is_copy() {
+            self.ty.clone()
+        } else {
+            format!("&{}", self.ty)
+        }
+    }
+
+    fn field_init(&self) -> String {
+        if self.is_copy() {
+            format!("conf.{}", self.name)
+        } else {
+            format!("&conf.{}", self.name)
+        }
+    }
+
+    /// The default value as a TOML value, if it is a simple literal
+    fn toml_default(&self) -> Option<String> {
+        let default = self.default.as_str();
+        match default {
+            "true" | "false" => Some(default.to_string()),
+            "Vec::new()" | "Vec::default()" | "vec![]" => Some(String::from("[]")),
+            _ if default.replace('_', "").parse::<f64>().is_ok() => Some(default.replace('_', "")),
+            _ if default.starts_with('"') => {
+                let literal = default
+                    .strip_suffix(".to_string()")
+                    .or_else(|| default.strip_suffix(".into()"))
+                    .unwrap_or(default);
+                Some(literal.to_string())
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Parses the `X.Y.Z` version given to `--msrv`, the patch version may be omitted
+///
+/// # Errors
+///
+/// This function errors out if the version isn't made of two or three numbers.
+pub fn parse_msrv(version: &str) -> Result<[u32; 3], String> {
+    let parts: Vec<_> = version.split('.').map(str::parse::<u32>).collect();
+    match parts[..] {
+        [Ok(major), Ok(minor)] => Ok([major, minor, 0]),
+        [Ok(major), Ok(minor), Ok(patch)] => Ok([major, minor, patch]),
+        _ => Err(format!("expected a version like `1.70.0`, found `{version}`")),
+    }
 }
 
 trait Context {
@@ -33,10 +145,20 @@ impl<T> Context for io::Result<T> {
 
 /// Creates the files required to implement and test a new lint and runs `update_lints`.
 ///
+/// `msrv_version` is the version required by the lint if it is known, it implies `msrv`.
+///
 /// # Errors
 ///
 /// This function errors out if the files couldn't be created or written to.
-pub fn create(pass: &str, name: &str, category: &str, mut ty: Option<&str>, msrv: bool) -> io::Result<()> {
+pub fn create(
+    pass: &str,
+    name: &str,
+    category: &str,
+    mut ty: Option<&str>,
+    msrv: bool,
+    msrv_version: Option<[u32; 3]>,
+    config: &[ConfOption],
+) -> io::Result<()> {
     if category == "cargo" && ty.is_none() {
         // `cargo` is a special category, these lints should always be in `clippy_lints/src/cargo`
         ty = Some("cargo");
@@ -48,7 +170,10 @@ pub fn create(pass: &str, name: &str, category: &str, mut ty: Option<&str>, msrv
         category,
         ty,
         project_root: clippy_project_root(),
+        msrv_version,
+        config,
     };
+    let msrv = msrv || msrv_version.is_some();
 
     create_lint(&lint, msrv).context("Unable to create lint implementation")?;
     create_test(&lint, msrv).context("Unable to create a test for the new lint")?;
@@ -57,6 +182,23 @@ pub fn create(pass: &str, name: &str, category: &str, mut ty: Option<&str>, msrv
         add_lint(&lint, msrv).context("Unable to add lint to clippy_lints/src/lib.rs")?;
     }
 
+    if let Some(version) = lint.msrv_version {
+        add_msrv_alias(&lint, version).context("Unable to add the MSRV to clippy_utils/src/msrvs.rs")?;
+    }
+
+    if msrv || !config.is_empty() {
+        add_conf(&lint, msrv).context("Unable to add the configuration to clippy_config/src/conf.rs")?;
+    }
+
+    if !config.is_empty() {
+        create_ui_toml_test(&lint).context("Unable to create a ui-toml test for the new lint")?;
+        println!(
+            "\n\
+            NOTE: Run `cargo bless --test config-metadata` to add the configuration to the book, and\n\
+            `TESTNAME=toml_unknown_key cargo uibless` to add it to the list of the known keys"
+        );
+    }
+
     if pass == "early" {
         println!(
             "\n\
@@ -88,6 +230,7 @@ fn create_test(lint: &LintData<'_>, msrv: bool) -> io::Result<()> {
         case: &str,
         hint: &str,
         msrv: bool,
+        msrv_version: Option<[u32; 3]>,
     ) -> io::Result<()> {
         let mut path = location.into().join(case);
         fs::create_dir(&path)?;
@@ -95,7 +238,10 @@ fn create_test(lint: &LintData<'_>, msrv: bool) -> io::Result<()> {
 
         path.push("src");
         fs::create_dir(&path)?;
-        write_file(path.join("main.rs"), get_test_file_contents(lint_name, msrv))?;
+        write_file(
+            path.join("main.rs"),
+            get_test_file_contents(lint_name, msrv, msrv_version),
+        )?;
 
         Ok(())
     }
@@ -111,6 +257,7 @@ fn create_test(lint: &LintData<'_>, msrv: bool) -> io::Result<()> {
             "fail",
             "Content that triggers the lint goes here",
             msrv,
+            lint.msrv_version,
         )?;
         create_project_layout(
             lint.name,
@@ -118,12 +265,13 @@ fn create_test(lint: &LintData<'_>, msrv: bool) -> io::Result<()> {
             "pass",
             "This file should not trigger the lint",
             false,
+            None,
         )?;
 
         println!("Generated test directories: `{relative_test_dir}/pass`, `{relative_test_dir}/fail`");
     } else {
         let test_path = format!("tests/ui/{}.rs", lint.name);
-        let test_contents = get_test_file_contents(lint.name, msrv);
+        let test_contents = get_test_file_contents(lint.name, msrv, lint.msrv_version);
         write_file(lint.project_root.join(&test_path), test_contents)?;
 
         println!("Generated test file: `{test_path}`");
@@ -136,12 +284,21 @@ fn add_lint(lint: &LintData<'_>, enable_msrv: bool) -> io::Result<()> {
     let path = "clippy_lints/src/lib.rs";
     let mut lib_rs = fs::read_to_string(path).context("reading")?;
 
+    register_pass(&mut lib_rs, lint, enable_msrv || !lint.config.is_empty());
+
+    fs::write(path, lib_rs).context("writing")
+}
+
+/// Registers the lint pass in `register_lints`, before the `// add lints here` comment
+fn register_pass(lib_rs: &mut String, lint: &LintData<'_>, needs_conf: bool) {
     let comment_start = lib_rs.find("// add lints here,").expect("Couldn't find comment");
 
-    let new_lint = if enable_msrv {
+    // Late passes look at one item at a time, their diagnostics can be cached for each item, see
+    // `register_lints`
+    let lint_pass = if lint.pass == "late" { "late_mod" } else { lint.pass };
+    let new_lint = if needs_conf {
         format!(
             "store.register_{lint_pass}_pass(move |{ctor_arg}| Box::new({module_name}::{camel_name}::new(conf)));\n    ",
-            lint_pass = lint.pass,
             ctor_arg = if lint.pass == "late" { "_" } else { "" },
             module_name = lint.name,
             camel_name = to_camel_case(lint.name),
@@ -149,7 +306,6 @@ fn add_lint(lint: &LintData<'_>, enable_msrv: bool) -> io::Result<()> {
     } else {
         format!(
             "store.register_{lint_pass}_pass(|{ctor_arg}| Box::new({module_name}::{camel_name}));\n    ",
-            lint_pass = lint.pass,
             ctor_arg = if lint.pass == "late" { "_" } else { "" },
             module_name = lint.name,
             camel_name = to_camel_case(lint.name),
@@ -157,8 +313,238 @@ fn add_lint(lint: &LintData<'_>, enable_msrv: bool) -> io::Result<()> {
     };
 
     lib_rs.insert_str(comment_start, &new_lint);
+}
 
-    fs::write(path, lib_rs).context("writing")
+/// Adds the lint's MSRV to `msrv_aliases!`, named after the lint. The versions are listed from the
+/// newest to the oldest.
+fn add_msrv_alias(lint: &LintData<'_>, version: [u32; 3]) -> io::Result<()> {
+    let path = lint.project_root.join("clippy_utils/src/msrvs.rs");
+    let mut contents = fs::read_to_string(&path).context("reading")?;
+    let alias = lint.name.to_uppercase();
+
+    insert_msrv_alias(&mut contents, &alias, version);
+
+    fs::write(path, contents).context("writing")?;
+    println!("Added `msrvs::{alias}` to `clippy_utils/src/msrvs.rs`");
+    Ok(())
+}
+
+/// Adds `alias` to the line of `version` in `msrv_aliases!`, or to a new line
+fn insert_msrv_alias(contents: &mut String, alias: &str, version: [u32; 3]) {
+    let start = contents
+        .find("msrv_aliases! {\n")
+        .expect("Couldn't find `msrv_aliases!`")
+        + "msrv_aliases! {\n".len();
+    let end = start
+        + contents[start..]
+            .find("\n}")
+            .expect("Couldn't find the end of `msrv_aliases!`")
+        + 1;
+
+    let mut offset = start;
+    let mut new_line = Some(format!(
+        "    {},{},{} {{ {alias} }}\n",
+        version[0], version[1], version[2]
+    ));
+    for line in contents[start..end].lines() {
+        let line_range = offset..offset + line.len();
+        offset = line_range.end + 1;
+
+        // `    1,83,0 { CONST_EXTERN_FN, CONST_FLOAT_BITS_CONV }`
+        let Some((line_version, aliases)) = line.split_once('{') else {
+            continue;
+        };
+        let Ok(line_version) = line_version
+            .trim()
+            .split(',')
+            .map(str::parse::<u32>)
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            continue;
+        };
+
+        if line_version == version {
+            let mut aliases: Vec<&str> = aliases
+                .trim_end_matches(|c: char| c == '}' || c.is_whitespace())
+                .split(',')
+                .map(str::trim)
+                .filter(|alias| !alias.is_empty())
+                .collect();
+            aliases.push(alias);
+            let new_aliases = format!(
+                "    {},{},{} {{ {} }}",
+                version[0],
+                version[1],
+                version[2],
+                aliases.join(", ")
+            );
+            contents.replace_range(line_range, &new_aliases);
+            new_line = None;
+            break;
+        } else if line_version.as_slice() < version.as_slice() {
+            contents.insert_str(line_range.start, &new_line.take().unwrap());
+            break;
+        }
+    }
+    if let Some(new_line) = new_line {
+        contents.insert_str(end, &new_line);
+    }
+}
+
+/// Adds the lint to the `msrv` option, and adds its configuration options to `define_Conf!`
+fn add_conf(lint: &LintData<'_>, enable_msrv: bool) -> io::Result<()> {
+    let path = lint.project_root.join("clippy_config/src/conf.rs");
+    let mut contents = fs::read_to_string(&path).context("reading")?;
+
+    if enable_msrv {
+        assert!(
+            add_lint_to_conf_option(&mut contents, "msrv", lint.name),
+            "Couldn't find the `msrv` option"
+        );
+    }
+    for option in lint.config {
+        // The lint may share an existing option
+        if !add_lint_to_conf_option(&mut contents, &option.name, lint.name) {
+            insert_conf_option(&mut contents, option, lint.name);
+        }
+    }
+
+    fs::write(path, contents).context("writing")?;
+    println!("Updated the configuration in `clippy_config/src/conf.rs`");
+    Ok(())
+}
+
+/// The range of the body of `define_Conf!`
+fn conf_body(contents: &str) -> std::ops::Range<usize> {
+    let start = contents.find("define_Conf! {\n").expect("Couldn't find `define_Conf!`") + "define_Conf! {\n".len();
+    let end = start
+        + contents[start..]
+            .find("\n}\n")
+            .expect("Couldn't find the end of `define_Conf!`")
+        + 1;
+    start..end
+}
+
+/// Adds the lint to the `#[lints(..)]` attribute of an existing option, returns `false` if there
+/// is no such option
+fn add_lint_to_conf_option(contents: &mut String, option: &str, lint: &str) -> bool {
+    let body = conf_body(contents);
+    let Some(option_start) = contents[body.clone()]
+        .find(&format!("\n    {option}: "))
+        .map(|pos| body.start + pos + 1)
+    else {
+        return false;
+    };
+
+    let attr_start = contents[..option_start]
+        .rfind("#[lints(")
+        .expect("Couldn't find the `#[lints(..)]` attribute");
+    let attr_end = attr_start + contents[attr_start..].find(")]").expect("malformed `#[lints(..)]`") + ")]".len();
+    assert!(
+        contents[attr_end..option_start]
+            .lines()
+            .all(|line| line.trim().is_empty() || line.trim().starts_with("#[")),
+        "The `{option}` option has no `#[lints(..)]` attribute"
+    );
+
+    let mut lints: Vec<&str> = contents[attr_start + "#[lints(".len()..attr_end - ")]".len()]
+        .split(',')
+        .map(str::trim)
+        .filter(|lint| !lint.is_empty())
+        .collect();
+    if !lints.contains(&lint) {
+        lints.push(lint);
+        lints.sort_unstable();
+    }
+
+    let attr = lints_attr(&lints);
+    contents.replace_range(attr_start..attr_end, &attr);
+    true
+}
+
+/// Formats the `#[lints(..)]` attribute of a configuration option, splitting it over multiple lines
+/// if it's too long
+pub(crate) fn lints_attr(lints: &[&str]) -> String {
+    let single_line = format!("#[lints({})]", lints.join(", "));
+    if single_line.len() + 4 <= 120 {
+        single_line
+    } else {
+        let mut attr = String::from("#[lints(\n");
+        for lint in lints {
+            let _: fmt::Result = writeln!(attr, "        {lint},");
+        }
+        attr.push_str("    )]");
+        attr
+    }
+}
+
+/// Inserts a new option before the first one which comes after it alphabetically
+fn insert_conf_option(contents: &mut String, option: &ConfOption, lint: &str) {
+    let body = conf_body(contents);
+    let new_option = format!(
+        "    /// TODO: Describe what the option does\n    #[lints({lint})]\n    {}: {} = {},\n",
+        option.name, option.ty, option.default
+    );
+
+    // Each option starts with its doc comment, right after the previous option
+    let mut offset = body.start;
+    let mut option_start = None;
+    let mut prev_is_doc = false;
+    for line in contents[body.clone()].lines() {
+        let is_doc = line.starts_with("    ///");
+        if is_doc && !prev_is_doc {
+            option_start = Some(offset);
+        }
+        prev_is_doc = is_doc;
+
+        if let Some(name) = line
+            .strip_prefix("    ")
+            .and_then(|line| line.split_once(": "))
+            .map(|(name, _)| name)
+            && !name.starts_with(['/', '#', ' '])
+            && name > option.name.as_str()
+        {
+            contents.insert_str(option_start.unwrap_or(offset), &new_option);
+            return;
+        }
+        offset += line.len() + 1;
+    }
+    contents.insert_str(body.end, &new_option);
+}
+
+fn create_ui_toml_test(lint: &LintData<'_>) -> io::Result<()> {
+    let relative_test_dir = format!("tests/ui-toml/{}", lint.name);
+    let test_dir = lint.project_root.join(&relative_test_dir);
+    fs::create_dir(&test_dir)?;
+
+    let mut clippy_toml = String::from("# TODO: Change the values to ones other than the defaults\n");
+    for option in lint.config {
+        let key = option.name.replace('_', "-");
+        match option.toml_default() {
+            Some(value) => {
+                let _: fmt::Result = writeln!(clippy_toml, "{key} = {value}");
+            },
+            None => {
+                let _: fmt::Result = writeln!(clippy_toml, "# {key} = (defaults to `{}`)", option.default);
+            },
+        }
+    }
+    write_file(test_dir.join("clippy.toml"), clippy_toml)?;
+
+    let test_contents = formatdoc!(
+        r"
+        #![warn(clippy::{})]
+
+        fn main() {{
+            // test code using the configuration in `clippy.toml` goes here
+        }}
+    ",
+        lint.name
+    );
+    write_file(test_dir.join(format!("{}.rs", lint.name)), test_contents)?;
+
+    println!("Generated ui-toml test: `{relative_test_dir}`");
+    Ok(())
 }
 
 fn write_file<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
@@ -205,7 +591,7 @@ pub(crate) fn get_stabilization_version() -> String {
     parse_manifest(&contents).expect("Unable to find package version in `Cargo.toml`")
 }
 
-fn get_test_file_contents(lint_name: &str, msrv: bool) -> String {
+fn get_test_file_contents(lint_name: &str, msrv: bool, msrv_version: Option<[u32; 3]>) -> String {
     let mut test = formatdoc!(
         r"
         #![warn(clippy::{lint_name})]
@@ -216,7 +602,24 @@ fn get_test_file_contents(lint_name: &str, msrv: bool) -> String {
     "
     );
 
-    if msrv {
+    if let Some([major, minor, _]) = msrv_version {
+        let below = minor.saturating_sub(1);
+        let _ = writedoc!(
+            test,
+            r#"
+
+                #[clippy::msrv = "{major}.{below}"]
+                fn msrv_{major}_{below}() {{
+                    // a simple example that would trigger the lint if the MSRV were met
+                }}
+
+                #[clippy::msrv = "{major}.{minor}"]
+                fn msrv_{major}_{minor}() {{
+                    // the same example as above
+                }}
+            "#
+        );
+    } else if msrv {
         let _ = writedoc!(
             test,
             r#"
@@ -254,6 +657,7 @@ fn get_manifest_contents(lint_name: &str, hint: &str) -> String {
     )
 }
 
+#[expect(clippy::too_many_lines)]
 fn get_lint_file_contents(lint: &LintData<'_>, enable_msrv: bool) -> String {
     let mut result = String::new();
 
@@ -270,13 +674,18 @@ fn get_lint_file_contents(lint: &LintData<'_>, enable_msrv: bool) -> String {
     let name_camel = to_camel_case(lint.name);
     let name_upper = lint_name.to_uppercase();
 
-    result.push_str(&if enable_msrv {
+    let needs_conf = enable_msrv || !lint.config.is_empty();
+
+    if enable_msrv {
+        result.push_str("use clippy_utils::msrvs::{self, Msrv};\n");
+    }
+    result.push_str(&if needs_conf {
+        let lint_context = if enable_msrv { ", LintContext" } else { "" };
         formatdoc!(
             r"
-            use clippy_utils::msrvs::{{self, Msrv}};
             use clippy_config::Conf;
             {pass_import}
-            use rustc_lint::{{{context_import}, {pass_type}, LintContext}};
+            use rustc_lint::{{{context_import}, {pass_type}{lint_context}}};
             use rustc_session::impl_lint_pass;
 
         "
@@ -294,38 +703,82 @@ fn get_lint_file_contents(lint: &LintData<'_>, enable_msrv: bool) -> String {
 
     let _: fmt::Result = writeln!(result, "{}", get_lint_declaration(&name_upper, category));
 
-    result.push_str(&if enable_msrv {
-        formatdoc!(
+    if !needs_conf {
+        result.push_str(&formatdoc!(
             r"
-            pub struct {name_camel} {{
-                msrv: Msrv,
+            declare_lint_pass!({name_camel} => [{name_upper}]);
+
+            impl {pass_type}{pass_lifetimes} for {name_camel} {{}}
+        "
+        ));
+        return result;
+    }
+
+    let mut fields = String::new();
+    let mut inits = String::new();
+    if enable_msrv {
+        fields.push_str("    msrv: Msrv,\n");
+        inits.push_str("            msrv: conf.msrv.clone(),\n");
+    }
+    for option in lint.config {
+        let _: fmt::Result = writeln!(fields, "    {}: {},", option.name, option.field_ty());
+        let _: fmt::Result = writeln!(inits, "            {}: {},", option.name, option.field_init());
+    }
+
+    let mut items = String::new();
+    if lint.msrv_version.is_some() {
+        let expr_lifetimes = if lint.pass == "late" { "<'_>" } else { "" };
+        let check_expr = formatdoc!(
+            r"
+            fn check_expr(&mut self, cx: &{context_import}<'_>, expr: &Expr{expr_lifetimes}) {{
+                if !self.msrv.meets(msrvs::{name_upper}) {{
+                    return;
+                }}
+                todo!();
             }}
 
+        "
+        );
+        for line in check_expr.lines() {
+            if line.is_empty() {
+                items.push('\n');
+            } else {
+                let _: fmt::Result = writeln!(items, "    {line}");
+            }
+        }
+    }
+    if enable_msrv {
+        let _: fmt::Result = writeln!(items, "    extract_msrv_attr!({context_import});");
+    }
+
+    let impl_body = if items.is_empty() {
+        String::from("{}")
+    } else {
+        format!("{{\n{items}}}")
+    };
+
+    let _: fmt::Result = writedoc!(
+        result,
+        r"
+            pub struct {name_camel} {{
+            {fields}}}
+
             impl {name_camel} {{
                 pub fn new(conf: &'static Conf) -> Self {{
-                    Self {{ msrv: conf.msrv.clone() }}
+                    Self {{
+            {inits}        }}
                 }}
             }}
 
             impl_lint_pass!({name_camel} => [{name_upper}]);
 
-            impl {pass_type}{pass_lifetimes} for {name_camel} {{
-                extract_msrv_attr!({context_import});
-            }}
-
-            // TODO: Add MSRV level to `clippy_config/src/msrvs.rs` if needed.
-            // TODO: Update msrv config comment in `clippy_config/src/conf.rs`
+            impl {pass_type}{pass_lifetimes} for {name_camel} {impl_body}
         "
-        )
-    } else {
-        formatdoc!(
-            r"
-            declare_lint_pass!({name_camel} => [{name_upper}]);
+    );
 
-            impl {pass_type}{pass_lifetimes} for {name_camel} {{}}
-        "
-        )
-    });
+    if enable_msrv && lint.msrv_version.is_none() {
+        result.push_str("\n// TODO: Add MSRV level to `clippy_utils/src/msrvs.rs` if needed.\n");
+    }
 
     result
 }
@@ -395,23 +848,36 @@ fn create_lint_for_ty(lint: &LintData<'_>, enable_msrv: bool, ty: &str) -> io::R
     let name_upper = lint.name.to_uppercase();
     let mut lint_file_contents = String::new();
 
+    let mut params = format!("cx: &{context_import}{pass_lifetimes}");
     if enable_msrv {
+        params.push_str(", msrv: &Msrv");
+    }
+    for option in lint.config {
+        let _: fmt::Result = write!(params, ", {}: {}", option.name, option.param_ty());
+    }
+
+    if enable_msrv {
+        let msrv_alias = if lint.msrv_version.is_some() {
+            format!("msrvs::{name_upper}")
+        } else {
+            String::from("todo!(\"Add a new entry in `clippy_utils/src/msrvs`\")")
+        };
         let _: fmt::Result = writedoc!(
             lint_file_contents,
-            r#"
+            r"
                 use clippy_utils::msrvs::{{self, Msrv}};
                 use rustc_lint::{{{context_import}, LintContext}};
 
                 use super::{name_upper};
 
                 // TODO: Adjust the parameters as necessary
-                pub(super) fn check(cx: &{context_import}{pass_lifetimes}, msrv: &Msrv) {{
-                    if !msrv.meets(todo!("Add a new entry in `clippy_utils/src/msrvs`")) {{
+                pub(super) fn check({params}) {{
+                    if !msrv.meets({msrv_alias}) {{
                         return;
                     }}
                     todo!();
                 }}
-           "#
+           "
         );
     } else {
         let _: fmt::Result = writedoc!(
@@ -422,7 +888,7 @@ fn create_lint_for_ty(lint: &LintData<'_>, enable_msrv: bool, ty: &str) -> io::R
                 use super::{name_upper};
 
                 // TODO: Adjust the parameters as necessary
-                pub(super) fn check(cx: &{context_import}{pass_lifetimes}) {{
+                pub(super) fn check({params}) {{
                     todo!();
                 }}
            "
@@ -435,6 +901,9 @@ fn create_lint_for_ty(lint: &LintData<'_>, enable_msrv: bool, ty: &str) -> io::R
         "Be sure to add a call to `{}::check` in `clippy_lints/src/{ty}/mod.rs`!",
         lint.name
     );
+    if !lint.config.is_empty() {
+        println!("The configuration needs to be added to the lint pass in `clippy_lints/src/{ty}/mod.rs`");
+    }
 
     Ok(())
 }
@@ -594,3 +1063,131 @@ fn test_camel_case() {
     let name4 = to_camel_case(name3);
     assert_eq!(name4, "LintName");
 }
+
+#[test]
+fn test_register_pass() {
+    let lib_rs = "    store.register_late_pass(|_| Box::new(old::Old));\n    // add lints here, do not remove this comment, it's used in `new_lint`\n";
+    let lint = LintData {
+        pass: "late",
+        name: "new_lint",
+        category: "style",
+        ty: None,
+        project_root: PathBuf::new(),
+        msrv_version: None,
+        config: &[],
+    };
+
+    let mut contents = lib_rs.to_string();
+    register_pass(&mut contents, &lint, false);
+    assert_eq!(
+        contents,
+        "    store.register_late_pass(|_| Box::new(old::Old));\n    \
+         store.register_late_mod_pass(|_| Box::new(new_lint::NewLint));\n    \
+         // add lints here, do not remove this comment, it's used in `new_lint`\n"
+    );
+
+    let mut contents = lib_rs.to_string();
+    register_pass(&mut contents, &lint, true);
+    assert_eq!(
+        contents,
+        "    store.register_late_pass(|_| Box::new(old::Old));\n    \
+         store.register_late_mod_pass(move |_| Box::new(new_lint::NewLint::new(conf)));\n    \
+         // add lints here, do not remove this comment, it's used in `new_lint`\n"
+    );
+
+    let mut contents = lib_rs.to_string();
+    register_pass(&mut contents, &LintData { pass: "early", ..lint }, true);
+    assert_eq!(
+        contents,
+        "    store.register_late_pass(|_| Box::new(old::Old));\n    \
+         store.register_early_pass(move || Box::new(new_lint::NewLint::new(conf)));\n    \
+         // add lints here, do not remove this comment, it's used in `new_lint`\n"
+    );
+}
+
+#[test]
+fn test_insert_msrv_alias() {
+    let msrvs = "msrv_aliases! {\n    1,83,0 { CONST_EXTERN_FN }\n    1,80,0 { BOX_INTO_ITER }\n}\n";
+
+    let mut contents = msrvs.to_string();
+    insert_msrv_alias(&mut contents, "NEW_LINT", [1, 83, 0]);
+    assert_eq!(
+        contents,
+        "msrv_aliases! {\n    1,83,0 { CONST_EXTERN_FN, NEW_LINT }\n    1,80,0 { BOX_INTO_ITER }\n}\n"
+    );
+
+    let mut contents = msrvs.to_string();
+    insert_msrv_alias(&mut contents, "NEW_LINT", [1, 81, 0]);
+    assert_eq!(
+        contents,
+        "msrv_aliases! {\n    1,83,0 { CONST_EXTERN_FN }\n    1,81,0 { NEW_LINT }\n    1,80,0 { BOX_INTO_ITER }\n}\n"
+    );
+
+    let mut contents = msrvs.to_string();
+    insert_msrv_alias(&mut contents, "NEW_LINT", [1, 70, 0]);
+    assert_eq!(
+        contents,
+        "msrv_aliases! {\n    1,83,0 { CONST_EXTERN_FN }\n    1,80,0 { BOX_INTO_ITER }\n    1,70,0 { NEW_LINT }\n}\n"
+    );
+}
+
+#[test]
+fn test_conf_options() {
+    let conf = "\
+define_Conf! {
+    /// Whether to allow foo
+    #[lints(foo)]
+    allow_foo: bool = false,
+    /// The minimum rust version
+    #[lints(
+        a_lint,
+        b_lint,
+    )]
+    msrv: Msrv = Msrv::empty(),
+}
+";
+
+    let mut contents = conf.to_string();
+    assert!(add_lint_to_conf_option(&mut contents, "allow_foo", "bar"));
+    assert!(add_lint_to_conf_option(&mut contents, "msrv", "new_lint"));
+    assert!(!add_lint_to_conf_option(&mut contents, "max_depth", "new_lint"));
+    assert_eq!(
+        contents,
+        "\
+define_Conf! {
+    /// Whether to allow foo
+    #[lints(bar, foo)]
+    allow_foo: bool = false,
+    /// The minimum rust version
+    #[lints(a_lint, b_lint, new_lint)]
+    msrv: Msrv = Msrv::empty(),
+}
+"
+    );
+
+    for option in ["max_depth:u64:3", "zzz:bool:true", "aaa:bool:false"] {
+        insert_conf_option(&mut contents, &option.parse().unwrap(), "new_lint");
+    }
+    assert_eq!(
+        contents,
+        "\
+define_Conf! {
+    /// TODO: Describe what the option does
+    #[lints(new_lint)]
+    aaa: bool = false,
+    /// Whether to allow foo
+    #[lints(bar, foo)]
+    allow_foo: bool = false,
+    /// TODO: Describe what the option does
+    #[lints(new_lint)]
+    max_depth: u64 = 3,
+    /// The minimum rust version
+    #[lints(a_lint, b_lint, new_lint)]
+    msrv: Msrv = Msrv::empty(),
+    /// TODO: Describe what the option does
+    #[lints(new_lint)]
+    zzz: bool = true,
+}
+"
+    );
+}
@@ -187,43 +187,13 @@ pub fn rename(old_name: &str, new_name: &str, uplift: bool) {
     );
 
     // Update all lint level attributes. (`clippy::lint_name`)
-    for file in WalkDir::new(clippy_project_root())
-        .into_iter()
-        .map(Result::unwrap)
-        .filter(|f| {
-            let name = f.path().file_name();
-            let ext = f.path().extension();
-            (ext == Some(OsStr::new("rs")) || ext == Some(OsStr::new("fixed")))
-                && name != Some(OsStr::new("rename.rs"))
-                && name != Some(OsStr::new("deprecated_lints.rs"))
-        })
-    {
+    for file in lint_reference_files() {
         rewrite_file(file.path(), |s| {
             replace_ident_like(s, &[(&lint.old_name, &lint.new_name)])
         });
     }
 
-    let version = crate::new_lint::get_stabilization_version();
-    rewrite_file(Path::new("clippy_lints/src/deprecated_lints.rs"), |s| {
-        insert_at_marker(
-            s,
-            "// end renamed lints. used by `cargo dev rename_lint`",
-            &format!(
-                "#[clippy::version = \"{version}\"]\n    \
-                (\"{}\", \"{}\"),\n    ",
-                lint.old_name, lint.new_name,
-            ),
-        )
-    });
-
-    renamed_lints.push(lint);
-    renamed_lints.sort_by(|lhs, rhs| {
-        lhs.new_name
-            .starts_with("clippy::")
-            .cmp(&rhs.new_name.starts_with("clippy::"))
-            .reverse()
-            .then_with(|| lhs.old_name.cmp(&rhs.old_name))
-    });
+    add_renamed_lint(lint, &mut renamed_lints);
 
     if uplift {
         write_file(Path::new("tests/ui/rename.rs"), &gen_renamed_lints_test(&renamed_lints));
@@ -307,7 +277,11 @@ pub fn rename(old_name: &str, new_name: &str, uplift: bool) {
 ///
 /// This does the following:
 /// * Adds an entry to `deprecated_lints.rs`.
-/// * Removes the lint declaration (and the entire file if applicable)
+/// * Removes the lint declaration (and the entire file if applicable), its tests and the
+///   registration of its lint pass if it has no other lints
+/// * Removes the lint from the lint attributes and the test annotations
+/// * Removes the lint from the configuration options
+/// * Adds an entry to the CHANGELOG
 ///
 /// # Panics
 ///
@@ -326,19 +300,11 @@ pub fn deprecate(name: &str, reason: &str) {
         return;
     };
 
-    let mod_path = {
-        let mut mod_path = PathBuf::from(format!("clippy_lints/src/{}", lint.module));
-        if mod_path.is_dir() {
-            mod_path = mod_path.join("mod");
-        }
-
-        mod_path.set_extension("rs");
-        mod_path
-    };
+    let mod_path = lint_mod_path(lint);
 
     let deprecated_lints_path = &*clippy_project_root().join("clippy_lints/src/deprecated_lints.rs");
 
-    if remove_lint_declaration(stripped_name, &mod_path, &mut lints).unwrap_or(false) {
+    if remove_lint_declaration(stripped_name, &mod_path, &mut lints, true).unwrap_or(false) {
         let version = crate::new_lint::get_stabilization_version();
         rewrite_file(deprecated_lints_path, |s| {
             insert_at_marker(
@@ -348,6 +314,12 @@ pub fn deprecate(name: &str, reason: &str) {
             )
         });
 
+        for file in lint_reference_files() {
+            rewrite_file(file.path(), |s| remove_lint_references(s, stripped_name));
+        }
+        update_conf_lints(stripped_name, None);
+        add_changelog_entry(&format!("Deprecated [`{stripped_name}`]: {reason}"));
+
         deprecated_lints.push(DeprecatedLint {
             name: prefixed_name,
             reason: reason.into(),
@@ -361,7 +333,356 @@ pub fn deprecate(name: &str, reason: &str) {
     }
 }
 
-fn remove_lint_declaration(name: &str, path: &Path, lints: &mut Vec<Lint>) -> io::Result<bool> {
+/// Runs the `merge_lint` command
+///
+/// This does the following:
+/// * Adds an entry to the renamed lints in `deprecated_lints.rs`, `old_name` becomes an alias of
+///   `into`.
+/// * Replaces `clippy::old_name` with `clippy::into` in the code and the test annotations
+/// * Removes the lint declaration of `old_name` (and the entire file if applicable), and the
+///   registration of its lint pass if it has no other lints
+/// * Replaces `old_name` with `into` in the configuration options
+/// * Adds an entry to the CHANGELOG
+///
+/// The tests of `old_name` are kept, as they now test `into`.
+///
+/// # Panics
+/// Panics for the following conditions:
+/// * If a file path could not read from or then written to
+/// * If either lint name has a prefix
+/// * If either lint doesn't name an existing lint
+pub fn merge(old_name: &str, into: &str) {
+    for name in [old_name, into] {
+        if let Some((prefix, _)) = name.split_once("::") {
+            panic!("`{name}` should not contain the `{prefix}` prefix");
+        }
+    }
+    assert_ne!(old_name, into, "a lint can't be merged into itself");
+
+    let (mut lints, deprecated_lints, mut renamed_lints) = gather_all();
+    let old_lint = lints
+        .iter()
+        .find(|l| l.name == old_name)
+        .unwrap_or_else(|| panic!("could not find lint `{old_name}`"));
+    assert!(lints.iter().any(|l| l.name == into), "could not find lint `{into}`");
+    let mod_path = lint_mod_path(old_lint);
+
+    let lint = RenamedLint {
+        old_name: format!("clippy::{old_name}"),
+        new_name: format!("clippy::{into}"),
+    };
+
+    // Update the lint level attributes, and the notes in the test annotations which use the
+    // `clippy::lint-name` form
+    let old_kebab = format!("clippy::{}", old_name.replace('_', "-"));
+    let into_kebab = format!("clippy::{}", into.replace('_', "-"));
+    for file in lint_reference_files() {
+        rewrite_file(file.path(), |s| {
+            replace_ident_like(s, &[(&lint.old_name, &lint.new_name), (&old_kebab, &into_kebab)])
+        });
+    }
+
+    assert!(
+        remove_lint_declaration(old_name, &mod_path, &mut lints, false).unwrap_or(false),
+        "failed to remove the declaration of `{old_name}`"
+    );
+    update_conf_lints(old_name, Some(into));
+    add_changelog_entry(&format!("Merged `{old_name}` into [`{into}`]"));
+    add_renamed_lint(lint, &mut renamed_lints);
+
+    generate_lint_files(UpdateMode::Change, &lints, &deprecated_lints, &renamed_lints);
+    println!("info: `{old_name}` has successfully been merged into `{into}`");
+    println!("note: `cargo uibless` still needs to be run to update the test results");
+}
+
+/// The file containing the lint declaration
+fn lint_mod_path(lint: &Lint) -> PathBuf {
+    let mut mod_path = PathBuf::from(format!("clippy_lints/src/{}", lint.module));
+    if mod_path.is_dir() {
+        mod_path = mod_path.join("mod");
+    }
+
+    mod_path.set_extension("rs");
+    mod_path
+}
+
+/// The name of each lint, with the file it's implemented in
+pub(crate) fn lint_sources() -> Vec<(String, PathBuf)> {
+    let (lints, _, _) = gather_all();
+    lints
+        .into_iter()
+        .map(|lint| {
+            let mod_path = lint_mod_path(&lint);
+            // Lints declared in a `mod.rs` are usually implemented in their own file
+            let lint_path = mod_path.with_file_name(format!("{}.rs", lint.name));
+            let path = if mod_path.ends_with("mod.rs") && lint_path.exists() {
+                lint_path
+            } else {
+                mod_path
+            };
+            (lint.name, path)
+        })
+        .collect()
+}
+
+/// The files which may refer to lints by their name, e.g. in lint level attributes
+fn lint_reference_files() -> impl Iterator<Item = DirEntry> {
+    WalkDir::new(clippy_project_root())
+        .into_iter()
+        .map(Result::unwrap)
+        .filter(|f| {
+            let name = f.path().file_name();
+            let ext = f.path().extension();
+            (ext == Some(OsStr::new("rs")) || ext == Some(OsStr::new("fixed")))
+                && name != Some(OsStr::new("rename.rs"))
+                && name != Some(OsStr::new("deprecated.rs"))
+                && name != Some(OsStr::new("deprecated_lints.rs"))
+        })
+}
+
+/// Adds the renamed lint to `deprecated_lints.rs`, and to the list used to generate
+/// `tests/ui/rename.rs`
+fn add_renamed_lint(lint: RenamedLint, renamed_lints: &mut Vec<RenamedLint>) {
+    let version = crate::new_lint::get_stabilization_version();
+    rewrite_file(Path::new("clippy_lints/src/deprecated_lints.rs"), |s| {
+        insert_at_marker(
+            s,
+            "// end renamed lints. used by `cargo dev rename_lint`",
+            &format!(
+                "#[clippy::version = \"{version}\"]\n    \
+                (\"{}\", \"{}\"),\n    ",
+                lint.old_name, lint.new_name,
+            ),
+        )
+    });
+
+    renamed_lints.push(lint);
+    renamed_lints.sort_by(|lhs, rhs| {
+        lhs.new_name
+            .starts_with("clippy::")
+            .cmp(&rhs.new_name.starts_with("clippy::"))
+            .reverse()
+            .then_with(|| lhs.old_name.cmp(&rhs.old_name))
+    });
+}
+
+/// Removes the lint from the `#[lints(..)]` attributes of the configuration options, or replaces
+/// it with `replacement`
+fn update_conf_lints(name: &str, replacement: Option<&str>) {
+    rewrite_file(Path::new("clippy_config/src/conf.rs"), |contents| {
+        let mut contents = contents.to_owned();
+        let mut edited = false;
+        let mut search_from = 0;
+        while let Some(attr_start) = contents[search_from..].find("#[lints(").map(|pos| search_from + pos) {
+            let attr_end = attr_start + contents[attr_start..].find(")]")? + ")]".len();
+            search_from = attr_end;
+
+            let mut lints: Vec<&str> = contents[attr_start + "#[lints(".len()..attr_end - ")]".len()]
+                .split(',')
+                .map(str::trim)
+                .filter(|lint| !lint.is_empty())
+                .collect();
+            if !lints.contains(&name) {
+                continue;
+            }
+            lints.retain(|&lint| lint != name);
+            if let Some(replacement) = replacement
+                && !lints.contains(&replacement)
+            {
+                lints.push(replacement);
+                lints.sort_unstable();
+            }
+            if lints.is_empty() {
+                let option = contents[attr_end..].trim_start().split(':').next().unwrap_or_default();
+                eprintln!(
+                    "warn: the `{option}` configuration option isn't used by any lint anymore, it has to be removed manually"
+                );
+            }
+
+            let attr = crate::new_lint::lints_attr(&lints);
+            contents.replace_range(attr_start..attr_end, &attr);
+            search_from = attr_start + attr.len();
+            edited = true;
+        }
+        edited.then_some(contents)
+    });
+}
+
+/// Adds an entry to the `Moves and Deprecations` of the unreleased changes in the CHANGELOG
+fn add_changelog_entry(entry: &str) {
+    const HEADING: &str = "### Moves and Deprecations\n";
+    rewrite_file(Path::new("CHANGELOG.md"), |s| {
+        let start = s.find("## Unreleased")?;
+        let end = start + s[start..].find("\n## ")? + 1;
+        let mut s = s.to_owned();
+        if let Some(pos) = s[start..end].find(HEADING) {
+            let section_start = start + pos + HEADING.len();
+            let section_end = s[section_start..end]
+                .find("\n### ")
+                .map_or(end, |pos| section_start + pos);
+            let insert_at = section_start + s[section_start..section_end].trim_end().len();
+            s.insert_str(insert_at, &format!("\n* {entry}"));
+        } else {
+            s.insert_str(end, &format!("{HEADING}\n* {entry}\n\n"));
+        }
+        Some(s)
+    });
+}
+
+/// Removes the lint from the lint level attributes, and removes the test annotations mentioning it.
+/// Returns `None` if the lint isn't mentioned.
+fn remove_lint_references(contents: &str, name: &str) -> Option<String> {
+    let lint = format!("clippy::{name}");
+    let kebab = format!("clippy::{}", name.replace('_', "-"));
+    let mentions = |line: &str| find_ident_like(line, &lint).is_some() || find_ident_like(line, &kebab).is_some();
+
+    let mut result = String::with_capacity(contents.len());
+    let mut edited = false;
+    let mut lines = contents.split_inclusive('\n').peekable();
+    while let Some(line) = lines.next() {
+        // An annotation, followed by its `//~|` continuations
+        if let Some(pos) = line.find("//~")
+            && !line[pos..].starts_with("//~|")
+        {
+            let mut annotation = vec![line];
+            while let Some(next) = lines.next_if(|next| next.trim_start().starts_with("//~|")) {
+                annotation.push(next);
+            }
+            if annotation.iter().any(|line| mentions(line)) {
+                edited = true;
+                // Keep the code the annotation is on
+                let code = line[..pos].trim_end();
+                if !code.is_empty() {
+                    result.push_str(code);
+                    result.push('\n');
+                }
+            } else {
+                result.extend(annotation);
+            }
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        if (trimmed.starts_with("#[") || trimmed.starts_with("#![") || trimmed.starts_with("clippy::"))
+            && let Some(pos) = find_ident_like(line, &lint)
+        {
+            edited = true;
+            if let Some(line) = remove_from_attr(line, pos, &lint) {
+                result.push_str(&line);
+            }
+            continue;
+        }
+
+        result.push_str(line);
+    }
+    edited.then_some(result)
+}
+
+/// Removes the lint at `pos` from the attribute on the line, returns `None` if the line should be
+/// removed
+fn remove_from_attr(line: &str, pos: usize, lint_path: &str) -> Option<String> {
+    // An item of a multi-line attribute
+    if matches!(line.trim().strip_prefix(lint_path), Some("" | ",")) {
+        return None;
+    }
+
+    let (Some(open), Some(close)) = (line[..pos].rfind('('), line[pos..].find(')').map(|close| pos + close)) else {
+        return Some(line.to_owned());
+    };
+    let items: Vec<&str> = line[open + 1..close]
+        .split(',')
+        .map(str::trim)
+        .filter(|item| *item != lint_path)
+        .collect();
+
+    // Only keep the attribute if other lints remain, not just e.g. a `reason`
+    if items
+        .iter()
+        .any(|item| !item.is_empty() && item.chars().all(|c| c.is_alphanumeric() || c == '_' || c == ':'))
+    {
+        return Some(format!("{}{}{}", &line[..=open], items.join(", "), &line[close..]));
+    }
+
+    let attr_start = line[..open].rfind('#').unwrap_or(0);
+    let attr_end = line[close..].find(']').map_or(line.len(), |end| close + end + 1);
+    let rest = format!("{}{}", &line[..attr_start], line[attr_end..].trim_start());
+    (!rest.trim().is_empty()).then_some(rest)
+}
+
+/// Finds `needle` if it isn't preceded by an identifier character, nor followed by one if it ends
+/// with one
+fn find_ident_like(haystack: &str, needle: &str) -> Option<usize> {
+    let ends_with_ident = needle.bytes().last().is_some_and(is_ident_char);
+    let byte_at = |pos: usize| haystack.as_bytes().get(pos).copied().unwrap_or(0);
+    haystack.match_indices(needle).map(|(pos, _)| pos).find(|&pos| {
+        let ident_before = is_ident_char(byte_at(pos.wrapping_sub(1)));
+        let ident_after = ends_with_ident && is_ident_char(byte_at(pos + needle.len()));
+        !ident_before && !ident_after
+    })
+}
+
+/// Returns the end of the statement starting at `start`, after its `;`
+fn statement_end(text: &str, start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text[start..].char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.checked_sub(1)?,
+            ';' if depth == 0 => return Some(start + i + 1),
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Removes the statements which start with `start` and contain `needle`, e.g. the registration of a
+/// lint pass. Only the statements on their own lines are removed.
+fn remove_statements(text: &mut String, start: &str, needle: &str) {
+    let mut search_from = 0;
+    while let Some(pos) = find_ident_like(&text[search_from..], needle).map(|pos| search_from + pos) {
+        let stmt_start = if needle.starts_with(start) {
+            Some(pos)
+        } else {
+            text[..pos]
+                .rfind(start)
+                .filter(|&stmt_start| !text[stmt_start..pos].contains(';'))
+        };
+        if let Some(stmt_start) = stmt_start
+            && let line_start = text[..stmt_start].rfind('\n').map_or(0, |i| i + 1)
+            && text[line_start..stmt_start].trim().is_empty()
+            && let Some(end) = statement_end(text, stmt_start)
+            && text[end..].starts_with('\n')
+        {
+            text.replace_range(line_start..=end, "");
+            search_from = line_start;
+        } else {
+            search_from = pos + needle.len();
+        }
+    }
+}
+
+/// Removes the registrations of the lint pass, or of all the lint passes of the module if `pass` is
+/// `None`
+fn remove_pass_registrations(module: &str, pass: Option<&str>) {
+    let path = Path::new("clippy_lints/src/lib.rs");
+    let needle = match pass {
+        Some(pass) => format!("{module}::{pass}"),
+        None => format!("{module}::"),
+    };
+    rewrite_file(path, |s| {
+        let mut s = s.to_owned();
+        remove_statements(&mut s, "store.register_", &needle);
+        if find_ident_like(&s, &needle).is_some() {
+            eprintln!(
+                "warn: you will have to manually remove the remaining uses of `{needle}` from `{}`",
+                path.display()
+            );
+        }
+        Some(s)
+    });
+}
+
+fn remove_lint_declaration(name: &str, path: &Path, lints: &mut Vec<Lint>, remove_tests: bool) -> io::Result<bool> {
     fn remove_lint(name: &str, lints: &mut Vec<Lint>) {
         lints.iter().position(|l| l.name == name).map(|pos| lints.remove(pos));
     }
@@ -380,20 +701,24 @@ fn remove_lint_declaration(name: &str, path: &Path, lints: &mut Vec<Lint>) -> io
         let _ = fs::remove_file(path.with_extension("rs"));
         let _ = fs::remove_file(path.with_extension("stderr"));
         let _ = fs::remove_file(path.with_extension("fixed"));
-    }
 
-    fn remove_impl_lint_pass(lint_name_upper: &str, content: &mut String) {
-        let impl_lint_pass_start = content.find("impl_lint_pass!").unwrap_or_else(|| {
-            content
-                .find("declare_lint_pass!")
-                .unwrap_or_else(|| panic!("failed to find `impl_lint_pass`"))
-        });
-        let mut impl_lint_pass_end = content[impl_lint_pass_start..]
-            .find(']')
-            .expect("failed to find `impl_lint_pass` terminator");
+        let _ = fs::remove_dir_all(format!("tests/ui-toml/{name}"));
+    }
 
-        impl_lint_pass_end += impl_lint_pass_start;
-        if let Some(lint_name_pos) = content[impl_lint_pass_start..impl_lint_pass_end].find(lint_name_upper) {
+    /// Removes the lint from its `impl_lint_pass!`/`declare_lint_pass!`, returns the name of the
+    /// pass if it has no other lints
+    fn remove_impl_lint_pass(lint_name_upper: &str, content: &mut String) -> Option<String> {
+        // The file may contain several passes
+        let (impl_lint_pass_start, impl_lint_pass_end) = content
+            .match_indices("impl_lint_pass!")
+            .chain(content.match_indices("declare_lint_pass!"))
+            .filter_map(|(start, _)| Some((start, start + content[start..].find(']')?)))
+            .find(|&(start, end)| find_ident_like(&content[start..end], lint_name_upper).is_some())
+            .unwrap_or_else(|| panic!("failed to find the `impl_lint_pass` of `{lint_name_upper}`"));
+
+        if let Some(lint_name_pos) =
+            find_ident_like(&content[impl_lint_pass_start..impl_lint_pass_end], lint_name_upper)
+        {
             let mut lint_name_end = impl_lint_pass_start + (lint_name_pos + lint_name_upper.len());
             for c in content[lint_name_end..impl_lint_pass_end].chars() {
                 // Remove trailing whitespace
@@ -406,6 +731,12 @@ fn remove_lint_declaration(name: &str, path: &Path, lints: &mut Vec<Lint>) -> io
 
             content.replace_range(impl_lint_pass_start + lint_name_pos..lint_name_end, "");
         }
+
+        // `impl_lint_pass!(Pass<'tcx> => [])`
+        let pass_macro = &content[impl_lint_pass_start..];
+        let (pass, lints) = pass_macro[pass_macro.find('(')? + 1..pass_macro.find(']')?].split_once("=>")?;
+        let pass = pass.split('<').next()?.trim();
+        (lints.trim() == "[").then(|| pass.to_owned())
     }
 
     if path.exists() {
@@ -413,6 +744,7 @@ fn remove_lint_declaration(name: &str, path: &Path, lints: &mut Vec<Lint>) -> io
             if lint.module == name {
                 // The lint name is the same as the file, we can just delete the entire file
                 fs::remove_file(path)?;
+                remove_pass_registrations(name, None);
             } else {
                 // We can't delete the entire file, just remove the declaration
 
@@ -443,15 +775,25 @@ fn remove_lint_declaration(name: &str, path: &Path, lints: &mut Vec<Lint>) -> io
                 // Remove lint declaration (declare_clippy_lint!)
                 content.replace_range(lint.declaration_range.clone(), "");
 
-                // Remove the module declaration (mod xyz;)
+                // Remove the module declaration (mod xyz;), and the calls to its functions
                 let mod_decl = format!("\nmod {name};");
                 content = content.replacen(&mod_decl, "", 1);
-
-                remove_impl_lint_pass(&lint.name.to_uppercase(), &mut content);
+                let mod_path = format!("{name}::");
+                remove_statements(&mut content, &mod_path, &mod_path);
+
+                if let Some(pass) = remove_impl_lint_pass(&lint.name.to_uppercase(), &mut content) {
+                    eprintln!(
+                        "warn: the `{pass}` lint pass has no lints anymore, its registration has been removed but \
+                        the pass has to be removed manually"
+                    );
+                    remove_pass_registrations(&lint.module, Some(&pass));
+                }
                 fs::write(path, content).unwrap_or_else(|_| panic!("failed to write to `{}`", path.to_string_lossy()));
             }
 
-            remove_test_assets(name);
+            if remove_tests {
+                remove_test_assets(name);
+            }
             remove_lint(name, lints);
             return Ok(true);
         }
@@ -463,10 +805,6 @@ fn remove_lint_declaration(name: &str, path: &Path, lints: &mut Vec<Lint>) -> io
 /// Replace substrings if they aren't bordered by identifier characters. Returns `None` if there
 /// were no replacements.
 fn replace_ident_like(contents: &str, replacements: &[(&str, &str)]) -> Option<String> {
-    fn is_ident_char(c: u8) -> bool {
-        matches!(c, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_')
-    }
-
     let searcher = AhoCorasickBuilder::new()
         .match_kind(aho_corasick::MatchKind::LeftmostLongest)
         .build(replacements.iter().map(|&(x, _)| x.as_bytes()))
@@ -494,6 +832,11 @@ fn replace_ident_like(contents: &str, replacements: &[(&str, &str)]) -> Option<S
     edited.then_some(result)
 }
 
+/// Includes `-` for the `clippy::lint-name` form of the lint names
+fn is_ident_char(c: u8) -> bool {
+    matches!(c, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'-')
+}
+
 fn round_to_fifty(count: usize) -> usize {
     count / 50 * 50
 }
@@ -1067,4 +1410,73 @@ mod tests {
         )]);
         assert_eq!(expected, Lint::by_lint_group(lints.into_iter()));
     }
+
+    #[test]
+    fn test_remove_lint_references() {
+        static CONTENTS: &str = r#"#![warn(clippy::foo_bar, clippy::baz)]
+#![allow(
+    clippy::foo_bar,
+    clippy::foo_bar_baz
+)]
+
+#[allow(clippy::foo_bar)] fn f() {}
+#[expect(clippy::foo_bar, reason = "a, b")]
+fn g() {
+    let _ = 1; //~ ERROR: foo
+    //~| NOTE: `-D clippy::foo-bar` implied by `-D warnings`
+    let _ = 2;
+    //~^ ERROR: bar
+    //~| NOTE: `-D clippy::foo-bar-baz` implied by `-D warnings`
+}
+"#;
+        static EXPECTED: &str = r"#![warn(clippy::baz)]
+#![allow(
+    clippy::foo_bar_baz
+)]
+
+fn f() {}
+fn g() {
+    let _ = 1;
+    let _ = 2;
+    //~^ ERROR: bar
+    //~| NOTE: `-D clippy::foo-bar-baz` implied by `-D warnings`
+}
+";
+        assert_eq!(remove_lint_references(CONTENTS, "foo_bar").as_deref(), Some(EXPECTED));
+        assert_eq!(remove_lint_references(CONTENTS, "qux"), None);
+    }
+
+    #[test]
+    fn test_remove_statements() {
+        let mut contents = String::from(
+            "    store.register_late_pass(|_| Box::new(foo::Foo));
+    store.register_late_pass(move |_| {
+        Box::new(foo_bar::FooBar::new(conf))
+    });
+    store.register_late_pass(|_| Box::new(foo::Bar));
+    foo::check(cx, (a, b));
+",
+        );
+        remove_statements(&mut contents, "store.register_", "foo::Foo");
+        assert_eq!(
+            contents,
+            "    store.register_late_pass(move |_| {
+        Box::new(foo_bar::FooBar::new(conf))
+    });
+    store.register_late_pass(|_| Box::new(foo::Bar));
+    foo::check(cx, (a, b));
+"
+        );
+        remove_statements(&mut contents, "foo::", "foo::");
+        assert_eq!(
+            contents,
+            "    store.register_late_pass(move |_| {
+        Box::new(foo_bar::FooBar::new(conf))
+    });
+    store.register_late_pass(|_| Box::new(foo::Bar));
+"
+        );
+        remove_statements(&mut contents, "store.register_", "foo_bar::");
+        assert_eq!(contents, "    store.register_late_pass(|_| Box::new(foo::Bar));\n");
+    }
 }
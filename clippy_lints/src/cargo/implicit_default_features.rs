@@ -0,0 +1,162 @@
+use cargo_metadata::{DependencyKind, Metadata, Package, PackageId, Resolve};
+use clippy_utils::diagnostics::span_lint_and_then;
+use itertools::Itertools;
+use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
+use rustc_lint::LateContext;
+use rustc_span::DUMMY_SP;
+
+use super::{IMPLICIT_DEFAULT_FEATURES, current_package};
+
+pub(super) fn check(cx: &LateContext<'_>, metadata: &Metadata) {
+    let Some(package) = current_package(metadata) else {
+        return;
+    };
+    let Some(resolve) = &metadata.resolve else {
+        return;
+    };
+    let Some(node) = resolve.nodes.iter().find(|node| node.id == package.id) else {
+        return;
+    };
+
+    for dep in package.dependencies.iter().filter(|dep| dep.uses_default_features) {
+        let Some(dep_package) = node
+            .deps
+            .iter()
+            .filter(|node_dep| node_dep.dep_kinds.iter().any(|info| info.kind == dep.kind))
+            .map(|node_dep| &metadata[&node_dep.pkg])
+            .find(|dep_package| dep_package.name == dep.name)
+        else {
+            continue;
+        };
+
+        let Some(dep_node) = resolve.nodes.iter().find(|node| node.id == dep_package.id) else {
+            continue;
+        };
+
+        let by_default = enabled_dependencies(dep_package, ["default"]);
+        let explicitly = enabled_dependencies(dep_package, dep.features.iter().map(String::as_str));
+
+        let mut optional_deps: Vec<&Package> = dep_package
+            .dependencies
+            .iter()
+            .filter(|optional| {
+                let key = optional.rename.as_ref().unwrap_or(&optional.name);
+                optional.optional
+                    && optional.kind == DependencyKind::Normal
+                    && by_default.contains(key.as_str())
+                    && !explicitly.contains(key.as_str())
+            })
+            // Only the dependencies in the resolve graph are compiled, e.g. not ones for other platforms
+            .filter_map(|optional| {
+                dep_node
+                    .deps
+                    .iter()
+                    .map(|node_dep| &metadata[&node_dep.pkg])
+                    .find(|resolved| resolved.name == optional.name)
+            })
+            .collect();
+        if optional_deps.is_empty() {
+            continue;
+        }
+        optional_deps.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        optional_deps.dedup_by_key(|package| &package.id);
+
+        // The crates compiled only because of the optional dependencies: the ones reachable from them,
+        // but not from the package once the edges to them are removed
+        let roots: FxHashSet<&PackageId> = optional_deps.iter().map(|package| &package.id).collect();
+        let without = reachable(resolve, &package.id, |from, to| {
+            from == &dep_package.id && roots.contains(to)
+        });
+        let mut transitive: Vec<&Package> = optional_deps
+            .iter()
+            .flat_map(|root| reachable(resolve, &root.id, |_, _| false))
+            .filter(|id| !roots.contains(id) && !without.contains(id))
+            .map(|id| &metadata[id])
+            .collect();
+        transitive.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        transitive.dedup_by_key(|package| &package.id);
+
+        let count = optional_deps.len();
+        span_lint_and_then(
+            cx,
+            IMPLICIT_DEFAULT_FEATURES,
+            DUMMY_SP,
+            format!(
+                "the default features of `{}` enable {count} optional {}",
+                dep.name,
+                if count == 1 { "dependency" } else { "dependencies" }
+            ),
+            |diag| {
+                diag.note(format!("enabled by default: {}", describe(&optional_deps)));
+                if !transitive.is_empty() {
+                    diag.note(format!("which also pull in: {}", describe(&transitive)));
+                }
+                diag.help("add `default-features = false` to the dependency, and enable only the needed features");
+            },
+        );
+    }
+}
+
+/// Collects the packages reachable from `start` in the resolve graph through the edges not
+/// `skipped`, without the development dependencies which are only compiled for the tests of their
+/// package.
+fn reachable<'a>(
+    resolve: &'a Resolve,
+    start: &'a PackageId,
+    skipped: impl Fn(&PackageId, &PackageId) -> bool,
+) -> FxIndexSet<&'a PackageId> {
+    let mut stack = vec![start];
+    let mut seen = FxIndexSet::default();
+    while let Some(id) = stack.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let Some(node) = resolve.nodes.iter().find(|node| &node.id == id) else {
+            continue;
+        };
+        stack.extend(
+            node.deps
+                .iter()
+                .filter(|dep| {
+                    dep.dep_kinds
+                        .iter()
+                        .any(|info| info.kind != DependencyKind::Development)
+                        && !skipped(id, &dep.pkg)
+                })
+                .map(|dep| &dep.pkg),
+        );
+    }
+    seen
+}
+
+/// Lists the names and versions of `packages`
+fn describe(packages: &[&Package]) -> String {
+    packages
+        .iter()
+        .map(|package| format!("`{} v{}`", package.name, package.version))
+        .join(", ")
+}
+
+/// Collects the keys of the dependencies which are enabled by the given features of `package`.
+fn enabled_dependencies<'a>(package: &'a Package, features: impl IntoIterator<Item = &'a str>) -> FxHashSet<&'a str> {
+    let mut stack: Vec<&str> = features.into_iter().collect();
+    let mut seen = FxHashSet::default();
+    let mut deps = FxHashSet::default();
+
+    while let Some(feature) = stack.pop() {
+        if !seen.insert(feature) {
+            continue;
+        }
+        if let Some(dep) = feature.strip_prefix("dep:") {
+            deps.insert(dep);
+        } else if let Some((dep, _)) = feature.split_once('/') {
+            // `dep?/feature` only enables the feature if the dependency is enabled by something else
+            if !dep.ends_with('?') {
+                deps.insert(dep);
+            }
+        } else if let Some(enables) = package.features.get(feature) {
+            stack.extend(enables.iter().map(String::as_str));
+        }
+    }
+    deps
+}
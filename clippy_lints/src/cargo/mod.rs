@@ -1,10 +1,16 @@
 mod common_metadata;
 mod feature_name;
+mod implicit_default_features;
 mod lint_groups_priority;
 mod multiple_crate_versions;
+mod unused_dependencies;
+mod unused_features;
 mod wildcard_dependencies;
 
-use cargo_metadata::MetadataCommand;
+use std::path::Path;
+use std::{env, fs};
+
+use cargo_metadata::{Metadata, MetadataCommand, Package};
 use clippy_config::Conf;
 use clippy_utils::diagnostics::span_lint;
 use clippy_utils::is_lint_allowed;
@@ -204,6 +210,117 @@ declare_clippy_lint! {
     "a lint group in `Cargo.toml` at the same priority as a lint"
 }
 
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for features declared in `Cargo.toml` which don't enable any code: they
+    /// are never used in a `cfg`, or only ever used negated, as in `#[cfg(not(feature = "foo"))]`.
+    ///
+    /// Features which enable other features or optional dependencies are only linted
+    /// if they are used negated. Build scripts reading the `CARGO_FEATURE_<name>`
+    /// environment variable and the `required-features` of a target count as a use.
+    ///
+    /// ### Why is this bad?
+    /// An unused feature does nothing when it is enabled, which is confusing for
+    /// users of the crate. Features are supposed to be additive, enabling a feature
+    /// which is only used negated removes functionality instead.
+    ///
+    /// ### Known problems
+    /// Only Rust files in the package directory are searched, uses in files which
+    /// are outside of it or generated by a build script are not found.
+    ///
+    /// ### Example
+    /// ```toml
+    /// [features]
+    /// # Never used in the code
+    /// foo = []
+    /// # Only used as `#[cfg(not(feature = "no-std"))]`
+    /// no-std = []
+    /// ```
+    /// Use instead:
+    /// ```toml
+    /// [features]
+    /// default = ["std"]
+    /// # Used as `#[cfg(feature = "std")]`
+    /// std = []
+    /// ```
+    #[clippy::version = "1.84.0"]
+    pub UNUSED_FEATURES,
+    cargo,
+    "features which are never used to enable code"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for dependencies, dev-dependencies and build-dependencies declared in
+    /// `Cargo.toml` which are never used.
+    ///
+    /// Dependencies are checked when linting the library and binaries, dev-dependencies
+    /// when linting tests, benchmarks and examples, and build-dependencies when linting
+    /// the build script. A dependency is not reported if it is mentioned in code disabled
+    /// by a `cfg`, e.g. `#[cfg(test)]`, `#[cfg(windows)]` or `#[cfg(feature = "..")]`, or
+    /// in a file of another target which could use it. Optional dependencies are only
+    /// checked when they are enabled.
+    ///
+    /// ### Why is this bad?
+    /// Unused dependencies increase the build time and the size of the dependency graph
+    /// for no benefit.
+    ///
+    /// ### Known problems
+    /// Dependencies which are only needed for linking, or to enable features of other
+    /// dependencies, are reported as well. Use `use dependency as _;` to mark them as used.
+    ///
+    /// The mentions of a dependency are found by name, so a dependency is not reported
+    /// if e.g. a local variable has the same name.
+    ///
+    /// ### Example
+    /// ```toml
+    /// [dependencies]
+    /// # Never used in the code
+    /// regex = "1"
+    /// serde = { version = "1", optional = true }
+    ///
+    /// [features]
+    /// serde = ["dep:serde"]
+    /// ```
+    #[clippy::version = "1.84.0"]
+    pub UNUSED_DEPENDENCIES,
+    cargo,
+    "dependencies which are never used"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for dependencies declared without `default-features = false`, where the
+    /// default features of the dependency enable optional dependencies of their own.
+    /// The optional dependencies enabled this way are listed, unless they are also
+    /// enabled by the features which are requested explicitly, along with the crates
+    /// of the resolve graph which are only compiled because of them.
+    ///
+    /// ### Why is this bad?
+    /// Default features often enable more than is needed. Every optional dependency
+    /// which is enabled has to be downloaded and compiled, which increases the build time.
+    ///
+    /// ### Known problems
+    /// Features are unified across the dependency graph, if another crate enables the
+    /// default features of the dependency, disabling them here has no effect.
+    ///
+    /// ### Example
+    /// ```toml
+    /// [dependencies]
+    /// # The default features enable `indexmap`
+    /// foo = "1"
+    /// ```
+    /// Use instead:
+    /// ```toml
+    /// [dependencies]
+    /// foo = { version = "1", default-features = false, features = ["std"] }
+    /// ```
+    #[clippy::version = "1.84.0"]
+    pub IMPLICIT_DEFAULT_FEATURES,
+    cargo,
+    "dependencies whose default features enable optional dependencies"
+}
+
 pub struct Cargo {
     allowed_duplicate_crates: FxHashSet<String>,
     ignore_publish: bool,
@@ -216,6 +333,9 @@ impl_lint_pass!(Cargo => [
     MULTIPLE_CRATE_VERSIONS,
     WILDCARD_DEPENDENCIES,
     LINT_GROUPS_PRIORITY,
+    UNUSED_FEATURES,
+    UNUSED_DEPENDENCIES,
+    IMPLICIT_DEFAULT_FEATURES,
 ]);
 
 impl Cargo {
@@ -234,8 +354,10 @@ impl LateLintPass<'_> for Cargo {
             REDUNDANT_FEATURE_NAMES,
             NEGATIVE_FEATURE_NAMES,
             WILDCARD_DEPENDENCIES,
+            UNUSED_FEATURES,
+            UNUSED_DEPENDENCIES,
         ];
-        static WITH_DEPS_LINTS: &[&Lint] = &[MULTIPLE_CRATE_VERSIONS];
+        static WITH_DEPS_LINTS: &[&Lint] = &[MULTIPLE_CRATE_VERSIONS, IMPLICIT_DEFAULT_FEATURES];
 
         lint_groups_priority::check(cx);
 
@@ -248,6 +370,12 @@ impl LateLintPass<'_> for Cargo {
                     common_metadata::check(cx, &metadata, self.ignore_publish);
                     feature_name::check(cx, &metadata);
                     wildcard_dependencies::check(cx, &metadata);
+                    if !is_lint_allowed(cx, UNUSED_FEATURES, CRATE_HIR_ID) {
+                        unused_features::check(cx, &metadata);
+                    }
+                    if !is_lint_allowed(cx, UNUSED_DEPENDENCIES, CRATE_HIR_ID) {
+                        unused_dependencies::check(cx, &metadata);
+                    }
                 },
                 Err(e) => {
                     for lint in NO_DEPS_LINTS {
@@ -264,6 +392,7 @@ impl LateLintPass<'_> for Cargo {
             match MetadataCommand::new().exec() {
                 Ok(metadata) => {
                     multiple_crate_versions::check(cx, &metadata, &self.allowed_duplicate_crates);
+                    implicit_default_features::check(cx, &metadata);
                 },
                 Err(e) => {
                     for lint in WITH_DEPS_LINTS {
@@ -274,3 +403,44 @@ impl LateLintPass<'_> for Cargo {
         }
     }
 }
+
+/// Finds the package which is currently being compiled.
+fn current_package(metadata: &Metadata) -> Option<&Package> {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR")?;
+    metadata.packages.iter().find(|package| {
+        package
+            .manifest_path
+            .parent()
+            .is_some_and(|dir| dir.as_std_path() == manifest_dir)
+    })
+}
+
+/// Calls `f` with the path and contents of every Rust file in the package, skipping the build
+/// output, hidden directories and nested packages.
+fn for_each_source_file(package: &Package, f: &mut impl FnMut(&Path, &str)) {
+    if let Some(dir) = package.manifest_path.parent() {
+        for_each_source_file_in(dir.as_std_path(), f);
+    }
+}
+
+fn for_each_source_file_in(dir: &Path, f: &mut impl FnMut(&Path, &str)) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            let name = entry.file_name();
+            if name != "target" && !name.to_string_lossy().starts_with('.') && !path.join("Cargo.toml").exists() {
+                for_each_source_file_in(&path, f);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs")
+            && let Ok(src) = fs::read_to_string(&path)
+        {
+            f(&path, &src);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cargo_metadata::{DependencyKind, Metadata, Package, Target};
+use clippy_utils::diagnostics::span_lint_and_help;
+use clippy_utils::tokenize_with_text;
+use itertools::Itertools;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_lexer::TokenKind;
+use rustc_lint::{LateContext, LintContext};
+use rustc_session::config::{ExternEntry, ExternLocation};
+use rustc_session::cstore::ExternCrate;
+use rustc_span::{DUMMY_SP, FileName};
+
+use super::{UNUSED_DEPENDENCIES, current_package, for_each_source_file};
+
+pub(super) fn check(cx: &LateContext<'_>, metadata: &Metadata) {
+    let Some(package) = current_package(metadata) else {
+        return;
+    };
+    let Some(target) = current_target(cx, package) else {
+        return;
+    };
+
+    let kind = if target.kind.iter().any(|kind| kind == "custom-build") {
+        DependencyKind::Build
+    } else if cx.sess().opts.test
+        || target
+            .kind
+            .iter()
+            .any(|kind| matches!(kind.as_str(), "test" | "bench" | "example"))
+    {
+        DependencyKind::Development
+    } else {
+        DependencyKind::Normal
+    };
+
+    let used_paths: FxHashSet<&Path> = cx
+        .tcx
+        .crates(())
+        .iter()
+        .filter(|&&cnum| cx.tcx.extern_crate(cnum).is_some_and(ExternCrate::is_direct))
+        .flat_map(|&cnum| cx.tcx.used_crate_source(cnum).paths())
+        .map(PathBuf::as_path)
+        .collect();
+    let mut idents = None;
+
+    for dep in &package.dependencies {
+        // A dependency which is also a normal dependency is checked as one
+        if dep.kind != kind
+            || (kind == DependencyKind::Development
+                && package
+                    .dependencies
+                    .iter()
+                    .any(|other| other.kind == DependencyKind::Normal && other.name == dep.name))
+        {
+            continue;
+        }
+
+        let key = dep.rename.as_ref().unwrap_or(&dep.name);
+        let crate_name = key.replace('-', "_");
+        // Dependencies which are not passed to this crate are disabled optional dependencies, or
+        // ones for other platforms
+        let Some(entry) = cx.sess().opts.externs.get(&crate_name) else {
+            continue;
+        };
+        if entry.nounused_dep
+            || is_used(entry, &used_paths)
+            || idents
+                .get_or_insert_with(|| unchecked_idents(cx, package, kind))
+                .contains(&crate_name)
+        {
+            continue;
+        }
+
+        let section = match kind {
+            DependencyKind::Build => "build-dependency",
+            DependencyKind::Development => "dev-dependency",
+            _ => "dependency",
+        };
+        let enabling_features: Vec<_> = package
+            .features
+            .iter()
+            .filter(|(_, enables)| matches!(enables.as_slice(), [enables] if *enables == format!("dep:{key}") || enables == key))
+            .map(|(feature, _)| format!("`{feature}`"))
+            .sorted()
+            .collect();
+        let help = match &*enabling_features {
+            [] => "remove it from `Cargo.toml`".to_owned(),
+            [feature] => format!("remove it and the {feature} feature, which only enables it, from `Cargo.toml`"),
+            features => format!(
+                "remove it and the {} features, which only enable it, from `Cargo.toml`",
+                features.join(", ")
+            ),
+        };
+
+        span_lint_and_help(
+            cx,
+            UNUSED_DEPENDENCIES,
+            DUMMY_SP,
+            format!("the {section} `{key}` is not used"),
+            None,
+            help,
+        );
+    }
+}
+
+/// Finds the target of the package which is currently being compiled.
+fn current_target<'a>(cx: &LateContext<'_>, package: &'a Package) -> Option<&'a Target> {
+    let file = cx.sess().local_crate_source_file()?;
+    let file = fs::canonicalize(file.local_path()?).ok()?;
+    package
+        .targets
+        .iter()
+        .find(|target| fs::canonicalize(&target.src_path).is_ok_and(|path| path == file))
+}
+
+fn is_used(entry: &ExternEntry, used_paths: &FxHashSet<&Path>) -> bool {
+    match &entry.location {
+        ExternLocation::ExactPaths(paths) => paths.iter().any(|path| {
+            used_paths.contains(path.canonicalized().as_path()) || used_paths.contains(path.original().as_path())
+        }),
+        // The crate could be anywhere in the search paths
+        ExternLocation::FoundInLibrarySearchDirectories => true,
+    }
+}
+
+/// Collects the identifiers of the code which may use a dependency without it being compiled
+/// here: the code of the crate disabled by a `cfg`, like `#[cfg(test)]` or a platform or feature
+/// `cfg`, and the files of the package which are not part of the crate. These may belong to other
+/// targets, which also receive the dependency, unless it is a build-dependency.
+///
+/// The code of the crate which is compiled doesn't need to be excluded, as the dependencies it
+/// mentions are used.
+fn unchecked_idents(cx: &LateContext<'_>, package: &Package, kind: DependencyKind) -> FxHashSet<String> {
+    let crate_files: FxHashSet<PathBuf> = cx
+        .sess()
+        .source_map()
+        .files()
+        .iter()
+        .filter_map(|file| match &file.name {
+            FileName::Real(name) => fs::canonicalize(name.local_path()?).ok(),
+            _ => None,
+        })
+        .collect();
+
+    let mut idents = FxHashSet::default();
+    for_each_source_file(package, &mut |path, src| {
+        if kind != DependencyKind::Build || fs::canonicalize(path).is_ok_and(|path| crate_files.contains(&path)) {
+            idents.extend(
+                tokenize_with_text(src)
+                    .filter(|(kind, ..)| *kind == TokenKind::Ident)
+                    .map(|(_, text, _)| text.to_owned()),
+            );
+        }
+    });
+    idents
+}
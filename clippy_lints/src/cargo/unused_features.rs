@@ -0,0 +1,118 @@
+use cargo_metadata::Metadata;
+use clippy_utils::diagnostics::span_lint_and_help;
+use clippy_utils::tokenize_with_text;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_lexer::{LiteralKind, TokenKind};
+use rustc_lint::LateContext;
+use rustc_span::DUMMY_SP;
+
+use super::{UNUSED_FEATURES, current_package, for_each_source_file};
+
+#[derive(Clone, Copy, Default)]
+struct Usage {
+    positive: bool,
+    negated: bool,
+}
+
+#[derive(Default)]
+struct FeatureUses {
+    /// `feature = ".."` predicates inside of `cfg`, `cfg_attr` and `cfg!`.
+    cfgs: FxHashMap<String, Usage>,
+    /// `CARGO_FEATURE_..` environment variables, as read by build scripts.
+    env_vars: FxHashSet<String>,
+}
+
+pub(super) fn check(cx: &LateContext<'_>, metadata: &Metadata) {
+    let Some(package) = current_package(metadata) else {
+        return;
+    };
+
+    let mut uses = FeatureUses::default();
+    for_each_source_file(package, &mut |_, src| collect_uses(src, &mut uses));
+    // A target with `required-features` is only built with them
+    for feature in package.targets.iter().flat_map(|target| &target.required_features) {
+        uses.cfgs.entry(feature.clone()).or_default().positive = true;
+    }
+
+    let mut features: Vec<_> = package.features.iter().collect();
+    features.sort();
+    for (feature, enables) in features {
+        if feature == "default" {
+            continue;
+        }
+
+        let env_var = format!("CARGO_FEATURE_{}", feature.to_uppercase().replace('-', "_"));
+        let mut usage = uses.cfgs.get(feature).copied().unwrap_or_default();
+        usage.positive |= uses.env_vars.contains(&env_var);
+
+        match usage {
+            // Features which enable other features or dependencies have an effect on their own
+            Usage {
+                positive: false,
+                negated: false,
+            } if enables.is_empty() => span_lint_and_help(
+                cx,
+                UNUSED_FEATURES,
+                DUMMY_SP,
+                format!("the feature \"{feature}\" is not used in any `cfg`"),
+                None,
+                "consider removing it from `Cargo.toml`",
+            ),
+            Usage {
+                positive: false,
+                negated: true,
+            } => span_lint_and_help(
+                cx,
+                UNUSED_FEATURES,
+                DUMMY_SP,
+                format!("the feature \"{feature}\" is only used negated"),
+                None,
+                "features should only add functionality, consider replacing it with a feature which enables this code",
+            ),
+            _ => {},
+        }
+    }
+}
+
+/// Collects the features used in the given source text. This works on the tokens rather than the
+/// AST, as `cfg`s which are disabled have already been removed by the time the lint runs.
+fn collect_uses(src: &str, uses: &mut FeatureUses) {
+    // Whether each open parenthesis is part of a `cfg` predicate, and whether that is negated
+    let mut parens: Vec<(bool, bool)> = Vec::new();
+    // The last three tokens, ignoring whitespace and comments
+    let mut prev = [""; 3];
+
+    for (kind, text, _) in tokenize_with_text(src) {
+        match kind {
+            TokenKind::Whitespace | TokenKind::LineComment { .. } | TokenKind::BlockComment { .. } => continue,
+            TokenKind::OpenParen => {
+                let (in_cfg, negated) = parens.last().copied().unwrap_or_default();
+                parens.push(match prev {
+                    [_, _, "cfg" | "cfg_attr"] | [_, "cfg", "!"] => (true, false),
+                    [.., "not"] if in_cfg => (true, !negated),
+                    _ => (in_cfg, negated),
+                });
+            },
+            TokenKind::CloseParen => {
+                parens.pop();
+            },
+            TokenKind::Literal {
+                kind: LiteralKind::Str { terminated: true },
+                ..
+            } => {
+                let value = &text[1..text.len() - 1];
+                if let Some(&(true, negated)) = parens.last()
+                    && prev[1..] == ["feature", "="]
+                {
+                    let usage = uses.cfgs.entry(value.to_owned()).or_default();
+                    usage.positive |= !negated;
+                    usage.negated |= negated;
+                } else if value.starts_with("CARGO_FEATURE_") {
+                    uses.env_vars.insert(value.to_owned());
+                }
+            },
+            _ => {},
+        }
+        prev = [prev[1], prev[2], text];
+    }
+}
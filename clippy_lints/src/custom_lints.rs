@@ -0,0 +1,600 @@
+//! The lints defined in the `custom-lints` configuration.
+//!
+//! A pattern is a Rust expression in which `$name` is a metavariable, matching any expression. It
+//! is parsed with the `$` replaced by `_`, and matched against the HIR of each expression. A
+//! metavariable used more than once must match equal expressions, in the sense of [`SpanlessEq`].
+
+use clippy_config::Conf;
+use clippy_config::types::{CustomLint, CustomLintLevel};
+use clippy_utils::diagnostics::{set_undocumented_lints, span_lint, span_lint_and_sugg};
+use clippy_utils::sugg::Sugg;
+use clippy_utils::{SpanlessEq, is_lint_allowed};
+use rustc_ast::ptr::P;
+use rustc_ast::{self as ast, GenericArg, GenericArgs, LitFloatType, LitIntType, LitKind, token};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::Applicability;
+use rustc_hir::def::Res;
+use rustc_hir::def_id::DefId;
+use rustc_hir::definitions::DefPathData;
+use rustc_hir::{Expr, ExprKind, QPath, TyKind};
+use rustc_lexer::TokenKind;
+use rustc_lint::{LateContext, LateLintPass, Level, Lint, LintPass, LintStore, LintVec};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_parse::new_parser_from_source_str;
+use rustc_span::symbol::{Symbol, sym};
+use rustc_span::{BytePos, FileName, Pos, Span};
+
+/// Registers the custom lints in the `clippy` tool namespace. The lints which could not be
+/// registered are reported by [`CustomLints::new`].
+pub fn register(store: &mut LintStore, conf: &'static Conf) -> Vec<Result<&'static Lint, String>> {
+    let mut registered = FxHashSet::default();
+    let checked: Vec<Result<String, String>> = conf
+        .custom_lints
+        .iter()
+        .map(|custom| {
+            let name = &custom.name;
+            if !name.starts_with(|c: char| c.is_ascii_lowercase())
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            {
+                return Err(format!(
+                    "`{name}` is not a valid lint name, use lowercase words separated by `_`"
+                ));
+            }
+            let full_name = format!("clippy::{name}");
+            if !registered.insert(name)
+                || store.find_lints(&full_name).is_ok()
+                || crate::deprecated_lints::DEPRECATED
+                    .iter()
+                    .any(|&(lint, _)| lint == full_name)
+            {
+                return Err(format!("a lint named `{full_name}` already exists"));
+            }
+            Ok(full_name.to_uppercase())
+        })
+        .collect();
+
+    // The lints live as long as the configuration, they are allocated all at once
+    let names: &'static str = String::leak(checked.iter().flatten().map(String::as_str).collect());
+    let mut pos = 0;
+    let lints: &'static [Lint] = Vec::leak(
+        conf.custom_lints
+            .iter()
+            .zip(&checked)
+            .filter_map(|(custom, checked)| {
+                let name = &names[pos..pos + checked.as_ref().ok()?.len()];
+                pos += name.len();
+                Some(Lint {
+                    name,
+                    default_level: match custom.level {
+                        CustomLintLevel::Allow => Level::Allow,
+                        CustomLintLevel::Warn => Level::Warn,
+                        CustomLintLevel::Deny => Level::Deny,
+                        CustomLintLevel::Forbid => Level::Forbid,
+                    },
+                    desc: &custom.message,
+                    is_externally_loaded: true,
+                    ..Lint::default_fields_for_macro()
+                })
+            })
+            .collect(),
+    );
+    let lint_refs: Vec<&'static Lint> = lints.iter().collect();
+    store.register_lints(&lint_refs);
+    set_undocumented_lints(lint_refs.iter().copied());
+
+    let mut lints = lints.iter();
+    checked
+        .into_iter()
+        .map(|checked| checked.map(|_| lints.next().unwrap()))
+        .collect()
+}
+
+pub struct CustomLints {
+    lints: Vec<CompiledLint>,
+}
+
+impl CustomLints {
+    pub fn new(tcx: TyCtxt<'_>, conf: &'static Conf, lints: &[Result<&'static Lint, String>]) -> Self {
+        Self {
+            lints: conf
+                .custom_lints
+                .iter()
+                .zip(lints)
+                .filter_map(|(custom, lint)| match lint {
+                    Ok(lint) => CompiledLint::new(tcx, custom, lint),
+                    Err(error) => {
+                        tcx.dcx().err(format!("invalid custom lint `{}`: {error}", custom.name));
+                        None
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
+// The lints are only known once the configuration is read
+#[allow(rustc::lint_pass_impl_without_macro)]
+impl LintPass for CustomLints {
+    fn name(&self) -> &'static str {
+        "CustomLints"
+    }
+
+    fn get_lints(&self) -> LintVec {
+        self.lints.iter().map(|lint| lint.lint).collect()
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for CustomLints {
+    fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'tcx>) {
+        if expr.span.from_expansion() {
+            return;
+        }
+        for lint in &self.lints {
+            if is_lint_allowed(cx, lint.lint, expr.hir_id) {
+                continue;
+            }
+            let mut matcher = Matcher {
+                cx,
+                lint,
+                bindings: FxHashMap::default(),
+            };
+            if !matcher.expr(&lint.pattern.expr, expr) {
+                continue;
+            }
+
+            match &lint.replacement {
+                Some(replacement) => {
+                    let mut app = Applicability::MachineApplicable;
+                    let sugg = replacement
+                        .iter()
+                        .map(|part| match part {
+                            ReplacementPart::Text(text) => text.clone(),
+                            ReplacementPart::Metavar(name, needs_par) => {
+                                let sugg = Sugg::hir_with_applicability(cx, matcher.bindings[name], "..", &mut app);
+                                if *needs_par {
+                                    sugg.maybe_par().to_string()
+                                } else {
+                                    sugg.to_string()
+                                }
+                            },
+                        })
+                        .collect();
+                    span_lint_and_sugg(
+                        cx,
+                        lint.lint,
+                        expr.span,
+                        lint.custom.message.clone(),
+                        lint.custom.help.clone().unwrap_or_else(|| "try".into()),
+                        sugg,
+                        app,
+                    );
+                },
+                None => span_lint(cx, lint.lint, expr.span, lint.custom.message.clone()),
+            }
+        }
+    }
+}
+
+struct CompiledLint {
+    lint: &'static Lint,
+    custom: &'static CustomLint,
+    pattern: Parsed<P<ast::Expr>>,
+    replacement: Option<Vec<ReplacementPart>>,
+    /// The types of the metavariables
+    types: FxHashMap<Symbol, Parsed<P<ast::Ty>>>,
+}
+
+enum ReplacementPart {
+    Text(String),
+    /// A metavariable, which is parenthesized if it needs to be
+    Metavar(Symbol, bool),
+}
+
+impl CompiledLint {
+    fn new(tcx: TyCtxt<'_>, custom: &'static CustomLint, lint: &'static Lint) -> Option<Self> {
+        let name = &custom.name;
+        let pattern = Parsed::new(tcx, format!("{name} pattern"), &custom.pattern, |parser| {
+            parser.parse_expr()
+        })?;
+        let mut metavars = FxHashSet::default();
+        if !pattern.check_supported(tcx, &pattern.expr, &mut |name, _, _| {
+            metavars.insert(name);
+        }) {
+            return None;
+        }
+
+        let replacement = match &custom.replacement {
+            Some(replacement) => {
+                let parsed = Parsed::new(tcx, format!("{name} replacement"), replacement, |parser| {
+                    parser.parse_expr()
+                })?;
+                let mut occurrences = Vec::new();
+                let mut unknown = false;
+                if !parsed.check_supported(tcx, &parsed.expr, &mut |name, span, needs_par| {
+                    if !metavars.contains(&name) {
+                        tcx.dcx()
+                            .span_err(span, format!("the metavariable `${name}` is not in the pattern"));
+                        unknown = true;
+                    }
+                    occurrences.push((span, name, needs_par));
+                }) || unknown
+                {
+                    return None;
+                }
+                Some(parsed.replacement(occurrences))
+            },
+            None => None,
+        };
+
+        let mut types = FxHashMap::default();
+        for (metavar, ty) in &custom.types {
+            let metavar = Symbol::intern(metavar.strip_prefix('$').unwrap_or(metavar));
+            if !metavars.contains(&metavar) {
+                tcx.dcx().err(format!(
+                    "invalid custom lint `{name}`: the metavariable `${metavar}` is not in the pattern"
+                ));
+                return None;
+            }
+            let ty = Parsed::new(tcx, format!("{name} type of ${metavar}"), ty, |parser| {
+                parser.parse_ty()
+            })?;
+            types.insert(metavar, ty);
+        }
+
+        Some(Self {
+            lint,
+            custom,
+            pattern,
+            replacement,
+            types,
+        })
+    }
+}
+
+/// A pattern parsed from the configuration
+struct Parsed<T> {
+    /// The source, in which the `$` of the metavariables were replaced by `_`
+    source: String,
+    /// The position of the source in the source map
+    start: BytePos,
+    /// The positions of the metavariables in the source map
+    metavars: FxHashSet<BytePos>,
+    expr: T,
+}
+
+impl<T> Parsed<T> {
+    fn new<'a>(
+        tcx: TyCtxt<'a>,
+        file_name: String,
+        source: &str,
+        parse: impl FnOnce(&mut rustc_parse::parser::Parser<'a>) -> rustc_errors::PResult<'a, T>,
+    ) -> Option<Self>
+    where
+        T: HasSpan,
+    {
+        let mut replaced = String::with_capacity(source.len());
+        let mut offsets = Vec::new();
+        let mut pos = 0;
+        let mut tokens = rustc_lexer::tokenize(source).peekable();
+        while let Some(token) = tokens.next() {
+            let text = &source[pos..pos + token.len as usize];
+            if token.kind == TokenKind::Dollar
+                && let Some(next) = tokens.peek()
+                && next.kind == TokenKind::Ident
+            {
+                offsets.push(pos);
+                replaced.push('_');
+            } else {
+                replaced.push_str(text);
+            }
+            pos += token.len as usize;
+        }
+
+        let psess = &tcx.sess.psess;
+        let mut parser = match new_parser_from_source_str(psess, FileName::Custom(file_name), replaced.clone()) {
+            Ok(parser) => parser,
+            Err(errors) => {
+                for error in errors {
+                    error.emit();
+                }
+                return None;
+            },
+        };
+        let expr = match parse(&mut parser).and_then(|expr| parser.expect(&token::Eof).map(|_| expr)) {
+            Ok(expr) => expr,
+            Err(error) => {
+                error.emit();
+                return None;
+            },
+        };
+
+        let start = psess.source_map().lookup_source_file(expr.span().lo()).start_pos;
+        Some(Self {
+            source: replaced,
+            start,
+            metavars: offsets
+                .into_iter()
+                .map(|offset| start + BytePos::from_usize(offset))
+                .collect(),
+            expr,
+        })
+    }
+
+    /// Returns the name of the metavariable `expr` is, if it is one.
+    fn metavar(&self, expr: &ast::Expr) -> Option<Symbol> {
+        if let ast::ExprKind::Path(None, path) = &expr.kind
+            && let [segment] = &*path.segments
+            && segment.args.is_none()
+            && self.metavars.contains(&segment.ident.span.lo())
+        {
+            Some(Symbol::intern(&segment.ident.as_str()[1..]))
+        } else {
+            None
+        }
+    }
+
+    /// Checks that `expr` is only made of the expressions supported by the [`Matcher`], which are
+    /// reported otherwise. `metavar` is called with the name and the span of each metavariable,
+    /// and whether it must be parenthesized when it is replaced by another expression.
+    fn check_supported(&self, tcx: TyCtxt<'_>, expr: &ast::Expr, metavar: &mut impl FnMut(Symbol, Span, bool)) -> bool {
+        self.check_supported_inner(tcx, expr, false, metavar)
+    }
+
+    fn check_supported_inner(
+        &self,
+        tcx: TyCtxt<'_>,
+        expr: &ast::Expr,
+        needs_par: bool,
+        metavar: &mut impl FnMut(Symbol, Span, bool),
+    ) -> bool {
+        if let Some(name) = self.metavar(expr) {
+            metavar(name, expr.span, needs_par);
+            return true;
+        }
+        let mut check = |expr: &ast::Expr, needs_par| self.check_supported_inner(tcx, expr, needs_par, metavar);
+        match &expr.kind {
+            ast::ExprKind::Lit(_) => true,
+            ast::ExprKind::Path(None, path) if path.segments.iter().all(|segment| segment.args.is_none()) => true,
+            ast::ExprKind::Paren(inner) => check(inner, false),
+            ast::ExprKind::Call(func, args) => {
+                check(func, true) & args.iter().fold(true, |ok, arg| ok & check(arg, false))
+            },
+            ast::ExprKind::MethodCall(call) if call.seg.args.is_none() => {
+                check(&call.receiver, true) & call.args.iter().fold(true, |ok, arg| ok & check(arg, false))
+            },
+            ast::ExprKind::Binary(_, lhs, rhs) => check(lhs, true) & check(rhs, true),
+            ast::ExprKind::Unary(_, inner) | ast::ExprKind::AddrOf(_, _, inner) | ast::ExprKind::Field(inner, _) => {
+                check(inner, true)
+            },
+            ast::ExprKind::Index(base, index, _) => check(base, true) & check(index, false),
+            ast::ExprKind::Tup(exprs) | ast::ExprKind::Array(exprs) => {
+                exprs.iter().fold(true, |ok, expr| ok & check(expr, false))
+            },
+            _ => {
+                tcx.dcx()
+                    .span_err(expr.span, "this expression is not supported in custom lints");
+                false
+            },
+        }
+    }
+
+    /// Splits the source around the `occurrences` of the metavariables.
+    fn replacement(&self, mut occurrences: Vec<(Span, Symbol, bool)>) -> Vec<ReplacementPart> {
+        occurrences.sort_by_key(|(span, ..)| span.lo());
+        let mut parts = Vec::new();
+        let mut pos = 0;
+        for (span, name, needs_par) in occurrences {
+            let (lo, hi) = ((span.lo() - self.start).to_usize(), (span.hi() - self.start).to_usize());
+            parts.push(ReplacementPart::Text(self.source[pos..lo].to_string()));
+            parts.push(ReplacementPart::Metavar(name, needs_par));
+            pos = hi;
+        }
+        parts.push(ReplacementPart::Text(self.source[pos..].to_string()));
+        parts
+    }
+}
+
+trait HasSpan {
+    fn span(&self) -> Span;
+}
+
+impl HasSpan for P<ast::Expr> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl HasSpan for P<ast::Ty> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// Matches a pattern against an expression, binding its metavariables
+struct Matcher<'a, 'tcx> {
+    cx: &'a LateContext<'tcx>,
+    lint: &'a CompiledLint,
+    bindings: FxHashMap<Symbol, &'tcx Expr<'tcx>>,
+}
+
+impl<'tcx> Matcher<'_, 'tcx> {
+    fn expr(&mut self, pat: &ast::Expr, expr: &'tcx Expr<'tcx>) -> bool {
+        if let Some(name) = self.lint.pattern.metavar(pat) {
+            return self.bind(name, expr);
+        }
+        if expr.span.from_expansion() {
+            return false;
+        }
+        let expr = match expr.kind {
+            ExprKind::DropTemps(inner) => inner,
+            _ => expr,
+        };
+        match (&pat.kind, &expr.kind) {
+            (ast::ExprKind::Paren(pat), _) => self.expr(pat, expr),
+            (ast::ExprKind::Lit(pat), ExprKind::Lit(lit)) => lit_matches(*pat, &lit.node),
+            (ast::ExprKind::Path(None, path), ExprKind::Path(qpath)) => {
+                path_matches(self.cx, &path.segments, self.cx.qpath_res(qpath, expr.hir_id))
+                    || type_relative_path_matches(self.cx, &path.segments, qpath)
+            },
+            (ast::ExprKind::Call(pat_func, pat_args), ExprKind::Call(func, args)) => {
+                self.expr(pat_func, func) && self.exprs(pat_args, args)
+            },
+            (ast::ExprKind::MethodCall(call), ExprKind::MethodCall(segment, receiver, args, _)) => {
+                call.seg.ident.name == segment.ident.name
+                    && self.expr(&call.receiver, receiver)
+                    && self.exprs(&call.args, args)
+            },
+            (ast::ExprKind::Binary(pat_op, pat_lhs, pat_rhs), ExprKind::Binary(op, lhs, rhs)) => {
+                pat_op.node == op.node && self.expr(pat_lhs, lhs) && self.expr(pat_rhs, rhs)
+            },
+            (ast::ExprKind::Unary(pat_op, pat), ExprKind::Unary(op, inner)) => pat_op == op && self.expr(pat, inner),
+            (ast::ExprKind::AddrOf(pat_kind, pat_mutbl, pat), ExprKind::AddrOf(kind, mutbl, inner)) => {
+                pat_kind == kind && pat_mutbl == mutbl && self.expr(pat, inner)
+            },
+            (ast::ExprKind::Field(pat, pat_ident), ExprKind::Field(inner, ident)) => {
+                pat_ident.name == ident.name && self.expr(pat, inner)
+            },
+            (ast::ExprKind::Index(pat_base, pat_index, _), ExprKind::Index(base, index, _)) => {
+                self.expr(pat_base, base) && self.expr(pat_index, index)
+            },
+            (ast::ExprKind::Tup(pats), ExprKind::Tup(exprs)) | (ast::ExprKind::Array(pats), ExprKind::Array(exprs)) => {
+                self.exprs(pats, exprs)
+            },
+            _ => false,
+        }
+    }
+
+    fn exprs(&mut self, pats: &[P<ast::Expr>], exprs: &'tcx [Expr<'tcx>]) -> bool {
+        pats.len() == exprs.len() && pats.iter().zip(exprs).all(|(pat, expr)| self.expr(pat, expr))
+    }
+
+    fn bind(&mut self, name: Symbol, expr: &'tcx Expr<'tcx>) -> bool {
+        if let Some(bound) = self.bindings.get(&name) {
+            return SpanlessEq::new(self.cx).eq_expr(bound, expr);
+        }
+        if let Some(ty) = self.lint.types.get(&name)
+            && !ty_matches(self.cx, &ty.expr, self.cx.typeck_results().expr_ty(expr))
+        {
+            return false;
+        }
+        self.bindings.insert(name, expr);
+        true
+    }
+}
+
+fn lit_matches(pat: token::Lit, lit: &LitKind) -> bool {
+    let Ok(pat) = LitKind::from_token_lit(pat) else {
+        return false;
+    };
+    match (pat, lit) {
+        (LitKind::Int(pat, LitIntType::Unsuffixed), LitKind::Int(value, _)) => pat == *value,
+        (LitKind::Float(pat, LitFloatType::Unsuffixed), LitKind::Float(value, _))
+        | (LitKind::Str(pat, _), LitKind::Str(value, _)) => pat == *value,
+        (pat, lit) => pat == *lit,
+    }
+}
+
+/// Checks if the `segments` of a path name the item of `res`, they must match the last segments of
+/// the path of the item. `std`, `core` and `alloc` are interchangeable.
+fn path_matches(cx: &LateContext<'_>, segments: &[ast::PathSegment], res: Res) -> bool {
+    let names = match res {
+        Res::Local(id) => vec![cx.tcx.hir().name(id)],
+        Res::Def(_, id) => def_path_names(cx.tcx, id),
+        _ => return false,
+    };
+    segments_match(segments, &names)
+}
+
+/// Checks if the `segments` of a path match a path relative to the type it is written with, e.g.
+/// `String::from` which names the method of the `From` trait.
+fn type_relative_path_matches(cx: &LateContext<'_>, segments: &[ast::PathSegment], qpath: &QPath<'_>) -> bool {
+    if let QPath::TypeRelative(ty, segment) = qpath
+        && let TyKind::Path(QPath::Resolved(None, ty_path)) = ty.kind
+        && let [ty_segments @ .., last] = segments
+    {
+        last.ident.name == segment.ident.name && path_matches(cx, ty_segments, ty_path.res)
+    } else {
+        false
+    }
+}
+
+fn segments_match(segments: &[ast::PathSegment], names: &[Symbol]) -> bool {
+    let Some(skip) = names.len().checked_sub(segments.len()) else {
+        return false;
+    };
+    let is_std = |name: Symbol| matches!(name, sym::std | sym::core | sym::alloc);
+    segments
+        .iter()
+        .zip(&names[skip..])
+        .enumerate()
+        .all(|(i, (segment, &name))| {
+            segment.ident.name == name || (i == 0 && skip == 0 && is_std(segment.ident.name) && is_std(name))
+        })
+}
+
+/// Returns the names in the path of an item. The items of inherent impls are named after the self
+/// type, e.g. `alloc::vec::Vec::new`.
+fn def_path_names(tcx: TyCtxt<'_>, def_id: DefId) -> Vec<Symbol> {
+    let mut names = Vec::new();
+    let mut next = Some(def_id);
+    while let Some(id) = next {
+        let key = tcx.def_key(id);
+        next = key.parent.map(|index| DefId { index, ..id });
+        match key.disambiguated_data.data {
+            DefPathData::Impl => {
+                let self_ty = tcx.type_of(id).instantiate_identity();
+                if let ty::Adt(adt, _) = self_ty.kind() {
+                    next = Some(adt.did());
+                } else {
+                    names.push(Symbol::intern(&self_ty.to_string()));
+                    break;
+                }
+            },
+            DefPathData::CrateRoot => names.push(tcx.crate_name(id.krate)),
+            data => names.extend(data.get_opt_name()),
+        }
+    }
+    names.reverse();
+    names
+}
+
+/// Checks if `ty` matches the type pattern `pat`, where `_` matches any type. A path matches the
+/// types named like [`path_matches`], and its generic arguments the first ones of the type.
+fn ty_matches<'tcx>(cx: &LateContext<'tcx>, pat: &ast::Ty, ty: Ty<'tcx>) -> bool {
+    match (&pat.kind, ty.kind()) {
+        (ast::TyKind::Infer, _) => true,
+        (ast::TyKind::Paren(pat), _) => ty_matches(cx, pat, ty),
+        (ast::TyKind::Ref(_, pat), ty::Ref(_, ty, mutbl)) => pat.mutbl == *mutbl && ty_matches(cx, &pat.ty, *ty),
+        (ast::TyKind::Slice(pat), ty::Slice(ty)) => ty_matches(cx, pat, *ty),
+        (ast::TyKind::Tup(pats), ty::Tuple(tys)) => {
+            pats.len() == tys.len() && pats.iter().zip(*tys).all(|(pat, ty)| ty_matches(cx, pat, ty))
+        },
+        (ast::TyKind::Path(None, path), ty::Adt(adt, args)) => {
+            let Some(last) = path.segments.last() else {
+                return false;
+            };
+            let pat_args: Vec<_> = match last.args.as_deref() {
+                None => Vec::new(),
+                Some(GenericArgs::AngleBracketed(args)) => args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        ast::AngleBracketedArg::Arg(GenericArg::Type(ty)) => Some(ty),
+                        _ => None,
+                    })
+                    .collect(),
+                Some(_) => return false,
+            };
+            let tys: Vec<_> = args.types().collect();
+            segments_match(&path.segments, &def_path_names(cx.tcx, adt.did()))
+                && pat_args.len() <= tys.len()
+                && pat_args.iter().zip(tys).all(|(pat, ty)| ty_matches(cx, pat, ty))
+        },
+        (ast::TyKind::Path(None, path), ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) | ty::Float(_) | ty::Str) => {
+            matches!(&*path.segments, [segment] if segment.args.is_none() && segment.ident.as_str() == ty.to_string())
+        },
+        _ => false,
+    }
+}
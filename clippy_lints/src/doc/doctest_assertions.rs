@@ -0,0 +1,263 @@
+use std::ops::Range;
+
+use crate::doc::{DOCTEST_WITHOUT_ASSERTIONS, UNMARKED_FAILING_DOCTEST};
+use clippy_utils::diagnostics::{span_lint_and_help, span_lint_and_then};
+use clippy_utils::tokenize_with_text;
+use rustc_errors::Applicability;
+use rustc_lexer::TokenKind;
+use rustc_lint::{LateContext, LintContext};
+use rustc_span::Span;
+
+use super::{DoctestAttrs, Fragments};
+
+/// Macros whose invocation counts as checking something in the example.
+const CHECKING_MACROS: &[&str] = &[
+    "assert",
+    "assert_eq",
+    "assert_ne",
+    "assert_matches",
+    "debug_assert",
+    "debug_assert_eq",
+    "debug_assert_ne",
+    "panic",
+    "unreachable",
+    "print",
+    "println",
+    "eprint",
+    "eprintln",
+    "dbg",
+    "write",
+    "writeln",
+];
+
+/// Methods and functions which only build a value, calling them alone does not make the example
+/// worth checking.
+const TRIVIAL_CALLS: &[&str] = &[
+    "as_ref",
+    "clone",
+    "default",
+    "from",
+    "into",
+    "iter",
+    "new",
+    "to_owned",
+    "to_string",
+    "to_vec",
+    "with_capacity",
+];
+
+/// Methods which panic when the value is not the expected one, like an assertion.
+const CHECKING_METHODS: &[&str] = &["expect", "expect_err", "unwrap", "unwrap_err"];
+
+/// Keywords which may be followed by an opening parenthesis without being a call.
+const NON_CALL_KEYWORDS: &[&str] = &[
+    "as", "fn", "for", "if", "impl", "in", "let", "match", "move", "mut", "ref", "return", "where", "while",
+];
+
+/// Phrases in comments that say the example does not compile.
+const COMPILE_FAILURE_PHRASES: &[&str] = &[
+    "does not compile",
+    "doesn't compile",
+    "won't compile",
+    "will not compile",
+    "fails to compile",
+    "compile error",
+    "compiler error",
+    "compilation error",
+    "compile-time error",
+    "error[e",
+];
+
+/// Phrases in comments that say the example panics when run.
+const RUNTIME_FAILURE_PHRASES: &[&str] = &["this panics", "will panic", "would panic", "panics here", "panics!"];
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Failure {
+    /// The comment says the code does not compile.
+    Compile,
+    /// The comment says the code panics.
+    Runtime,
+    /// The comment says the code fails, but not how.
+    Unknown,
+}
+
+impl Failure {
+    fn from_comment(comment: &str) -> Option<Self> {
+        let comment = comment
+            .trim_start_matches('/')
+            .trim_start_matches('*')
+            .trim_end_matches("*/")
+            .trim()
+            .to_lowercase();
+
+        if is_word_or_prefix(&comment, "error") || COMPILE_FAILURE_PHRASES.iter().any(|p| comment.contains(p)) {
+            Some(Self::Compile)
+        } else if RUNTIME_FAILURE_PHRASES.iter().any(|p| comment.contains(p)) {
+            Some(Self::Runtime)
+        } else if is_word_or_prefix(&comment, "fails") || comment.contains("this fails") {
+            Some(Self::Unknown)
+        } else {
+            None
+        }
+    }
+
+    fn help(self) -> &'static str {
+        match self {
+            Self::Compile => "if the example is not supposed to compile, mark it `compile_fail`",
+            Self::Runtime => "if the example is supposed to panic, mark it `should_panic`",
+            Self::Unknown => "if the example is supposed to fail, mark it `compile_fail` or `should_panic`",
+        }
+    }
+}
+
+/// Checks whether the comment is `word` on its own or starts with e.g. `word:` or `word!`.
+fn is_word_or_prefix(comment: &str, word: &str) -> bool {
+    comment
+        .strip_prefix(word)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([':', '!', '[', '.']))
+}
+
+#[derive(Default)]
+struct DoctestSummary {
+    /// Whether there is an assertion, a `?` or some output.
+    checks_something: bool,
+    /// Whether a function is called, other than the ones defined in the example, constructors and
+    /// the functions in `TRIVIAL_CALLS`.
+    calls_something: bool,
+    /// The first comment saying the code fails, relative to the start of the code.
+    failure_comment: Option<(Failure, Range<usize>)>,
+}
+
+fn summarize(code: &str) -> DoctestSummary {
+    let mut summary = DoctestSummary::default();
+    let mut prev = (TokenKind::Whitespace, "");
+    let mut defined_fns = Vec::new();
+    let mut called_fns = Vec::new();
+
+    for (kind, text, span) in tokenize_with_text(code) {
+        match kind {
+            TokenKind::Whitespace => continue,
+            TokenKind::LineComment { doc_style: None } | TokenKind::BlockComment { doc_style: None, .. } => {
+                if summary.failure_comment.is_none()
+                    && let Some(failure) = Failure::from_comment(text)
+                {
+                    summary.failure_comment = Some((failure, span.start..span.end));
+                }
+                continue;
+            },
+            TokenKind::Ident if prev.1 == "fn" => defined_fns.push(text),
+            TokenKind::Bang if prev.0 == TokenKind::Ident && CHECKING_MACROS.contains(&prev.1) => {
+                summary.checks_something = true;
+            },
+            // `T: ?Sized` is preceded by a colon or a plus, not by an expression
+            TokenKind::Question
+                if matches!(
+                    prev.0,
+                    TokenKind::Ident | TokenKind::CloseParen | TokenKind::CloseBracket | TokenKind::Literal { .. }
+                ) && prev.1 != "impl" =>
+            {
+                summary.checks_something = true;
+            },
+            TokenKind::OpenParen if prev.0 == TokenKind::Ident && CHECKING_METHODS.contains(&prev.1) => {
+                summary.checks_something = true;
+            },
+            // Tuple structs and enum variants start with an uppercase letter
+            TokenKind::OpenParen
+                if prev.0 == TokenKind::Ident
+                    && !NON_CALL_KEYWORDS.contains(&prev.1)
+                    && !TRIVIAL_CALLS.contains(&prev.1)
+                    && !prev.1.starts_with(char::is_uppercase) =>
+            {
+                called_fns.push(prev.1);
+            },
+            _ => {},
+        }
+        prev = (kind, text);
+    }
+
+    summary.calls_something = called_fns.iter().any(|name| !defined_fns.contains(name));
+    summary
+}
+
+pub fn check(cx: &LateContext<'_>, text: &str, range: Range<usize>, fragments: Fragments<'_>, attrs: DoctestAttrs) {
+    if attrs.compile_fail || attrs.should_panic {
+        return;
+    }
+
+    let summary = summarize(text);
+
+    if let Some((failure, comment)) = summary.failure_comment {
+        // `no_run` examples are still compiled, so only a comment about a compilation failure is
+        // suspicious there
+        if (!attrs.no_run || failure == Failure::Compile)
+            && let Some(span) = fragments.span(cx, (range.start + comment.start)..(range.start + comment.end))
+        {
+            span_lint_and_help(
+                cx,
+                UNMARKED_FAILING_DOCTEST,
+                span,
+                "this comment says the example fails, but the code block is not marked as failing",
+                None,
+                failure.help(),
+            );
+        }
+        return;
+    }
+
+    if !attrs.no_run && summary.calls_something && !summary.checks_something {
+        let trailing_whitespace = text.len() - text.trim_end().len();
+        if let Some(span) = fragments.span(cx, range.start..range.end - trailing_whitespace) {
+            span_lint_and_then(
+                cx,
+                DOCTEST_WITHOUT_ASSERTIONS,
+                span,
+                "this example never checks the result of the code it runs",
+                |diag| {
+                    if let Some((line_span, sugg)) = assertion_sugg(cx, text, range.start, fragments) {
+                        diag.span_suggestion(
+                            line_span.shrink_to_hi(),
+                            "add an assertion checking the result",
+                            sugg,
+                            Applicability::HasPlaceholders,
+                        );
+                    } else {
+                        diag.help("add an assertion checking the result, e.g. with `assert_eq!`");
+                    }
+                },
+            );
+        }
+    }
+}
+
+/// Returns the span of the last line of the example, and a new line asserting something about the
+/// variable it binds, if any.
+fn assertion_sugg(cx: &LateContext<'_>, text: &str, start: usize, fragments: Fragments<'_>) -> Option<(Span, String)> {
+    let trimmed = text.trim_end();
+    let line_start = trimmed.rfind('\n').map_or(0, |i| i + 1);
+    let line = trimmed[line_start..].trim_start();
+    // The lines hidden with `#`, e.g. the end of a `main` function, can't be followed by the
+    // assertion
+    if line.starts_with('#') {
+        return None;
+    }
+    let line_span = fragments.span(cx, start + trimmed.len() - line.len()..start + trimmed.len())?;
+
+    // The prefix of the line in the source, e.g. `    /// `
+    let sm = cx.sess().source_map();
+    let source_line = sm.lookup_line(line_span.lo()).ok()?;
+    let line_bounds = source_line.sf.line_bounds(source_line.line);
+    let prefix = sm
+        .span_to_snippet(line_span.with_lo(line_bounds.start).with_hi(line_span.lo()))
+        .ok()?;
+
+    let binding = line
+        .strip_prefix("let ")
+        .map(|rest| rest.trim_start_matches("mut "))
+        .and_then(|rest| rest.split([' ', ':', '=']).next())
+        .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_'));
+    let assertion = match binding {
+        Some(name) => format!("assert_eq!({name}, /* expected value */);"),
+        None => "assert!(/* check the result */);".to_string(),
+    };
+    Some((line_span, format!("\n{prefix}{assertion}")))
+}
@@ -32,6 +32,7 @@ use rustc_span::{Span, sym};
 use std::ops::Range;
 use url::Url;
 
+mod doctest_assertions;
 mod empty_line_after;
 mod link_with_quotes;
 mod markdown;
@@ -238,6 +239,84 @@ declare_clippy_lint! {
     "presence of `#[test]` in code examples"
 }
 
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for doctests that call a function but never check anything: they
+    /// contain no `assert*!` or `panic!`, no `?` operator or `unwrap()` and
+    /// print nothing. Calling constructors like `Vec::new()`, conversions like
+    /// `into()` or the functions defined in the example does not count.
+    /// Examples marked `no_run`, `compile_fail` or `should_panic` are ignored.
+    ///
+    /// ### Why restrict this?
+    /// Such an example only tests that the code compiles. It will keep passing
+    /// if the documented function starts returning something else, so it does
+    /// not show (or verify) what a reader can expect from it.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// /// # Examples
+    /// ///
+    /// /// ```
+    /// /// let x = my_crate::double(2);
+    /// /// ```
+    /// pub fn double(x: u32) -> u32 {
+    ///     x * 2
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// /// # Examples
+    /// ///
+    /// /// ```
+    /// /// assert_eq!(my_crate::double(2), 4);
+    /// /// ```
+    /// pub fn double(x: u32) -> u32 {
+    ///     x * 2
+    /// }
+    /// ```
+    #[clippy::version = "1.84.0"]
+    pub DOCTEST_WITHOUT_ASSERTIONS,
+    restriction,
+    "code examples that never assert, propagate an error or print anything"
+}
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for doctests containing a comment saying that the code fails
+    /// (e.g. `// error: ...`, `// this panics`, `// doesn't compile`) which are
+    /// not marked `compile_fail` or `should_panic`.
+    ///
+    /// ### Why is this bad?
+    /// Either the comment is wrong, or the example is expected to fail and
+    /// rustdoc should be told so. Without the marker the failure is not tested
+    /// (or the example does not build at all).
+    ///
+    /// ### Known problems
+    /// The comments are matched against a list of common phrases, so
+    /// unusual wordings are missed.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// /// ```
+    /// /// let v: Vec<u8> = Vec::new();
+    /// /// let x = v[0]; // this panics
+    /// /// ```
+    /// fn indexing() {}
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// /// ```should_panic
+    /// /// let v: Vec<u8> = Vec::new();
+    /// /// let x = v[0]; // this panics
+    /// /// ```
+    /// fn indexing() {}
+    /// ```
+    #[clippy::version = "1.84.0"]
+    pub UNMARKED_FAILING_DOCTEST,
+    pedantic,
+    "code examples commented as failing that are not marked `compile_fail` or `should_panic`"
+}
+
 declare_clippy_lint! {
     /// ### What it does
     /// Detects the syntax `['foo']` in documentation comments (notice quotes instead of backticks)
@@ -554,6 +633,8 @@ impl_lint_pass!(Documentation => [
     MISSING_PANICS_DOC,
     NEEDLESS_DOCTEST_MAIN,
     TEST_ATTR_IN_DOCTEST,
+    DOCTEST_WITHOUT_ASSERTIONS,
+    UNMARKED_FAILING_DOCTEST,
     UNNECESSARY_SAFETY_DOC,
     SUSPICIOUS_DOC_COMMENTS,
     EMPTY_DOCS,
@@ -674,6 +755,14 @@ struct DocHeaders {
     first_paragraph_len: usize,
 }
 
+/// The attributes of a rust code block that change how rustdoc runs it.
+#[derive(Copy, Clone, Default)]
+struct DoctestAttrs {
+    no_run: bool,
+    compile_fail: bool,
+    should_panic: bool,
+}
+
 /// Does some pre-processing on raw, desugared `#[doc]` attributes such as parsing them and
 /// then delegates to `check_doc`.
 /// Some lints are already checked here if they can work with attributes directly and don't need
@@ -770,6 +859,7 @@ fn check_doc<'a, Events: Iterator<Item = (pulldown_cmark::Event<'a>, Range<usize
     let mut is_rust = false;
     let mut no_test = false;
     let mut ignore = false;
+    let mut doctest_attrs = DoctestAttrs::default();
     let mut edition = None;
     let mut ticks_unbalanced = false;
     let mut text_to_check: Vec<(CowStr<'_>, Range<usize>, isize)> = Vec::new();
@@ -815,6 +905,12 @@ fn check_doc<'a, Events: Iterator<Item = (pulldown_cmark::Event<'a>, Range<usize
                         } else if item == "no_run" || item == "compile_fail" {
                             ignore = true;
                         }
+                        match item {
+                            "no_run" => doctest_attrs.no_run = true,
+                            "compile_fail" => doctest_attrs.compile_fail = true,
+                            "should_panic" => doctest_attrs.should_panic = true,
+                            _ => {},
+                        }
                         if let Some(stripped) = item.strip_prefix("edition") {
                             is_rust = true;
                             edition = stripped.parse::<Edition>().ok();
@@ -828,6 +924,7 @@ fn check_doc<'a, Events: Iterator<Item = (pulldown_cmark::Event<'a>, Range<usize
                 in_code = false;
                 is_rust = false;
                 ignore = false;
+                doctest_attrs = DoctestAttrs::default();
             },
             Start(Link { dest_url, .. }) => in_link = Some(dest_url),
             End(TagEnd::Link) => in_link = None,
@@ -925,6 +1022,7 @@ fn check_doc<'a, Events: Iterator<Item = (pulldown_cmark::Event<'a>, Range<usize
                     if is_rust && !no_test {
                         let edition = edition.unwrap_or_else(|| cx.tcx.sess.edition());
                         needless_doctest_main::check(cx, &text, edition, range.clone(), fragments, ignore);
+                        doctest_assertions::check(cx, &text, range.clone(), fragments, doctest_attrs);
                     }
                 } else {
                     if in_link.is_some() {
@@ -1,7 +1,8 @@
-use clippy_utils::diagnostics::span_lint_and_help;
+use clippy_utils::diagnostics::{record_allow, span_lint_and_help};
 use rustc_ast::ast::{Crate, Inline, Item, ItemKind, ModKind};
 use rustc_errors::MultiSpan;
 use rustc_lint::{EarlyContext, EarlyLintPass, Level, LintContext};
+use rustc_middle::lint::LevelAndSource;
 use rustc_session::impl_lint_pass;
 use rustc_span::{FileName, Span};
 use std::collections::BTreeMap;
@@ -45,11 +46,11 @@ declare_clippy_lint! {
     "file loaded as module multiple times"
 }
 
-#[derive(PartialOrd, Ord, PartialEq, Eq)]
+#[derive(PartialEq, Eq)]
 struct Modules {
     local_path: PathBuf,
     spans: Vec<Span>,
-    lint_levels: Vec<Level>,
+    lint_levels: Vec<LevelAndSource>,
 }
 
 #[derive(Default)]
@@ -74,7 +75,7 @@ impl EarlyLintPass for DuplicateMod {
                 lint_levels: Vec::new(),
             });
             modules.spans.push(item.span_with_attributes());
-            modules.lint_levels.push(cx.get_lint_level(DUPLICATE_MOD));
+            modules.lint_levels.push(cx.builder.lint_level(DUPLICATE_MOD));
         }
     }
 
@@ -94,10 +95,11 @@ impl EarlyLintPass for DuplicateMod {
             let spans: Vec<_> = spans
                 .iter()
                 .zip(lint_levels)
-                .filter_map(|(span, lvl)| {
+                .filter_map(|(span, &(lvl, source))| {
                     if let Some(id) = lvl.get_expectation_id() {
                         cx.fulfill_expectation(id);
                     }
+                    record_allow((lvl, source));
 
                     (!matches!(lvl, Level::Allow | Level::Expect(_))).then_some(*span)
                 })
@@ -0,0 +1,253 @@
+//! The state of a lint in the project of the current directory, printed by `cargo clippy --explain`
+//! after the documentation of the lint.
+
+use crate::LintInfo;
+use clippy_config::{ClippyConfiguration, lookup_conf_file};
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+use toml::{Table, Value};
+
+/// The values set in the configuration file of the project
+pub(crate) struct ConfFile {
+    path: PathBuf,
+    values: Table,
+}
+
+impl ConfFile {
+    /// Reads the configuration file found by `lookup_conf_file`, like `clippy-driver` does
+    pub(crate) fn read() -> Option<Self> {
+        let (Some(path), _) = lookup_conf_file().ok()? else {
+            return None;
+        };
+        if let Ok(Ok(values)) = fs::read_to_string(&path).map(|contents| contents.parse()) {
+            Some(Self { path, values })
+        } else {
+            println!("(could not read `{}`)\n", path.display());
+            None
+        }
+    }
+
+    /// Describes the value of `conf` used in the project, the one set in the file or the default
+    /// value
+    fn describe_value(conf_file: Option<&Self>, conf: &ClippyConfiguration) -> String {
+        match conf_file {
+            Some(file) => match file.values.get(&conf.name) {
+                Some(value) => format!("(set to `{value}` in `{}`)", file.path.display()),
+                None => format!(
+                    "(the default value `{}` is used, it is not set in `{}`)",
+                    conf.default,
+                    file.path.display()
+                ),
+            },
+            None => format!(
+                "(the default value `{}` is used, no `clippy.toml` was found)",
+                conf.default
+            ),
+        }
+    }
+
+    /// Prints the value of `conf` used in the project
+    pub(crate) fn print_value(conf_file: Option<&Self>, conf: &ClippyConfiguration) {
+        println!("   {}", Self::describe_value(conf_file, conf));
+    }
+}
+
+/// A level set for the lint in a `[lints]` table of a `Cargo.toml`
+struct LevelSource {
+    level: String,
+    /// The lint or group the level is set for, as it's written in the table
+    key: String,
+    table: &'static str,
+    manifest: PathBuf,
+}
+
+/// Prints the level of the lint in the `[lints]` table of the package in the current directory, and
+/// whether it is enabled for that package
+pub(crate) fn print_status(info: &LintInfo) {
+    let Some((manifest_path, manifest)) = env::current_dir().ok().and_then(|dir| find_manifest(&dir, |_| true)) else {
+        return;
+    };
+    let Some(package) = manifest
+        .get("package")
+        .and_then(|package| package.get("name"))
+        .and_then(Value::as_str)
+    else {
+        return;
+    };
+
+    let uses_workspace_lints = manifest
+        .get("lints")
+        .and_then(|lints| lints.get("workspace"))
+        .and_then(Value::as_bool)
+        .unwrap_or_default();
+    let workspace = if uses_workspace_lints {
+        manifest_path
+            .parent()
+            .and_then(|dir| find_manifest(dir, |manifest| manifest.contains_key("workspace")))
+    } else {
+        None
+    };
+    let (lints, lints_manifest, table) = match &workspace {
+        Some((workspace_path, workspace)) => (
+            workspace.get("workspace").and_then(|workspace| workspace.get("lints")),
+            workspace_path,
+            "[workspace.lints.clippy]",
+        ),
+        None if uses_workspace_lints => (None, &manifest_path, "[lints.clippy]"),
+        None => (manifest.get("lints"), &manifest_path, "[lints.clippy]"),
+    };
+
+    let source = lints
+        .and_then(|lints| lints.get("clippy"))
+        .and_then(Value::as_table)
+        .and_then(|clippy| level_source(info, clippy))
+        .map(|(level, key)| LevelSource {
+            level,
+            key,
+            table,
+            manifest: lints_manifest.clone(),
+        });
+
+    println!("### Status in package `{package}`:\n");
+    let level = if let Some(source) = &source {
+        println!(
+            "- level: `{}`, set for `{}` in the `{}` table of `{}`",
+            source.level,
+            source.key,
+            source.table,
+            source.manifest.display()
+        );
+        source.level.as_str()
+    } else {
+        let level = info.lint.default_level.as_str();
+        println!("- level: `{level}`, the default level of the lint");
+        level
+    };
+    if level == "allow" {
+        println!("- the lint is not enabled, unless it is enabled in the code or on the command line");
+    } else {
+        println!("- the lint is enabled, unless it is allowed in the code or on the command line");
+    }
+}
+
+/// Returns the level of the lint set in the `clippy` table of `[lints]`, with the key of the lint
+/// or the group it is set for.
+fn level_source(info: &LintInfo, clippy: &Table) -> Option<(String, String)> {
+    let mut names = vec![info.name_lower(), info.category_str().to_string()];
+    if info.category.is_all() {
+        names.push("all".to_string());
+    }
+    clippy
+        .iter()
+        .filter(|(key, _)| names.contains(&key.replace('-', "_")))
+        .filter_map(|(key, config)| {
+            let (level, priority) = match config {
+                Value::String(level) => (level.as_str(), 0),
+                Value::Table(config) => (
+                    config.get("level")?.as_str()?,
+                    config.get("priority").and_then(Value::as_integer).unwrap_or_default(),
+                ),
+                _ => return None,
+            };
+            Some((level, priority, key))
+        })
+        // Cargo passes the levels sorted by priority, then by name, the last one wins
+        .max_by(|a, b| (a.1, a.2).cmp(&(b.1, b.2)))
+        .map(|(level, _, key)| (level.to_string(), key.clone()))
+}
+
+/// Finds the first `Cargo.toml` in `dir` or its parents accepted by `filter`
+fn find_manifest(dir: &Path, filter: impl Fn(&Table) -> bool) -> Option<(PathBuf, Table)> {
+    dir.ancestors().find_map(|dir| {
+        let path = dir.join("Cargo.toml");
+        let manifest: Table = fs::read_to_string(&path).ok()?.parse().ok()?;
+        filter(&manifest).then_some((path, manifest))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfFile, level_source};
+    use crate::declared_lints::LINTS;
+    use clippy_config::ClippyConfiguration;
+    use std::path::PathBuf;
+
+    fn level(lint: &str, table: &str) -> Option<(String, String)> {
+        let info = LINTS.iter().find(|info| info.name_lower() == lint).unwrap();
+        level_source(info, &table.parse().unwrap())
+    }
+
+    #[test]
+    fn level_of_lint() {
+        assert_eq!(level("needless_return", ""), None);
+        assert_eq!(level("needless_return", "len_zero = \"deny\""), None);
+        assert_eq!(
+            level("needless_return", "needless-return = \"deny\""),
+            Some(("deny".into(), "needless-return".into()))
+        );
+        assert_eq!(
+            level(
+                "needless_return",
+                "needless_return = { level = \"forbid\", priority = 1 }"
+            ),
+            Some(("forbid".into(), "needless_return".into()))
+        );
+    }
+
+    #[test]
+    fn level_of_group() {
+        assert_eq!(
+            level("needless_return", "style = \"deny\""),
+            Some(("deny".into(), "style".into()))
+        );
+        assert_eq!(
+            level("needless_return", "all = \"deny\""),
+            Some(("deny".into(), "all".into()))
+        );
+        assert_eq!(level("implicit_return", "all = \"deny\""), None);
+        // The levels with a higher priority win, then the last one in the order of the names
+        assert_eq!(
+            level("needless_return", "needless_return = \"allow\"\nstyle = \"deny\""),
+            Some(("deny".into(), "style".into()))
+        );
+        assert_eq!(
+            level(
+                "needless_return",
+                "needless_return = { level = \"allow\", priority = 1 }\nstyle = \"deny\""
+            ),
+            Some(("allow".into(), "needless_return".into()))
+        );
+    }
+
+    #[test]
+    fn value_of_configuration() {
+        let conf = ClippyConfiguration {
+            name: "too-many-arguments-threshold".into(),
+            default: "7".into(),
+            lints: &["too_many_arguments"],
+            doc: "",
+            deprecation_reason: None,
+        };
+        let file = ConfFile {
+            path: PathBuf::from("clippy.toml"),
+            values: "too-many-arguments-threshold = 3".parse().unwrap(),
+        };
+        assert_eq!(
+            ConfFile::describe_value(Some(&file), &conf),
+            "(set to `3` in `clippy.toml`)"
+        );
+
+        let file = ConfFile {
+            path: PathBuf::from("clippy.toml"),
+            values: "msrv = \"1.50\"".parse().unwrap(),
+        };
+        assert_eq!(
+            ConfFile::describe_value(Some(&file), &conf),
+            "(the default value `7` is used, it is not set in `clippy.toml`)"
+        );
+        assert_eq!(
+            ConfFile::describe_value(None, &conf),
+            "(the default value `7` is used, no `clippy.toml` was found)"
+        );
+    }
+}
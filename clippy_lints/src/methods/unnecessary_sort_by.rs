@@ -1,6 +1,6 @@
-use clippy_utils::diagnostics::span_lint_and_sugg;
+use clippy_utils::diagnostics::{span_lint_and_sugg, span_lint_and_sugg_group};
 use clippy_utils::is_trait_method;
-use clippy_utils::sugg::Sugg;
+use clippy_utils::sugg::{Sugg, SuggGroup};
 use clippy_utils::ty::implements_trait;
 use rustc_errors::Applicability;
 use rustc_hir::{Closure, Expr, ExprKind, Mutability, Param, Pat, PatKind, Path, PathSegment, QPath};
@@ -201,29 +201,37 @@ pub(super) fn check<'tcx>(
     is_unstable: bool,
 ) {
     match detect_lint(cx, expr, recv, arg) {
-        Some(LintTrigger::SortByKey(trigger)) => span_lint_and_sugg(
-            cx,
-            UNNECESSARY_SORT_BY,
-            expr.span,
-            "consider using `sort_by_key`",
-            "try",
-            format!(
-                "{}.sort{}_by_key(|{}| {})",
-                trigger.vec_name,
-                if is_unstable { "_unstable" } else { "" },
-                trigger.closure_arg,
+        Some(LintTrigger::SortByKey(trigger)) => {
+            let mut sugg = SuggGroup::default();
+            let key = if trigger.reverse {
+                let reverse = sugg.import(cx, expr.hir_id, &["std", "cmp", "Reverse"]);
+                format!("{reverse}({})", trigger.closure_body)
+            } else {
+                trigger.closure_body.to_string()
+            };
+            sugg.replace(
+                expr.span,
+                format!(
+                    "{}.sort{}_by_key(|{}| {key})",
+                    trigger.vec_name,
+                    if is_unstable { "_unstable" } else { "" },
+                    trigger.closure_arg,
+                ),
+            );
+            span_lint_and_sugg_group(
+                cx,
+                UNNECESSARY_SORT_BY,
+                expr.span,
+                "consider using `sort_by_key`",
+                "try",
+                sugg,
                 if trigger.reverse {
-                    format!("std::cmp::Reverse({})", trigger.closure_body)
+                    Applicability::MaybeIncorrect
                 } else {
-                    trigger.closure_body.to_string()
+                    Applicability::MachineApplicable
                 },
-            ),
-            if trigger.reverse {
-                Applicability::MaybeIncorrect
-            } else {
-                Applicability::MachineApplicable
-            },
-        ),
+            );
+        },
         Some(LintTrigger::Sort(trigger)) => span_lint_and_sugg(
             cx,
             UNNECESSARY_SORT_BY,
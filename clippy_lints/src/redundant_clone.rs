@@ -1,6 +1,6 @@
 use clippy_utils::diagnostics::{span_lint_hir, span_lint_hir_and_then};
 use clippy_utils::fn_has_unsatisfiable_preds;
-use clippy_utils::mir::{LocalUsage, PossibleBorrowerMap, visit_local_usage};
+use clippy_utils::mir::{LocalUsage, PossibleBorrowerMap, analysis_mir, visit_local_usage};
 use clippy_utils::source::SpanRangeExt;
 use clippy_utils::ty::{has_drop, is_copy, is_type_diagnostic_item, is_type_lang_item, walk_ptrs_ty_depth};
 use rustc_errors::Applicability;
@@ -77,7 +77,7 @@ impl<'tcx> LateLintPass<'tcx> for RedundantClone {
             return;
         }
 
-        let mir = cx.tcx.optimized_mir(def_id.to_def_id());
+        let mir = analysis_mir(cx.tcx, def_id);
 
         let mut possible_borrower = PossibleBorrowerMap::new(cx, mir);
 
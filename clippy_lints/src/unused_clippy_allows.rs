@@ -0,0 +1,271 @@
+use clippy_utils::diagnostics::{allow_was_used, allowed_emissions, span_lint_hir, span_lint_hir_and_then};
+use clippy_utils::source::{IntoSpan, SpanRangeExt};
+use rustc_ast::{Attribute, MetaItemInner};
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_errors::Applicability;
+use rustc_hir::def::DefKind;
+use rustc_hir::{CRATE_HIR_ID, HirId, Item, ItemKind, Node};
+use rustc_lint::{LateContext, LateLintPass, LintContext, LintId, unerased_lint_store};
+use rustc_middle::lint::in_external_macro;
+use rustc_middle::ty::TyCtxt;
+use rustc_session::impl_lint_pass;
+use rustc_span::{Span, Symbol, sym};
+
+declare_clippy_lint! {
+    /// ### What it does
+    /// Checks for `#[allow(clippy::..)]` attributes which did not suppress any
+    /// lint emission.
+    ///
+    /// This lint can also be enabled with `cargo clippy --report-unused-allows`.
+    ///
+    /// ### Why restrict this?
+    /// Code changes and lint fixes over time, so an `allow` which was needed
+    /// once may now only hide future occurrences of the lint. Unlike
+    /// `#[expect]`, `#[allow]` never tells you when it is no longer needed.
+    /// See also [`allow_attributes`](#allow_attributes).
+    ///
+    /// ### Known problems
+    /// Only the code that was compiled is checked: an `allow` which is needed
+    /// for another target, set of features or `cfg` is reported as well.
+    ///
+    /// Lints which skip their analysis when they are allowed never see the
+    /// emission the `allow` would suppress, so such an `allow` is reported even
+    /// when it is needed. Use `#[expect]` there instead.
+    ///
+    /// For most lints, the `allow` which suppressed an emission is found from
+    /// the span of the emission. An `allow` of the lint around a span which is
+    /// allowed by a lint group instead is considered used.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// #[allow(clippy::needless_return)]
+    /// fn f() -> u32 {
+    ///     1
+    /// }
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// fn f() -> u32 {
+    ///     1
+    /// }
+    /// ```
+    #[clippy::version = "1.84.0"]
+    pub UNUSED_CLIPPY_ALLOWS,
+    restriction,
+    "`#[allow(clippy::..)]` attributes which don't suppress any lint"
+}
+
+enum Entry {
+    /// A single clippy lint, which can be tracked.
+    Lint(LintId, Symbol),
+    /// A lint group, another tool's lint or this lint itself.
+    Other,
+    /// The `reason = ".."` entry.
+    Reason,
+}
+
+struct AllowAttr {
+    hir_id: HirId,
+    span: Span,
+    /// Whether the attribute was written as `#[allow(..)]`, and not e.g. expanded from `cfg_attr`.
+    is_plain: bool,
+    entries: Vec<(Span, Entry)>,
+}
+
+/// Registered by `register_final_passes` after all other passes, so that all lint emissions have
+/// been seen by the time `check_crate_post` is called.
+#[derive(Default)]
+pub struct UnusedClippyAllows {
+    attrs: Vec<AllowAttr>,
+}
+
+impl_lint_pass!(UnusedClippyAllows => [UNUSED_CLIPPY_ALLOWS]);
+
+impl UnusedClippyAllows {
+    /// Finds the entries of the `#[allow]` attributes which suppressed the lints allowed at a span,
+    /// see `allowed_emissions`. The attribute is the one of the innermost node containing the span,
+    /// or of one of its parents, which allows the lint.
+    fn allows_of_emissions(&self, tcx: TyCtxt<'_>) -> FxHashSet<Span> {
+        let mut entries = FxHashMap::<HirId, Vec<(LintId, Span)>>::default();
+        for attr in &self.attrs {
+            for (span, entry) in &attr.entries {
+                if let Entry::Lint(lint, _) = entry {
+                    entries.entry(attr.hir_id).or_default().push((*lint, *span));
+                }
+            }
+        }
+
+        let mut used = FxHashSet::default();
+        let emissions = allowed_emissions();
+        if emissions.is_empty() || entries.is_empty() {
+            return used;
+        }
+
+        // The modules are needed as well, their items may be in another file than their parent
+        let nodes: Vec<(HirId, Span)> = self
+            .attrs
+            .iter()
+            .map(|attr| attr.hir_id)
+            .chain(
+                tcx.hir_crate_items(())
+                    .free_items()
+                    .filter(|item| tcx.def_kind(item.owner_id) == DefKind::Mod)
+                    .map(|item| item.hir_id()),
+            )
+            .chain([CRATE_HIR_ID])
+            .map(|hir_id| (hir_id, node_span(tcx, hir_id)))
+            .collect();
+
+        for (lint, span) in emissions {
+            let span = span.source_callsite();
+            let Some(&(innermost, _)) = nodes
+                .iter()
+                .filter(|(_, node)| node.contains(span))
+                .min_by_key(|(_, node)| node.hi() - node.lo())
+            else {
+                continue;
+            };
+            let allow = [innermost]
+                .into_iter()
+                .chain(tcx.hir().parent_id_iter(innermost))
+                .find_map(|hir_id| entries.get(&hir_id)?.iter().find(|(allowed, _)| *allowed == lint));
+            if let Some(&(_, entry)) = allow {
+                used.insert(entry);
+            }
+        }
+        used
+    }
+}
+
+/// Returns the span of the code an attribute of the node applies to, the contents of a module
+/// may be in another file.
+fn node_span(tcx: TyCtxt<'_>, hir_id: HirId) -> Span {
+    match tcx.hir_node(hir_id) {
+        Node::Item(Item {
+            kind: ItemKind::Mod(module),
+            ..
+        }) => module.spans.inner_span,
+        Node::Crate(module) => module.spans.inner_span,
+        _ => tcx.hir().span_with_body(hir_id),
+    }
+}
+
+impl<'tcx> LateLintPass<'tcx> for UnusedClippyAllows {
+    fn check_attribute(&mut self, cx: &LateContext<'tcx>, attr: &'tcx Attribute) {
+        if !attr.has_name(sym::allow)
+            || attr.span.from_expansion()
+            || in_external_macro(cx.sess(), attr.span)
+            // The attributes of a `let` statement are visited for both the statement and the local
+            || self.attrs.last().is_some_and(|last| last.span == attr.span)
+        {
+            return;
+        }
+        let Some(items) = attr.meta_item_list() else {
+            return;
+        };
+
+        let store = unerased_lint_store(cx.tcx.sess);
+        let entries = items
+            .iter()
+            .map(|item| {
+                let entry = match item {
+                    MetaItemInner::MetaItem(mi) if mi.has_name(sym::reason) => Entry::Reason,
+                    MetaItemInner::MetaItem(mi)
+                        if mi.is_word()
+                            && let [tool, name] = &*mi.path.segments
+                            && tool.ident.name == sym::clippy =>
+                    {
+                        let full_name = pprust::path_to_string(&mi.path);
+                        match store.find_lints(&full_name).as_deref() {
+                            Ok(&[lint])
+                                if lint != LintId::of(UNUSED_CLIPPY_ALLOWS)
+                                    && !store.is_lint_group(Symbol::intern(&full_name)) =>
+                            {
+                                Entry::Lint(lint, name.ident.name)
+                            },
+                            _ => Entry::Other,
+                        }
+                    },
+                    _ => Entry::Other,
+                };
+                (item.span(), entry)
+            })
+            .collect();
+
+        self.attrs.push(AllowAttr {
+            hir_id: cx.last_node_with_lint_attrs,
+            span: attr.span,
+            is_plain: attr.span.check_source_text(cx, |src| src.starts_with('#')),
+            entries,
+        });
+    }
+
+    fn check_crate_post(&mut self, cx: &LateContext<'tcx>) {
+        // The passes of these lints may not have run at all
+        let not_run = cx.tcx.lints_that_dont_need_to_run(());
+        let used = self.allows_of_emissions(cx.tcx);
+
+        for attr in &self.attrs {
+            let is_unused = |(span, entry): &(Span, Entry)| match entry {
+                Entry::Lint(lint, _) => !not_run.contains(lint) && !allow_was_used(*span) && !used.contains(span),
+                Entry::Other | Entry::Reason => false,
+            };
+
+            if !attr.entries.iter().any(is_unused) {
+                continue;
+            }
+            if attr
+                .entries
+                .iter()
+                .all(|entry| is_unused(entry) || matches!(entry.1, Entry::Reason))
+            {
+                let msg = "this `allow` attribute does not suppress any lint";
+                if attr.is_plain {
+                    span_lint_hir_and_then(cx, UNUSED_CLIPPY_ALLOWS, attr.hir_id, attr.span, msg, |diag| {
+                        // Also remove the indentation of the next line
+                        let removal = attr
+                            .span
+                            .map_range(cx, |src, range| {
+                                let rest = src.get(range.end..)?;
+                                Some(range.start..range.end + (rest.len() - rest.trim_start().len()))
+                            })
+                            .map_or(attr.span, IntoSpan::into_span);
+                        diag.span_suggestion(removal, "remove the attribute", "", Applicability::MaybeIncorrect);
+                    });
+                } else {
+                    span_lint_hir(cx, UNUSED_CLIPPY_ALLOWS, attr.hir_id, attr.span, msg);
+                }
+                continue;
+            }
+
+            for (i, entry @ (span, kind)) in attr.entries.iter().enumerate() {
+                let Entry::Lint(_, name) = kind else {
+                    continue;
+                };
+                if !is_unused(entry) {
+                    continue;
+                }
+
+                // Remove the comma before the entry if something before it is kept, otherwise the
+                // one after it.
+                let removal = if attr.entries[..i].iter().any(|entry| !is_unused(entry)) {
+                    span.with_lo(attr.entries[i - 1].0.hi())
+                } else {
+                    span.with_hi(attr.entries[i + 1].0.lo())
+                };
+
+                span_lint_hir_and_then(
+                    cx,
+                    UNUSED_CLIPPY_ALLOWS,
+                    attr.hir_id,
+                    *span,
+                    format!("`clippy::{name}` is allowed here, but it does not suppress any lint"),
+                    |diag| {
+                        diag.span_suggestion(removal, "remove it", "", Applicability::MaybeIncorrect);
+                    },
+                );
+            }
+        }
+    }
+}
@@ -1,16 +1,17 @@
 use clippy_utils::{get_attr, higher};
-use rustc_ast::LitIntType;
 use rustc_ast::ast::{LitFloatType, LitKind};
+use rustc_ast::{Attribute, LitIntType};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_hir::{
     self as hir, ArrayLen, BindingMode, CaptureBy, Closure, ClosureKind, ConstArg, ConstArgKind, CoroutineKind,
     ExprKind, FnRetTy, HirId, Lit, PatKind, QPath, StmtKind, TyKind,
 };
 use rustc_lint::{LateContext, LateLintPass, LintContext};
-use rustc_session::declare_lint_pass;
+use rustc_session::{Session, declare_lint_pass};
 use rustc_span::symbol::{Ident, Symbol};
-use std::cell::Cell;
-use std::fmt::{Display, Formatter, Write as _};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Arguments, Display, Formatter, Write as _};
 
 declare_lint_pass!(
     /// ### What it does
@@ -31,26 +32,35 @@ declare_lint_pass!(
     /// ```
     ///
     /// Running `TESTNAME=ui/my_lint cargo uitest` will produce
-    /// a `./tests/ui/new_lint.stdout` file with the generated code:
+    /// a `./tests/ui/new_lint.stdout` file with a lint whose `LateLintPass`
+    /// checks for the pattern:
     ///
     /// ```rust,ignore
     /// // ./tests/ui/new_lint.stdout
-    /// if ExprKind::If(ref cond, ref then, None) = item.kind
-    ///     && let ExprKind::Binary(BinOp::Eq, ref left, ref right) = cond.kind
-    ///     && let ExprKind::Path(ref path) = left.kind
-    ///     && let ExprKind::Lit(ref lit) = right.kind
-    ///     && let LitKind::Int(42, _) = lit.node
-    /// {
-    ///     // report your lint here
+    /// impl<'tcx> LateLintPass<'tcx> for LintName {
+    ///     fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx Expr<'_>) {
+    ///         if let ExprKind::If(cond, then, None) = expr.kind
+    ///             && let ExprKind::Binary(op, left, right) = cond.kind
+    ///             && BinOpKind::Eq == op.node
+    ///             && let ExprKind::Path(ref qpath) = left.kind
+    ///             && let ExprKind::Lit(ref lit) = right.kind
+    ///             && let LitKind::Int(42, LitIntType::Unsuffixed) = lit.node
+    ///         {
+    ///             // report your lint here, with `span_lint_and_sugg`
+    ///         }
+    ///     }
     /// }
     /// ```
+    ///
+    /// `#[clippy::author(ast)]` generates an `EarlyLintPass` matching the AST
+    /// instead, see `author::ast::AuthorAst`.
     Author => []
 );
 
 /// Writes a line of output with indentation added
 macro_rules! out {
-    ($($t:tt)*) => {
-        println!("    {}", format_args!($($t)*))
+    ($self:ident, $($t:tt)*) => {
+        $self.chain.comment(format_args!($($t)*))
     };
 }
 
@@ -60,7 +70,7 @@ macro_rules! out {
 /// Note that the `Binding` may be printed directly to output the `name`.
 macro_rules! bind {
     ($self:ident $(, $name:ident)+) => {
-        $(let $name = & $self.bind(stringify!($name), $name);)+
+        $(let $name = & $self.chain.bind(stringify!($name), $name);)+
     };
 }
 
@@ -69,7 +79,7 @@ macro_rules! bind {
 /// is set to the name of the variable passed to the macro.
 macro_rules! opt_bind {
     ($self:ident $(, $name:ident)+) => {
-        $(let $name = OptionPat::new($name.map(|o| $self.bind(stringify!($name), o)));)+
+        $(let $name = OptionPat::new($name.map(|o| $self.chain.bind(stringify!($name), o)));)+
     };
 }
 
@@ -87,36 +97,73 @@ macro_rules! field {
 /// `if let Some(x) = y` on the first call and `    && let Some(x) = y` thereafter
 macro_rules! chain {
     ($self:ident, $($t:tt)*) => {
-        if $self.first.take() {
-            println!("if {}", format_args!($($t)*));
-        } else {
-            println!("    && {}", format_args!($($t)*));
-        }
+        $self.chain.push(format_args!($($t)*))
     }
 }
 
+pub mod ast;
+
+/// The items of `rustc_hir` and other crates which may be used by the generated conditions
+const HIR_IMPORTS: &[(&str, &[&str])] = &[
+    ("clippy_utils", &["higher", "match_qpath"]),
+    ("rustc_ast", &[
+        "FloatTy",
+        "IntTy",
+        "LitFloatType",
+        "LitIntType",
+        "LitKind",
+        "UintTy",
+    ]),
+    ("rustc_hir", &[
+        "ArrayLen",
+        "BinOpKind",
+        "BindingMode",
+        "BorrowKind",
+        "CaptureBy",
+        "ClosureKind",
+        "ConstArgKind",
+        "CoroutineDesugaring",
+        "CoroutineKind",
+        "CoroutineSource",
+        "ExprKind",
+        "FnRetTy",
+        "LangItem",
+        "LoopSource",
+        "MatchSource",
+        "Movability",
+        "Mutability",
+        "PatKind",
+        "QPath",
+        "RangeEnd",
+        "StmtKind",
+        "TyKind",
+        "UnOp",
+        "YieldSource",
+    ]),
+];
+
 impl<'tcx> LateLintPass<'tcx> for Author {
     fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'_>) {
-        check_item(cx, item.hir_id());
+        check_item(cx, item.hir_id(), &Hook::new("check_item", "item", "Item"));
     }
 
     fn check_impl_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::ImplItem<'_>) {
-        check_item(cx, item.hir_id());
+        check_item(cx, item.hir_id(), &Hook::new("check_impl_item", "item", "ImplItem"));
     }
 
     fn check_trait_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::TraitItem<'_>) {
-        check_item(cx, item.hir_id());
+        check_item(cx, item.hir_id(), &Hook::new("check_trait_item", "item", "TraitItem"));
     }
 
     fn check_arm(&mut self, cx: &LateContext<'tcx>, arm: &'tcx hir::Arm<'_>) {
-        check_node(cx, arm.hir_id, |v| {
-            v.arm(&v.bind("arm", arm));
+        check_node(cx, arm.hir_id, &Hook::new("check_arm", "arm", "Arm"), |v| {
+            v.arm(&v.chain.bind("arm", arm));
         });
     }
 
     fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'_>) {
-        check_node(cx, expr.hir_id, |v| {
-            v.expr(&v.bind("expr", expr));
+        check_node(cx, expr.hir_id, &Hook::new("check_expr", "expr", "Expr"), |v| {
+            v.expr(&v.chain.bind("expr", expr));
         });
     }
 
@@ -125,28 +172,176 @@ impl<'tcx> LateLintPass<'tcx> for Author {
             StmtKind::Expr(e) | StmtKind::Semi(e) if has_attr(cx, e.hir_id) => return,
             _ => {},
         }
-        check_node(cx, stmt.hir_id, |v| {
-            v.stmt(&v.bind("stmt", stmt));
+        check_node(cx, stmt.hir_id, &Hook::new("check_stmt", "stmt", "Stmt"), |v| {
+            v.stmt(&v.chain.bind("stmt", stmt));
         });
     }
 }
 
-fn check_item(cx: &LateContext<'_>, hir_id: HirId) {
+fn check_item(cx: &LateContext<'_>, hir_id: HirId, hook: &Hook) {
     let hir = cx.tcx.hir();
     if let Some(body) = hir.maybe_body_owned_by(hir_id.expect_owner().def_id) {
-        check_node(cx, hir_id, |v| {
-            v.expr(&v.bind("expr", body.value));
+        check_node(cx, hir_id, hook, |v| {
+            let item = v.chain.next("item");
+            let body = v.chain.bind("body", body);
+            chain!(
+                v,
+                "let Some({body}) = cx.tcx.hir().maybe_body_owned_by({item}.owner_id.def_id)"
+            );
+            v.expr(&Binding {
+                name: format!("{body}.value"),
+                value: body.value.value,
+            });
         });
     }
 }
 
-fn check_node(cx: &LateContext<'_>, hir_id: HirId, f: impl Fn(&PrintVisitor<'_, '_>)) {
+fn check_node(cx: &LateContext<'_>, hir_id: HirId, hook: &Hook, f: impl Fn(&PrintVisitor<'_, '_>)) {
     if has_attr(cx, hir_id) {
-        f(&PrintVisitor::new(cx));
-        println!("{{");
-        println!("    // report your lint here");
-        println!("}}");
+        let v = PrintVisitor::new(cx);
+        f(&v);
+        print_lint(hook, &v.chain, false, HIR_IMPORTS);
+    }
+}
+
+/// The `check_*` method of the generated lint pass
+struct Hook {
+    /// The name of the method, e.g. `check_expr`
+    name: &'static str,
+    /// The name of the checked node, e.g. `expr`
+    node: &'static str,
+    /// The type of the checked node, e.g. `Expr`
+    ty: &'static str,
+}
+
+impl Hook {
+    fn new(name: &'static str, node: &'static str, ty: &'static str) -> Self {
+        Self { name, node, ty }
+    }
+}
+
+/// Prints a lint whose early or late lint pass implements `hook` with the conditions of the chain,
+/// and suggests replacing the first matched expression. The items which are used by the
+/// conditions are imported from the crates of `imports`.
+fn print_lint(hook: &Hook, chain: &Chain, early: bool, imports: &[(&'static str, &[&'static str])]) {
+    let conditions = chain.text.take();
+    let sugg_expr = chain.sugg_expr.take();
+    let Hook { name, node, ty } = *hook;
+
+    let mut uses: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut import = |path, item| {
+        uses.entry(path).or_default().insert(item);
+    };
+    import("clippy_utils::diagnostics", "span_lint_and_sugg");
+    import("rustc_errors", "Applicability");
+    import("rustc_session", "declare_lint_pass");
+    if sugg_expr.is_some() {
+        import("clippy_utils::sugg", "Sugg");
+    } else {
+        import("clippy_utils::source", "snippet_with_applicability");
+    }
+    if early {
+        import("rustc_ast", ty);
+        import("rustc_lint", "EarlyContext");
+        import("rustc_lint", "EarlyLintPass");
+    } else {
+        import("rustc_hir", ty);
+        import("rustc_lint", "LateContext");
+        import("rustc_lint", "LateLintPass");
     }
+    for &(path, items) in imports {
+        for &item in items {
+            if is_used(&conditions, item) {
+                import(path, item);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    for (path, items) in uses {
+        let items: Vec<_> = items.into_iter().collect();
+        if let [item] = *items {
+            writeln!(out, "use {path}::{item};").unwrap();
+        } else {
+            writeln!(out, "use {path}::{{{}}};", items.join(", ")).unwrap();
+        }
+    }
+    out.push_str(
+        r#"
+declare_clippy_lint! {
+    /// ### What it does
+    ///
+    /// ### Why is this bad?
+    ///
+    /// ### Example
+    /// ```no_run
+    /// // example code where clippy issues a warning
+    /// ```
+    /// Use instead:
+    /// ```no_run
+    /// // example code which does not raise clippy warning
+    /// ```
+    #[clippy::version = "1.XX.0"]
+    pub LINT_NAME,
+    nursery,
+    "default lint description"
+}
+
+declare_lint_pass!(LintName => [LINT_NAME]);
+
+"#,
+    );
+    if early {
+        out.push_str("impl EarlyLintPass for LintName {\n");
+        writeln!(out, "    fn {name}(&mut self, cx: &EarlyContext<'_>, {node}: &{ty}) {{").unwrap();
+    } else {
+        out.push_str("impl<'tcx> LateLintPass<'tcx> for LintName {\n");
+        writeln!(
+            out,
+            "    fn {name}(&mut self, cx: &LateContext<'tcx>, {node}: &'tcx {ty}<'_>) {{"
+        )
+        .unwrap();
+    }
+    for line in conditions.lines() {
+        writeln!(out, "        {line}").unwrap();
+    }
+    out.push_str("        {\n");
+    if !early && let Some(expr) = &sugg_expr {
+        out.push_str("            // Check the types with `clippy_utils::ty`, e.g.\n");
+        writeln!(out, "            // let ty = cx.typeck_results().expr_ty({expr});").unwrap();
+        out.push_str("            // if clippy_utils::ty::is_type_diagnostic_item(cx, ty, sym::Option) { .. }\n");
+    }
+    out.push_str("            let mut applicability = Applicability::MachineApplicable;\n");
+    let sugg = match (&sugg_expr, early) {
+        (Some(expr), false) => format!("Sugg::hir_with_applicability(cx, {expr}, \"..\", &mut applicability)"),
+        (Some(expr), true) => {
+            format!("Sugg::ast(cx, {expr}, \"..\", {node}.span.ctxt(), &mut applicability)")
+        },
+        (None, _) => format!("snippet_with_applicability(cx, {node}.span, \"..\", &mut applicability)"),
+    };
+    writeln!(out, "            let sugg = {sugg};").unwrap();
+    writeln!(
+        out,
+        r#"            span_lint_and_sugg(
+                cx,
+                LINT_NAME,
+                {node}.span,
+                "your lint message",
+                "try",
+                sugg.to_string(),
+                applicability,
+            );"#
+    )
+    .unwrap();
+    out.push_str("        }\n    }\n}");
+    println!("{out}");
+}
+
+/// Whether `ident` is used in the code, not as part of another identifier
+fn is_used(code: &str, ident: &str) -> bool {
+    let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+    code.match_indices(ident)
+        .any(|(pos, _)| !code[..pos].ends_with(is_ident_char) && !code[pos + ident.len()..].starts_with(is_ident_char))
 }
 
 struct Binding<T> {
@@ -185,25 +380,42 @@ impl<T: Display> Display for OptionPat<T> {
     }
 }
 
-struct PrintVisitor<'a, 'tcx> {
-    cx: &'a LateContext<'tcx>,
+/// The conditions of the let chain matching a node
+#[derive(Default)]
+struct Chain {
     /// Fields are the current index that needs to be appended to pattern
     /// binding names
     ids: Cell<FxHashMap<&'static str, u32>>,
-    /// Currently at the first condition in the if chain
-    first: Cell<bool>,
+    /// Currently past the first condition in the if chain
+    started: Cell<bool>,
+    /// The conditions, one per line
+    text: RefCell<String>,
+    /// The binding of the first expression matched
+    sugg_expr: RefCell<Option<String>>,
 }
 
-#[allow(clippy::unused_self)]
-impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
-    fn new(cx: &'a LateContext<'tcx>) -> Self {
-        Self {
-            cx,
-            ids: Cell::default(),
-            first: Cell::new(true),
+impl Chain {
+    /// Adds a condition, `if let Some(x) = y` on the first call and `    && let Some(x) = y`
+    /// thereafter
+    fn push(&self, condition: Arguments<'_>) {
+        let mut text = self.text.borrow_mut();
+        if self.started.replace(true) {
+            writeln!(text, "    && {condition}").unwrap();
+        } else {
+            writeln!(text, "if {condition}").unwrap();
         }
     }
 
+    /// Adds a line with indentation added
+    fn comment(&self, line: Arguments<'_>) {
+        writeln!(self.text.borrow_mut(), "    {line}").unwrap();
+    }
+
+    /// Records the binding of a matched expression, the suggestion is made from the first one
+    fn matched_expr(&self, expr: &impl Display) {
+        self.sugg_expr.borrow_mut().get_or_insert_with(|| expr.to_string());
+    }
+
     fn next(&self, s: &'static str) -> String {
         let mut ids = self.ids.take();
         let out = match *ids.entry(s).and_modify(|n| *n += 1).or_default() {
@@ -223,10 +435,10 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
 
     fn option<T: Copy>(&self, option: &Binding<Option<T>>, name: &'static str, f: impl Fn(&Binding<T>)) {
         match option.value {
-            None => chain!(self, "{option}.is_none()"),
+            None => self.push(format_args!("{option}.is_none()")),
             Some(value) => {
                 let value = &self.bind(name, value);
-                chain!(self, "let Some({value}) = {option}");
+                self.push(format_args!("let Some({value}) = {option}"));
                 f(value);
             },
         }
@@ -234,9 +446,9 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
 
     fn slice<T>(&self, slice: &Binding<&[T]>, f: impl Fn(&Binding<&T>)) {
         if slice.value.is_empty() {
-            chain!(self, "{slice}.is_empty()");
+            self.push(format_args!("{slice}.is_empty()"));
         } else {
-            chain!(self, "{slice}.len() == {}", slice.value.len());
+            self.push(format_args!("{slice}.len() == {}", slice.value.len()));
             for (i, value) in slice.value.iter().enumerate() {
                 let name = format!("{slice}[{i}]");
                 f(&Binding { name, value });
@@ -244,18 +456,33 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
         }
     }
 
-    fn destination(&self, destination: &Binding<hir::Destination>) {
-        self.option(field!(destination.label), "label", |label| {
-            self.ident(field!(label.ident));
-        });
-    }
-
     fn ident(&self, ident: &Binding<Ident>) {
-        chain!(self, "{ident}.as_str() == {:?}", ident.value.as_str());
+        self.push(format_args!("{ident}.as_str() == {:?}", ident.value.as_str()));
     }
 
     fn symbol(&self, symbol: &Binding<Symbol>) {
-        chain!(self, "{symbol}.as_str() == {:?}", symbol.value.as_str());
+        self.push(format_args!("{symbol}.as_str() == {:?}", symbol.value.as_str()));
+    }
+}
+
+struct PrintVisitor<'a, 'tcx> {
+    cx: &'a LateContext<'tcx>,
+    chain: Chain,
+}
+
+#[allow(clippy::unused_self)]
+impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
+    fn new(cx: &'a LateContext<'tcx>) -> Self {
+        Self {
+            cx,
+            chain: Chain::default(),
+        }
+    }
+
+    fn destination(&self, destination: &Binding<hir::Destination>) {
+        self.chain.option(field!(destination.label), "label", |label| {
+            self.chain.ident(field!(label.ident));
+        });
     }
 
     fn qpath(&self, qpath: &Binding<&QPath<'_>>) {
@@ -320,7 +547,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
             LitKind::Str(s, _) => {
                 bind!(self, s);
                 kind!("Str({s}, _)");
-                self.symbol(s);
+                self.chain.symbol(s);
             },
         }
     }
@@ -340,6 +567,8 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
 
     #[allow(clippy::too_many_lines)]
     fn expr(&self, expr: &Binding<&hir::Expr<'_>>) {
+        self.chain.matched_expr(expr);
+
         if let Some(higher::While { condition, body, .. }) = higher::While::hir(expr.value) {
             bind!(self, condition, body);
             chain!(
@@ -406,25 +635,25 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
             ExprKind::Array(elements) => {
                 bind!(self, elements);
                 kind!("Array({elements})");
-                self.slice(elements, |e| self.expr(e));
+                self.chain.slice(elements, |e| self.expr(e));
             },
             ExprKind::Call(func, args) => {
                 bind!(self, func, args);
                 kind!("Call({func}, {args})");
                 self.expr(func);
-                self.slice(args, |e| self.expr(e));
+                self.chain.slice(args, |e| self.expr(e));
             },
             ExprKind::MethodCall(method_name, receiver, args, _) => {
                 bind!(self, method_name, receiver, args);
                 kind!("MethodCall({method_name}, {receiver}, {args}, _)");
-                self.ident(field!(method_name.ident));
+                self.chain.ident(field!(method_name.ident));
                 self.expr(receiver);
-                self.slice(args, |e| self.expr(e));
+                self.chain.slice(args, |e| self.expr(e));
             },
             ExprKind::Tup(elements) => {
                 bind!(self, elements);
                 kind!("Tup({elements})");
-                self.slice(elements, |e| self.expr(e));
+                self.chain.slice(elements, |e| self.expr(e));
             },
             ExprKind::Binary(op, left, right) => {
                 bind!(self, op, left, right);
@@ -463,7 +692,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                 opt_bind!(self, label);
                 kind!("Loop({body}, {label}, LoopSource::{des:?}, _)");
                 self.block(body);
-                label.if_some(|l| self.ident(field!(l.ident)));
+                label.if_some(|l| self.chain.ident(field!(l.ident)));
             },
             ExprKind::If(cond, then, else_expr) => {
                 bind!(self, cond, then);
@@ -477,7 +706,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                 bind!(self, scrutinee, arms);
                 kind!("Match({scrutinee}, {arms}, MatchSource::{des:?})");
                 self.expr(scrutinee);
-                self.slice(arms, |arm| self.arm(arm));
+                self.chain.slice(arms, |arm| self.arm(arm));
             },
             ExprKind::Closure(&Closure {
                 capture_clause,
@@ -528,7 +757,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                 opt_bind!(self, label);
                 kind!("Block({block}, {label})");
                 self.block(block);
-                label.if_some(|l| self.ident(field!(l.ident)));
+                label.if_some(|l| self.chain.ident(field!(l.ident)));
             },
             ExprKind::Assign(target, value, _) => {
                 bind!(self, target, value);
@@ -546,7 +775,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
             ExprKind::Field(object, field_name) => {
                 bind!(self, object, field_name);
                 kind!("Field({object}, {field_name})");
-                self.ident(field_name);
+                self.chain.ident(field_name);
                 self.expr(object);
             },
             ExprKind::Index(object, index, _) => {
@@ -589,7 +818,10 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
             },
             ExprKind::InlineAsm(_) => {
                 kind!("InlineAsm(_)");
-                out!("// unimplemented: `ExprKind::InlineAsm` is not further destructured at the moment");
+                out!(
+                    self,
+                    "// unimplemented: `ExprKind::InlineAsm` is not further destructured at the moment"
+                );
             },
             ExprKind::OffsetOf(container, ref fields) => {
                 bind!(self, container, fields);
@@ -600,8 +832,8 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                 opt_bind!(self, base);
                 kind!("Struct({qpath}, {fields}, {base})");
                 self.qpath(qpath);
-                self.slice(fields, |field| {
-                    self.ident(field!(field.ident));
+                self.chain.slice(fields, |field| {
+                    self.chain.ident(field!(field.ident));
                     self.expr(field!(field.expr));
                 });
                 base.if_some(|e| self.expr(e));
@@ -630,8 +862,8 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
     }
 
     fn block(&self, block: &Binding<&hir::Block<'_>>) {
-        self.slice(field!(block.stmts), |stmt| self.stmt(stmt));
-        self.option(field!(block.expr), "trailing_expr", |expr| {
+        self.chain.slice(field!(block.stmts), |stmt| self.stmt(stmt));
+        self.chain.option(field!(block.expr), "trailing_expr", |expr| {
             self.expr(expr);
         });
     }
@@ -664,28 +896,28 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                     BindingMode::MUT_REF_MUT => "MUT_REF_MUT",
                 };
                 kind!("Binding(BindingMode::{ann}, _, {name}, {sub})");
-                self.ident(name);
+                self.chain.ident(name);
                 sub.if_some(|p| self.pat(p));
             },
             PatKind::Struct(ref qpath, fields, ignore) => {
                 bind!(self, qpath, fields);
                 kind!("Struct(ref {qpath}, {fields}, {ignore})");
                 self.qpath(qpath);
-                self.slice(fields, |field| {
-                    self.ident(field!(field.ident));
+                self.chain.slice(fields, |field| {
+                    self.chain.ident(field!(field.ident));
                     self.pat(field!(field.pat));
                 });
             },
             PatKind::Or(fields) => {
                 bind!(self, fields);
                 kind!("Or({fields})");
-                self.slice(fields, |pat| self.pat(pat));
+                self.chain.slice(fields, |pat| self.pat(pat));
             },
             PatKind::TupleStruct(ref qpath, fields, skip_pos) => {
                 bind!(self, qpath, fields);
                 kind!("TupleStruct(ref {qpath}, {fields}, {skip_pos:?})");
                 self.qpath(qpath);
-                self.slice(fields, |pat| self.pat(pat));
+                self.chain.slice(fields, |pat| self.pat(pat));
             },
             PatKind::Path(ref qpath) => {
                 bind!(self, qpath);
@@ -695,7 +927,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
             PatKind::Tuple(fields, skip_pos) => {
                 bind!(self, fields);
                 kind!("Tuple({fields}, {skip_pos:?})");
-                self.slice(fields, |field| self.pat(field));
+                self.chain.slice(fields, |field| self.pat(field));
             },
             PatKind::Box(pat) => {
                 bind!(self, pat);
@@ -728,8 +960,8 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
                 opt_bind!(self, middle);
                 kind!("Slice({start}, {middle}, {end})");
                 middle.if_some(|p| self.pat(p));
-                self.slice(start, |pat| self.pat(pat));
-                self.slice(end, |pat| self.pat(pat));
+                self.chain.slice(start, |pat| self.pat(pat));
+                self.chain.slice(end, |pat| self.pat(pat));
             },
             PatKind::Err(_) => kind!("Err"),
         }
@@ -745,7 +977,7 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
             StmtKind::Let(local) => {
                 bind!(self, local);
                 kind!("Let({local})");
-                self.option(field!(local.init), "init", |init| {
+                self.chain.option(field!(local.init), "init", |init| {
                     self.expr(init);
                 });
                 self.pat(field!(local.pat));
@@ -766,8 +998,17 @@ impl<'a, 'tcx> PrintVisitor<'a, 'tcx> {
 }
 
 fn has_attr(cx: &LateContext<'_>, hir_id: HirId) -> bool {
-    let attrs = cx.tcx.hir().attrs(hir_id);
-    get_attr(cx.sess(), attrs, "author").count() > 0
+    is_author(cx.sess(), cx.tcx.hir().attrs(hir_id), false)
+}
+
+/// Whether the attributes contain `#[clippy::author]`, or `#[clippy::author(ast)]` if `ast` is set
+fn is_author(sess: &Session, attrs: &[Attribute], ast: bool) -> bool {
+    get_attr(sess, attrs, "author").any(|attr| {
+        let is_ast = attr
+            .meta_item_list()
+            .is_some_and(|items| items.iter().any(|item| item.name_or_empty().as_str() == "ast"));
+        is_ast == ast
+    })
 }
 
 fn path_to_string(path: &QPath<'_>) -> Result<String, ()> {
@@ -0,0 +1,676 @@
+use super::{Binding, Chain, Hook, OptionPat, is_author, print_lint};
+use rustc_ast::ptr::P;
+use rustc_ast::{
+    AssocItem, AssocItemKind, BindingMode, Block, CaptureBy, Expr, ExprKind, FnRetTy, Item, ItemKind, LocalKind, Pat,
+    PatKind, Path, RangeEnd, Stmt, StmtKind, StructRest, TyKind, token,
+};
+use rustc_lint::{EarlyContext, EarlyLintPass, LintContext};
+use rustc_session::declare_lint_pass;
+
+declare_lint_pass!(
+    /// ### What it does
+    /// Generates clippy code that detects the offending pattern in the AST, like
+    /// `Author` does with the HIR
+    ///
+    /// ### Example
+    /// ```rust,ignore
+    /// // ./tests/ui/my_lint.rs
+    /// fn foo() {
+    ///     #[clippy::author(ast)]
+    ///     let x = (1);
+    /// }
+    /// ```
+    ///
+    /// Running `TESTNAME=ui/my_lint cargo uitest` will produce a lint whose
+    /// `EarlyLintPass` checks for the pattern:
+    ///
+    /// ```rust,ignore
+    /// impl EarlyLintPass for LintName {
+    ///     fn check_stmt(&mut self, cx: &EarlyContext<'_>, stmt: &Stmt) {
+    ///         if let StmtKind::Let(local) = &stmt.kind
+    ///             && let LocalKind::Init(init) = &local.kind
+    ///             && let ExprKind::Paren(inner) = &init.kind
+    ///             ...
+    /// ```
+    AuthorAst => []
+);
+
+/// The items of `rustc_ast` which may be used by the generated conditions
+const AST_IMPORTS: &[(&str, &[&str])] = &[("rustc_ast", &[
+    "AssocItemKind",
+    "BinOpKind",
+    "BindingMode",
+    "BorrowKind",
+    "CaptureBy",
+    "ExprKind",
+    "FnRetTy",
+    "ForLoopKind",
+    "GenBlockKind",
+    "ItemKind",
+    "LocalKind",
+    "MatchKind",
+    "Mutability",
+    "PatFieldsRest",
+    "PatKind",
+    "RangeEnd",
+    "RangeLimits",
+    "StmtKind",
+    "StructRest",
+    "TyKind",
+    "UnOp",
+    "token",
+])];
+
+/// Like `bind!`, but binds the `P<T>` and `ThinVec<T>` nodes of the AST as `&T` and `&[T]`
+macro_rules! bind_ref {
+    ($self:ident $(, $name:ident)+) => {
+        $(let $name = &$self.chain.bind(stringify!($name), &**$name);)+
+    };
+}
+
+/// Like `opt_bind!`, for the `Option<P<T>>` nodes of the AST
+macro_rules! opt_bind_ref {
+    ($self:ident $(, $name:ident)+) => {
+        $(let $name = OptionPat::new($name.as_deref().map(|o| $self.chain.bind(stringify!($name), o)));)+
+    };
+}
+
+/// Creates a `Binding` that borrows the field of an existing `Binding`, the `P<T>` and
+/// `ThinVec<T>` fields are borrowed as `&T` and `&[T]`
+macro_rules! field_ref {
+    ($binding:ident.$field:ident) => {
+        &Binding {
+            name: $binding.name.to_string() + stringify!(.$field),
+            value: &*$binding.value.$field,
+        }
+    };
+}
+
+impl EarlyLintPass for AuthorAst {
+    fn check_item(&mut self, cx: &EarlyContext<'_>, item: &Item) {
+        if is_author(cx.sess(), &item.attrs, true) {
+            let v = PrintVisitor::default();
+            let item = &v.chain.bind("item", item);
+            if let ItemKind::Fn(func) = &item.value.kind {
+                let func = &v.chain.bind("func", &**func);
+                chain!(v, "let ItemKind::Fn({func}) = &{item}.kind");
+                v.fn_body(func.value.body.as_deref(), func);
+            } else {
+                chain!(v, "let ItemKind::Fn(func) = &{item}.kind");
+                out!(v, "// unimplemented: only the bodies of functions are matched");
+            }
+            print_lint(&Hook::new("check_item", "item", "Item"), &v.chain, true, AST_IMPORTS);
+        }
+    }
+
+    fn check_impl_item(&mut self, cx: &EarlyContext<'_>, item: &AssocItem) {
+        check_assoc_item(cx, item, &Hook::new("check_impl_item", "item", "AssocItem"));
+    }
+
+    fn check_trait_item(&mut self, cx: &EarlyContext<'_>, item: &AssocItem) {
+        check_assoc_item(cx, item, &Hook::new("check_trait_item", "item", "AssocItem"));
+    }
+
+    fn check_arm(&mut self, cx: &EarlyContext<'_>, arm: &rustc_ast::Arm) {
+        if is_author(cx.sess(), &arm.attrs, true) {
+            let v = PrintVisitor::default();
+            v.arm(&v.chain.bind("arm", arm));
+            print_lint(&Hook::new("check_arm", "arm", "Arm"), &v.chain, true, AST_IMPORTS);
+        }
+    }
+
+    fn check_expr(&mut self, cx: &EarlyContext<'_>, expr: &Expr) {
+        if is_author(cx.sess(), &expr.attrs, true) {
+            let v = PrintVisitor::default();
+            v.expr(&v.chain.bind("expr", expr));
+            print_lint(&Hook::new("check_expr", "expr", "Expr"), &v.chain, true, AST_IMPORTS);
+        }
+    }
+
+    fn check_stmt(&mut self, cx: &EarlyContext<'_>, stmt: &Stmt) {
+        // The attributes of the other statements are those of their expression or item
+        if let StmtKind::Let(local) = &stmt.kind
+            && is_author(cx.sess(), &local.attrs, true)
+        {
+            let v = PrintVisitor::default();
+            v.stmt(&v.chain.bind("stmt", stmt));
+            print_lint(&Hook::new("check_stmt", "stmt", "Stmt"), &v.chain, true, AST_IMPORTS);
+        }
+    }
+}
+
+fn check_assoc_item(cx: &EarlyContext<'_>, item: &AssocItem, hook: &Hook) {
+    if is_author(cx.sess(), &item.attrs, true) {
+        let v = PrintVisitor::default();
+        let item = &v.chain.bind("item", item);
+        if let AssocItemKind::Fn(func) = &item.value.kind {
+            let func = &v.chain.bind("func", &**func);
+            chain!(v, "let AssocItemKind::Fn({func}) = &{item}.kind");
+            v.fn_body(func.value.body.as_deref(), func);
+        } else {
+            chain!(v, "let AssocItemKind::Fn(func) = &{item}.kind");
+            out!(v, "// unimplemented: only the bodies of functions are matched");
+        }
+        print_lint(hook, &v.chain, true, AST_IMPORTS);
+    }
+}
+
+/// Derefs the `P<T>` of a binding
+fn unbox<'a, T>(binding: &Binding<&'a P<T>>) -> Binding<&'a T> {
+    Binding {
+        name: binding.name.clone(),
+        value: binding.value,
+    }
+}
+
+#[derive(Default)]
+struct PrintVisitor {
+    chain: Chain,
+}
+
+impl PrintVisitor {
+    fn fn_body(&self, body: Option<&Block>, func: &Binding<&rustc_ast::Fn>) {
+        match body {
+            Some(body) => {
+                bind!(self, body);
+                chain!(self, "let Some({body}) = &{func}.body");
+                self.block(body);
+            },
+            None => chain!(self, "{func}.body.is_none()"),
+        }
+    }
+
+    fn path(&self, path: &Binding<&Path>) {
+        self.chain.slice(field_ref!(path.segments), |segment| {
+            self.chain.ident(field!(segment.ident));
+        });
+    }
+
+    fn lit(&self, lit: &Binding<&token::Lit>) {
+        match lit.value.kind {
+            token::LitKind::Err(_) => chain!(self, "let token::LitKind::Err(_) = {lit}.kind"),
+            kind => chain!(self, "let token::LitKind::{kind:?} = {lit}.kind"),
+        }
+        self.chain.symbol(field!(lit.symbol));
+        self.chain
+            .option(field!(lit.suffix), "suffix", |suffix| self.chain.symbol(suffix));
+    }
+
+    fn exprs(&self, exprs: &Binding<&[P<Expr>]>) {
+        self.chain.slice(exprs, |expr| self.expr(&unbox(expr)));
+    }
+
+    fn label(&self, label: &OptionPat<Binding<rustc_ast::Label>>) {
+        label.if_some(|label| self.chain.ident(field!(label.ident)));
+    }
+
+    fn arm(&self, arm: &Binding<&rustc_ast::Arm>) {
+        self.pat(field_ref!(arm.pat));
+        for (field, value) in [("guard", &arm.value.guard), ("body", &arm.value.body)] {
+            match value.as_deref() {
+                Some(e) => {
+                    let e = &self.chain.bind(field, e);
+                    chain!(self, "let Some({e}) = &{arm}.{field}");
+                    self.expr(e);
+                },
+                None => chain!(self, "{arm}.{field}.is_none()"),
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn expr(&self, expr: &Binding<&Expr>) {
+        self.chain.matched_expr(expr);
+
+        let kind = |kind| chain!(self, "let ExprKind::{kind} = &{expr}.kind");
+        macro_rules! kind {
+            ($($t:tt)*) => (kind(format_args!($($t)*)));
+        }
+
+        match &expr.value.kind {
+            ExprKind::Array(elements) | ExprKind::Tup(elements) => {
+                bind_ref!(self, elements);
+                if let ExprKind::Array(_) = expr.value.kind {
+                    kind!("Array({elements})");
+                } else {
+                    kind!("Tup({elements})");
+                }
+                self.exprs(elements);
+            },
+            ExprKind::ConstBlock(anon_const) => {
+                bind!(self, anon_const);
+                kind!("ConstBlock({anon_const})");
+                self.expr(field_ref!(anon_const.value));
+            },
+            ExprKind::Call(func, args) => {
+                bind_ref!(self, func, args);
+                kind!("Call({func}, {args})");
+                self.expr(func);
+                self.exprs(args);
+            },
+            ExprKind::MethodCall(method_call) => {
+                bind_ref!(self, method_call);
+                kind!("MethodCall({method_call})");
+                self.chain.ident(&Binding {
+                    name: format!("{method_call}.seg.ident"),
+                    value: method_call.value.seg.ident,
+                });
+                self.expr(field_ref!(method_call.receiver));
+                self.exprs(field_ref!(method_call.args));
+            },
+            ExprKind::Binary(op, left, right) => {
+                bind!(self, op);
+                bind_ref!(self, left, right);
+                kind!("Binary({op}, {left}, {right})");
+                chain!(self, "BinOpKind::{:?} == {op}.node", op.value.node);
+                self.expr(left);
+                self.expr(right);
+            },
+            ExprKind::Unary(op, inner) => {
+                bind_ref!(self, inner);
+                kind!("Unary(UnOp::{op:?}, {inner})");
+                self.expr(inner);
+            },
+            ExprKind::Lit(lit) => {
+                bind!(self, lit);
+                kind!("Lit({lit})");
+                self.lit(lit);
+            },
+            ExprKind::Cast(expr, cast_ty) => {
+                bind_ref!(self, expr, cast_ty);
+                kind!("Cast({expr}, {cast_ty})");
+                if let TyKind::Path(None, path) = &cast_ty.value.kind {
+                    bind!(self, path);
+                    chain!(self, "let TyKind::Path(None, {path}) = &{cast_ty}.kind");
+                    self.path(path);
+                }
+                self.expr(expr);
+            },
+            ExprKind::Type(expr, _ty) => {
+                bind_ref!(self, expr);
+                kind!("Type({expr}, _)");
+                self.expr(expr);
+            },
+            ExprKind::Let(pat, init, ..) => {
+                bind_ref!(self, pat, init);
+                kind!("Let({pat}, {init}, _, _)");
+                self.pat(pat);
+                self.expr(init);
+            },
+            ExprKind::If(cond, then, else_expr) => {
+                bind_ref!(self, cond, then);
+                opt_bind_ref!(self, else_expr);
+                kind!("If({cond}, {then}, {else_expr})");
+                self.expr(cond);
+                self.block(then);
+                else_expr.if_some(|e| self.expr(e));
+            },
+            ExprKind::While(cond, body, label) => {
+                bind_ref!(self, cond, body);
+                opt_bind!(self, label);
+                kind!("While({cond}, {body}, {label})");
+                self.expr(cond);
+                self.block(body);
+                self.label(&label);
+            },
+            ExprKind::ForLoop {
+                pat,
+                iter,
+                body,
+                label,
+                kind: loop_kind,
+            } => {
+                bind_ref!(self, pat, iter, body);
+                opt_bind!(self, label);
+                kind!(
+                    "ForLoop {{ pat: {pat}, iter: {iter}, body: {body}, label: {label}, kind: ForLoopKind::{loop_kind:?} }}"
+                );
+                self.pat(pat);
+                self.expr(iter);
+                self.block(body);
+                self.label(&label);
+            },
+            ExprKind::Loop(body, label, _) => {
+                bind_ref!(self, body);
+                opt_bind!(self, label);
+                kind!("Loop({body}, {label}, _)");
+                self.block(body);
+                self.label(&label);
+            },
+            ExprKind::Match(scrutinee, arms, match_kind) => {
+                bind_ref!(self, scrutinee, arms);
+                kind!("Match({scrutinee}, {arms}, MatchKind::{match_kind:?})");
+                self.expr(scrutinee);
+                self.chain.slice(arms, |arm| self.arm(arm));
+            },
+            ExprKind::Closure(closure) => {
+                bind_ref!(self, closure);
+                kind!("Closure({closure})");
+                match closure.value.capture_clause {
+                    CaptureBy::Value { .. } => chain!(self, "let CaptureBy::Value {{ .. }} = {closure}.capture_clause"),
+                    CaptureBy::Ref => chain!(self, "let CaptureBy::Ref = {closure}.capture_clause"),
+                }
+                match closure.value.fn_decl.output {
+                    FnRetTy::Default(_) => chain!(self, "let FnRetTy::Default(_) = {closure}.fn_decl.output"),
+                    FnRetTy::Ty(_) => chain!(self, "let FnRetTy::Ty(_ty) = &{closure}.fn_decl.output"),
+                }
+                self.expr(field_ref!(closure.body));
+            },
+            ExprKind::Block(block, label) => {
+                bind_ref!(self, block);
+                opt_bind!(self, label);
+                kind!("Block({block}, {label})");
+                self.block(block);
+                self.label(&label);
+            },
+            ExprKind::Gen(_, block, gen_kind, _) => {
+                bind_ref!(self, block);
+                kind!("Gen(_, {block}, GenBlockKind::{gen_kind:?}, _)");
+                self.block(block);
+            },
+            ExprKind::Await(inner, _) => {
+                bind_ref!(self, inner);
+                kind!("Await({inner}, _)");
+                self.expr(inner);
+            },
+            ExprKind::TryBlock(block) => {
+                bind_ref!(self, block);
+                kind!("TryBlock({block})");
+                self.block(block);
+            },
+            ExprKind::Assign(target, value, _) => {
+                bind_ref!(self, target, value);
+                kind!("Assign({target}, {value}, _)");
+                self.expr(target);
+                self.expr(value);
+            },
+            ExprKind::AssignOp(op, target, value) => {
+                bind!(self, op);
+                bind_ref!(self, target, value);
+                kind!("AssignOp({op}, {target}, {value})");
+                chain!(self, "BinOpKind::{:?} == {op}.node", op.value.node);
+                self.expr(target);
+                self.expr(value);
+            },
+            ExprKind::Field(object, field_name) => {
+                let field_name = *field_name;
+                bind_ref!(self, object);
+                bind!(self, field_name);
+                kind!("Field({object}, {field_name})");
+                self.chain.ident(field_name);
+                self.expr(object);
+            },
+            ExprKind::Index(object, index, _) => {
+                bind_ref!(self, object, index);
+                kind!("Index({object}, {index}, _)");
+                self.expr(object);
+                self.expr(index);
+            },
+            ExprKind::Range(start, end, limits) => {
+                opt_bind_ref!(self, start, end);
+                kind!("Range({start}, {end}, RangeLimits::{limits:?})");
+                start.if_some(|e| self.expr(e));
+                end.if_some(|e| self.expr(e));
+            },
+            ExprKind::Underscore => kind!("Underscore"),
+            ExprKind::Path(None, path) => {
+                bind!(self, path);
+                kind!("Path(None, {path})");
+                self.path(path);
+            },
+            ExprKind::Path(Some(_), path) => {
+                bind!(self, path);
+                kind!("Path(Some(_qself), {path})");
+                self.path(path);
+            },
+            ExprKind::AddrOf(borrow_kind, mutability, inner) => {
+                bind_ref!(self, inner);
+                kind!("AddrOf(BorrowKind::{borrow_kind:?}, Mutability::{mutability:?}, {inner})");
+                self.expr(inner);
+            },
+            ExprKind::Break(label, value) => {
+                opt_bind!(self, label);
+                opt_bind_ref!(self, value);
+                kind!("Break({label}, {value})");
+                self.label(&label);
+                value.if_some(|e| self.expr(e));
+            },
+            ExprKind::Continue(label) => {
+                opt_bind!(self, label);
+                kind!("Continue({label})");
+                self.label(&label);
+            },
+            ExprKind::Ret(value) => {
+                opt_bind_ref!(self, value);
+                kind!("Ret({value})");
+                value.if_some(|e| self.expr(e));
+            },
+            ExprKind::Yield(value) => {
+                opt_bind_ref!(self, value);
+                kind!("Yield({value})");
+                value.if_some(|e| self.expr(e));
+            },
+            ExprKind::Yeet(value) => {
+                opt_bind_ref!(self, value);
+                kind!("Yeet({value})");
+                value.if_some(|e| self.expr(e));
+            },
+            ExprKind::Become(value) => {
+                bind_ref!(self, value);
+                kind!("Become({value})");
+                self.expr(value);
+            },
+            ExprKind::Paren(inner) => {
+                bind_ref!(self, inner);
+                kind!("Paren({inner})");
+                self.expr(inner);
+            },
+            ExprKind::Try(inner) => {
+                bind_ref!(self, inner);
+                kind!("Try({inner})");
+                self.expr(inner);
+            },
+            ExprKind::InlineAsm(_) => {
+                kind!("InlineAsm(_)");
+                out!(
+                    self,
+                    "// unimplemented: `ExprKind::InlineAsm` is not further destructured at the moment"
+                );
+            },
+            ExprKind::OffsetOf(..) => {
+                kind!("OffsetOf(_, _)");
+                out!(
+                    self,
+                    "// unimplemented: `ExprKind::OffsetOf` is not further destructured at the moment"
+                );
+            },
+            ExprKind::MacCall(mac) => {
+                bind_ref!(self, mac);
+                kind!("MacCall({mac})");
+                self.path(&Binding {
+                    name: format!("{mac}.path"),
+                    value: &mac.value.path,
+                });
+            },
+            ExprKind::Struct(struct_expr) => {
+                bind_ref!(self, struct_expr);
+                kind!("Struct({struct_expr})");
+                self.path(&Binding {
+                    name: format!("{struct_expr}.path"),
+                    value: &struct_expr.value.path,
+                });
+                self.chain.slice(field_ref!(struct_expr.fields), |field| {
+                    self.chain.ident(field!(field.ident));
+                    self.expr(field_ref!(field.expr));
+                });
+                match &struct_expr.value.rest {
+                    StructRest::Base(base) => {
+                        bind_ref!(self, base);
+                        chain!(self, "let StructRest::Base({base}) = &{struct_expr}.rest");
+                        self.expr(base);
+                    },
+                    StructRest::Rest(_) => chain!(self, "let StructRest::Rest(_) = {struct_expr}.rest"),
+                    StructRest::None => chain!(self, "let StructRest::None = {struct_expr}.rest"),
+                }
+            },
+            ExprKind::Repeat(value, length) => {
+                bind_ref!(self, value);
+                bind!(self, length);
+                kind!("Repeat({value}, {length})");
+                self.expr(value);
+                self.expr(field_ref!(length.value));
+            },
+            ExprKind::IncludedBytes(_) => kind!("IncludedBytes(_)"),
+            ExprKind::FormatArgs(_) => {
+                kind!("FormatArgs(_)");
+                out!(
+                    self,
+                    "// unimplemented: `ExprKind::FormatArgs` is not further destructured at the moment"
+                );
+            },
+            ExprKind::Err(_) => kind!("Err(_)"),
+            ExprKind::Dummy => kind!("Dummy"),
+        }
+    }
+
+    fn block(&self, block: &Binding<&Block>) {
+        self.chain.slice(field_ref!(block.stmts), |stmt| self.stmt(stmt));
+    }
+
+    fn pat(&self, pat: &Binding<&Pat>) {
+        let kind = |kind| chain!(self, "let PatKind::{kind} = &{pat}.kind");
+        macro_rules! kind {
+            ($($t:tt)*) => (kind(format_args!($($t)*)));
+        }
+
+        match &pat.value.kind {
+            PatKind::Wild => kind!("Wild"),
+            PatKind::Rest => kind!("Rest"),
+            PatKind::Never => kind!("Never"),
+            PatKind::Ident(ann, name, sub) => {
+                let name = *name;
+                bind!(self, name);
+                opt_bind_ref!(self, sub);
+                let ann = match *ann {
+                    BindingMode::NONE => "NONE",
+                    BindingMode::REF => "REF",
+                    BindingMode::MUT => "MUT",
+                    BindingMode::REF_MUT => "REF_MUT",
+                    BindingMode::MUT_REF => "MUT_REF",
+                    BindingMode::MUT_REF_MUT => "MUT_REF_MUT",
+                };
+                kind!("Ident(BindingMode::{ann}, {name}, {sub})");
+                self.chain.ident(name);
+                sub.if_some(|p| self.pat(p));
+            },
+            PatKind::Struct(qself, path, fields, rest) => {
+                bind!(self, path);
+                bind_ref!(self, fields);
+                let qself = if qself.is_some() { "Some(_qself)" } else { "None" };
+                kind!("Struct({qself}, {path}, {fields}, PatFieldsRest::{rest:?})");
+                self.path(path);
+                self.chain.slice(fields, |field| {
+                    self.chain.ident(field!(field.ident));
+                    self.pat(field_ref!(field.pat));
+                });
+            },
+            PatKind::TupleStruct(qself, path, fields) => {
+                bind!(self, path);
+                bind_ref!(self, fields);
+                let qself = if qself.is_some() { "Some(_qself)" } else { "None" };
+                kind!("TupleStruct({qself}, {path}, {fields})");
+                self.path(path);
+                self.chain.slice(fields, |pat| self.pat(&unbox(pat)));
+            },
+            PatKind::Path(qself, path) => {
+                bind!(self, path);
+                let qself = if qself.is_some() { "Some(_qself)" } else { "None" };
+                kind!("Path({qself}, {path})");
+                self.path(path);
+            },
+            PatKind::Or(fields) | PatKind::Tuple(fields) | PatKind::Slice(fields) => {
+                bind_ref!(self, fields);
+                match pat.value.kind {
+                    PatKind::Or(_) => kind!("Or({fields})"),
+                    PatKind::Tuple(_) => kind!("Tuple({fields})"),
+                    _ => kind!("Slice({fields})"),
+                }
+                self.chain.slice(fields, |pat| self.pat(&unbox(pat)));
+            },
+            PatKind::Box(inner) | PatKind::Deref(inner) | PatKind::Paren(inner) => {
+                bind_ref!(self, inner);
+                match pat.value.kind {
+                    PatKind::Box(_) => kind!("Box({inner})"),
+                    PatKind::Deref(_) => kind!("Deref({inner})"),
+                    _ => kind!("Paren({inner})"),
+                }
+                self.pat(inner);
+            },
+            PatKind::Ref(inner, mutability) => {
+                bind_ref!(self, inner);
+                kind!("Ref({inner}, Mutability::{mutability:?})");
+                self.pat(inner);
+            },
+            PatKind::Lit(lit_expr) => {
+                bind_ref!(self, lit_expr);
+                kind!("Lit({lit_expr})");
+                self.expr(lit_expr);
+            },
+            PatKind::Range(start, end, end_kind) => {
+                opt_bind_ref!(self, start, end);
+                bind!(self, end_kind);
+                kind!("Range({start}, {end}, {end_kind})");
+                match end_kind.value.node {
+                    RangeEnd::Included(_) => chain!(self, "let RangeEnd::Included(_) = {end_kind}.node"),
+                    RangeEnd::Excluded => chain!(self, "let RangeEnd::Excluded = {end_kind}.node"),
+                }
+                start.if_some(|e| self.expr(e));
+                end.if_some(|e| self.expr(e));
+            },
+            PatKind::MacCall(_) => kind!("MacCall(_)"),
+            PatKind::Err(_) => kind!("Err(_)"),
+        }
+    }
+
+    fn stmt(&self, stmt: &Binding<&Stmt>) {
+        let kind = |kind| chain!(self, "let StmtKind::{kind} = &{stmt}.kind");
+        macro_rules! kind {
+            ($($t:tt)*) => (kind(format_args!($($t)*)));
+        }
+
+        match &stmt.value.kind {
+            StmtKind::Let(local) => {
+                bind_ref!(self, local);
+                kind!("Let({local})");
+                match &local.value.kind {
+                    LocalKind::Decl => chain!(self, "let LocalKind::Decl = {local}.kind"),
+                    LocalKind::Init(init) => {
+                        bind_ref!(self, init);
+                        chain!(self, "let LocalKind::Init({init}) = &{local}.kind");
+                        self.expr(init);
+                    },
+                    LocalKind::InitElse(init, els) => {
+                        bind_ref!(self, init, els);
+                        chain!(self, "let LocalKind::InitElse({init}, {els}) = &{local}.kind");
+                        self.expr(init);
+                        self.block(els);
+                    },
+                }
+                self.pat(field_ref!(local.pat));
+            },
+            StmtKind::Item(_) => kind!("Item(_item)"),
+            StmtKind::Expr(e) => {
+                bind_ref!(self, e);
+                kind!("Expr({e})");
+                self.expr(e);
+            },
+            StmtKind::Semi(e) => {
+                bind_ref!(self, e);
+                kind!("Semi({e})");
+                self.expr(e);
+            },
+            StmtKind::Empty => kind!("Empty"),
+            StmtKind::MacCall(_) => kind!("MacCall(_)"),
+        }
+    }
+}
@@ -1,14 +1,34 @@
+use clippy_utils::consts::ConstEvalCtxt;
 use clippy_utils::get_attr;
+use clippy_utils::mir::analysis_mir;
+use clippy_utils::source::snippet;
+use clippy_utils::visitors::{Visitable, for_each_expr};
+use core::ops::ControlFlow;
 use hir::TraitItem;
 use rustc_hir as hir;
+use rustc_hir::ConstContext;
+use rustc_hir::def_id::LocalDefId;
 use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::mir::pretty::{PrettyPrintMirOptions, write_mir_fn};
+use rustc_middle::ty::adjustment::{Adjust, Adjustment, AutoBorrow, AutoBorrowMutability};
 use rustc_session::declare_lint_pass;
+use std::io::Write;
 
 declare_lint_pass!(
     /// ### What it does
     /// It formats the attached node with `{:#?}` and writes the result to the
     /// standard output. This is intended for debugging.
     ///
+    /// The facts lints usually query about the node can be printed as well by
+    /// listing them in the attribute:
+    /// - `hir`: the node formatted with `{:#?}`, the default when nothing is listed
+    /// - `types`: the type of every expression, followed by its adjustments
+    /// - `consts`: the value `ConstEvalCtxt::eval` returns for every expression
+    /// - `mir`: the MIR of the enclosing body MIR based lints analyze
+    ///
+    /// Expressions are printed with their source snippet so the output can be
+    /// used in ui tests.
+    ///
     /// ### Examples
     /// ```rs
     /// #[clippy::dump]
@@ -18,20 +38,31 @@ declare_lint_pass!(
     /// fn foo(input: u32) -> u64 {
     ///     input as u64
     /// }
+    ///
+    /// #[clippy::dump(types, consts, mir)]
+    /// fn bar() -> u32 {
+    ///     1 + 2
+    /// }
     /// ```
     DumpHir => []
 );
 
 impl<'tcx> LateLintPass<'tcx> for DumpHir {
     fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::Item<'_>) {
-        if has_attr(cx, item.hir_id()) {
-            println!("{item:#?}");
+        if let Some(options) = dump_options(cx, item.hir_id()) {
+            if options.hir {
+                println!("{item:#?}");
+            }
+            options.dump_body_of(cx, item.owner_id.def_id);
         }
     }
 
     fn check_expr(&mut self, cx: &LateContext<'tcx>, expr: &'tcx hir::Expr<'_>) {
-        if has_attr(cx, expr.hir_id) {
-            println!("{expr:#?}");
+        if let Some(options) = dump_options(cx, expr.hir_id) {
+            if options.hir {
+                println!("{expr:#?}");
+            }
+            options.dump(cx, expr, cx.tcx.hir().enclosing_body_owner(expr.hir_id));
         }
     }
 
@@ -40,25 +71,142 @@ impl<'tcx> LateLintPass<'tcx> for DumpHir {
             hir::StmtKind::Expr(e) | hir::StmtKind::Semi(e) if has_attr(cx, e.hir_id) => return,
             _ => {},
         }
-        if has_attr(cx, stmt.hir_id) {
-            println!("{stmt:#?}");
+        if let Some(options) = dump_options(cx, stmt.hir_id) {
+            if options.hir {
+                println!("{stmt:#?}");
+            }
+            options.dump(cx, stmt, cx.tcx.hir().enclosing_body_owner(stmt.hir_id));
         }
     }
 
-    fn check_trait_item(&mut self, cx: &LateContext<'_>, item: &TraitItem<'_>) {
-        if has_attr(cx, item.hir_id()) {
-            println!("{item:#?}");
+    fn check_trait_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx TraitItem<'_>) {
+        if let Some(options) = dump_options(cx, item.hir_id()) {
+            if options.hir {
+                println!("{item:#?}");
+            }
+            options.dump_body_of(cx, item.owner_id.def_id);
         }
     }
 
-    fn check_impl_item(&mut self, cx: &LateContext<'_>, item: &hir::ImplItem<'_>) {
-        if has_attr(cx, item.hir_id()) {
-            println!("{item:#?}");
+    fn check_impl_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx hir::ImplItem<'_>) {
+        if let Some(options) = dump_options(cx, item.hir_id()) {
+            if options.hir {
+                println!("{item:#?}");
+            }
+            options.dump_body_of(cx, item.owner_id.def_id);
         }
     }
 }
 
+/// The sections requested by a `#[clippy::dump(..)]` attribute
+#[derive(Default)]
+#[expect(clippy::struct_excessive_bools)]
+struct DumpOptions {
+    hir: bool,
+    types: bool,
+    consts: bool,
+    mir: bool,
+}
+
+impl DumpOptions {
+    fn dump_body_of(&self, cx: &LateContext<'_>, def_id: LocalDefId) {
+        if let Some(body) = cx.tcx.hir().maybe_body_owned_by(def_id) {
+            self.dump(cx, body, def_id);
+        } else if self.types || self.consts || self.mir {
+            println!("no body");
+        }
+    }
+
+    /// Prints the requested facts about the expressions of `node`, which is a part of the body
+    /// owned by `owner`
+    fn dump<'tcx>(&self, cx: &LateContext<'tcx>, node: impl Visitable<'tcx> + Copy, owner: LocalDefId) {
+        if self.types {
+            println!("types:");
+            let typeck = cx.tcx.typeck(owner);
+            for_each_expr(cx, node, |e| {
+                println!("    `{}`: {}", expr_snippet(cx, e), typeck.expr_ty(e));
+                for adjustment in typeck.expr_adjustments(e) {
+                    println!("        {}", format_adjustment(adjustment));
+                }
+                ControlFlow::<()>::Continue(())
+            });
+        }
+        if self.consts {
+            println!("consts:");
+            let ecx = ConstEvalCtxt::with_env(cx.tcx, cx.tcx.param_env(owner), cx.tcx.typeck(owner));
+            for_each_expr(cx, node, |e| {
+                if let Some(value) = ecx.eval(e) {
+                    println!("    `{}`: {value:?}", expr_snippet(cx, e));
+                }
+                ControlFlow::<()>::Continue(())
+            });
+        }
+        if self.mir {
+            println!("mir:");
+            let mir = match cx.tcx.hir().body_const_context(owner) {
+                Some(ConstContext::Const { .. } | ConstContext::Static(_)) => cx.tcx.mir_for_ctfe(owner),
+                Some(ConstContext::ConstFn) | None => analysis_mir(cx.tcx, owner),
+            };
+            let mut out = Vec::new();
+            let options = PrettyPrintMirOptions {
+                include_extra_comments: false,
+            };
+            write_mir_fn(cx.tcx, mir, &mut |_, _| Ok(()), &mut out, options).unwrap();
+            std::io::stdout().write_all(&out).unwrap();
+        }
+    }
+}
+
+/// The source of an expression on a single line
+fn expr_snippet(cx: &LateContext<'_>, e: &hir::Expr<'_>) -> String {
+    snippet(cx, e.span, "..")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn format_adjustment(adjustment: &Adjustment<'_>) -> String {
+    let kind = match adjustment.kind {
+        Adjust::NeverToAny => "NeverToAny".to_string(),
+        Adjust::Deref(None) => "Deref".to_string(),
+        Adjust::Deref(Some(deref)) => format!("OverloadedDeref({})", deref.mutbl.ref_prefix_str().trim_end()),
+        Adjust::Borrow(AutoBorrow::Ref(AutoBorrowMutability::Not)) => "Borrow(&)".to_string(),
+        Adjust::Borrow(AutoBorrow::Ref(AutoBorrowMutability::Mut { .. })) => "Borrow(&mut)".to_string(),
+        Adjust::Borrow(AutoBorrow::RawPtr(mutbl)) => format!("Borrow(&raw {})", mutbl.ptr_str()),
+        Adjust::Pointer(cast) => format!("Pointer({cast:?})"),
+        Adjust::ReborrowPin(mutbl) => format!("ReborrowPin({})", mutbl.ref_prefix_str().trim_end()),
+    };
+    format!("{kind} -> {}", adjustment.target)
+}
+
 fn has_attr(cx: &LateContext<'_>, hir_id: hir::HirId) -> bool {
     let attrs = cx.tcx.hir().attrs(hir_id);
     get_attr(cx.sess(), attrs, "dump").count() > 0
 }
+
+/// Reads the options of the `#[clippy::dump]` attribute of the node, if it has one
+fn dump_options(cx: &LateContext<'_>, hir_id: hir::HirId) -> Option<DumpOptions> {
+    let attrs = cx.tcx.hir().attrs(hir_id);
+    let attr = get_attr(cx.sess(), attrs, "dump").next()?;
+    let mut options = DumpOptions::default();
+    match attr.meta_item_list() {
+        Some(items) if !items.is_empty() => {
+            for item in items {
+                match item.name_or_empty().as_str() {
+                    "hir" => options.hir = true,
+                    "types" => options.types = true,
+                    "consts" => options.consts = true,
+                    "mir" => options.mir = true,
+                    _ => {
+                        cx.sess().dcx().span_err(
+                            item.span(),
+                            "unknown `clippy::dump` option, expected one of `hir`, `types`, `consts` or `mir`",
+                        );
+                    },
+                }
+            }
+        },
+        _ => options.hir = true,
+    }
+    Some(options)
+}
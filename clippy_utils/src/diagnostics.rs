@@ -8,16 +8,212 @@
 //! Thank you!
 //! ~The `INTERNAL_METADATA_COLLECTOR` lint
 
-use rustc_errors::{Applicability, Diag, DiagMessage, MultiSpan, SubdiagMessage};
+use crate::diff;
+use crate::sugg::SuggGroup;
+use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
+use rustc_errors::{
+    Applicability, Diag, DiagInner, DiagMessage, ErrorGuaranteed, MultiSpan, SubdiagMessage, Suggestions,
+};
 #[cfg(debug_assertions)]
-use rustc_errors::{EmissionGuarantee, SubstitutionPart, Suggestions};
+use rustc_errors::{EmissionGuarantee, SubstitutionPart};
 use rustc_hir::HirId;
-use rustc_lint::{LateContext, Lint, LintContext};
+use rustc_lint::{LateContext, Level, Lint, LintContext, LintId};
+use rustc_middle::lint::{LevelAndSource, LintLevelSource, in_external_macro, lint_level};
+use rustc_session::Session;
 use rustc_span::Span;
+use rustc_span::source_map::SourceMap;
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Checks if a lint emitted at `sp` at the current node of `cx` is dropped by
+/// `cargo clippy --diff-base`, because it is not on the changed lines.
+///
+/// The level of the lint is only needed for the lints off the changed lines: expected lints are
+/// still emitted so that the expectation is fulfilled, and allowed ones are recorded.
+fn is_dropped<T: LintContext>(cx: &T, lint: &'static Lint, sp: &MultiSpan) -> bool {
+    if diff::is_kept(cx.sess().source_map(), sp.primary_span()) {
+        return false;
+    }
+    match cx.get_lint_level(lint) {
+        Level::Expect(_) => false,
+        Level::Allow => {
+            record_emission(cx.sess(), lint, sp.primary_span());
+            true
+        },
+        Level::Warn | Level::ForceWarn(_) | Level::Deny | Level::Forbid => true,
+    }
+}
+
+/// Like [`is_dropped`], for a lint emitted at a node whose level is already known.
+fn is_dropped_at(sm: &SourceMap, level: Level, sp: &MultiSpan) -> bool {
+    !matches!(level, Level::Allow | Level::Expect(_)) && !diff::is_kept(sm, sp.primary_span())
+}
+
+/// The `#[allow]` attributes which suppressed a lint during this session.
+#[derive(Default)]
+pub(crate) struct Suppressions {
+    /// The spans of the lint names in the `#[allow]` attributes known to have suppressed a lint
+    allows: FxHashSet<Span>,
+    /// The lints allowed where they were emitted by a function which does not know the node it is
+    /// emitted at, like [`span_lint`]. The `#[allow]` attributes are found from their spans.
+    emissions: FxIndexSet<(LintId, Span)>,
+}
+
+static SUPPRESSIONS: OnceLock<&'static Mutex<Suppressions>> = OnceLock::new();
+
+/// Returns the suppressions recorded so far, which are shared with the plugins.
+pub(crate) fn suppressions() -> &'static Mutex<Suppressions> {
+    SUPPRESSIONS.get_or_init(|| Box::leak(Box::default()))
+}
+
+/// Records the `#[allow]` attribute responsible for a lint not being emitted, if any.
+///
+/// This is used by the `unused_clippy_allows` lint to find attributes which no longer suppress
+/// anything, so it must only be called where the lint would otherwise be emitted. The emission
+/// functions of this module already do so.
+pub fn record_allow((level, source): LevelAndSource) {
+    if level == Level::Allow
+        && let LintLevelSource::Node { span, .. } = source
+    {
+        suppressions().lock().unwrap().allows.insert(span);
+    }
+}
+
+/// Records that `lint` was not emitted at `span` by a function which only knows the current node
+/// of the lint context, the `#[allow]` attribute responsible is found later from the span.
+///
+/// Only the lints which are allowed are not emitted, apart from the ones in external macros.
+fn record_emission(sess: &Session, lint: &'static Lint, span: Option<Span>) {
+    if let Some(span) = span
+        && !in_external_macro(sess, span)
+    {
+        suppressions()
+            .lock()
+            .unwrap()
+            .emissions
+            .insert((LintId::of(lint), span));
+    }
+}
+
+/// Checks if the `#[allow]` attribute entry at `span` has suppressed a lint emission so far.
+pub fn allow_was_used(span: Span) -> bool {
+    SUPPRESSIONS
+        .get()
+        .is_some_and(|suppressions| suppressions.lock().unwrap().allows.contains(&span))
+}
+
+/// Returns the lints which were allowed at a span, for the emissions whose `#[allow]` attribute is
+/// not known. See [`allow_was_used`] for the others.
+pub fn allowed_emissions() -> Vec<(LintId, Span)> {
+    SUPPRESSIONS.get().map_or_else(Vec::new, |suppressions| {
+        suppressions.lock().unwrap().emissions.iter().copied().collect()
+    })
+}
+
+/// The lints whose suggestions are applied by `cargo clippy --fix-only=..`, named without the
+/// `clippy::` prefix.
+static FIXED_LINTS: OnceLock<&'static FxHashSet<String>> = OnceLock::new();
+
+/// Returns the lints set by [`restrict_fixes_to`], which are shared with the plugins.
+pub(crate) fn fixed_lints() -> Option<&'static FxHashSet<String>> {
+    FIXED_LINTS.get().copied()
+}
+
+/// Set in the copy of `clippy_utils` of a dylib plugin, whose lints are not documented on clippy's
+/// website.
+static IN_PLUGIN: AtomicBool = AtomicBool::new(false);
+
+/// Uses the state of the `clippy_utils` of `clippy-driver` in the copy of a plugin.
+pub(crate) fn share(suppressions: &'static Mutex<Suppressions>, fixed_lints: Option<&'static FxHashSet<String>>) {
+    IN_PLUGIN.store(true, Ordering::Relaxed);
+    let _ = SUPPRESSIONS.set(suppressions);
+    if let Some(lints) = fixed_lints {
+        let _ = FIXED_LINTS.set(lints);
+    }
+}
+
+/// Restricts the suggestions applied by `cargo fix` to the ones of `lints`. The suggestions of the
+/// other lints are still displayed, but are no longer machine applicable.
+///
+/// The suggestions of rustc are only restricted once [`restrict_rustc_fixes`] is called.
+pub fn restrict_fixes_to(lints: impl IntoIterator<Item = String>) {
+    let _ = FIXED_LINTS.set(Box::leak(Box::new(lints.into_iter().collect())));
+}
+
+/// The argument added to the diagnostics of the lints set by [`restrict_fixes_to`], so that
+/// [`track_diagnostic`] keeps their suggestions.
+const FIXED_ARG: &str = "clippy_fixed";
+
+type TrackDiagnostic = fn(DiagInner, &mut dyn FnMut(DiagInner) -> Option<ErrorGuaranteed>) -> Option<ErrorGuaranteed>;
+
+/// The function diagnostics were passed to before [`restrict_rustc_fixes`].
+static RUSTC_TRACK_DIAGNOSTIC: OnceLock<TrackDiagnostic> = OnceLock::new();
+
+/// Also restricts the suggestions of rustc's lints and errors when [`restrict_fixes_to`] was
+/// called, by passing every diagnostic through [`track_diagnostic`].
+///
+/// This has to be called after rustc set up its own callbacks, e.g. once the `ParseSess` is
+/// created.
+pub fn restrict_rustc_fixes() {
+    if FIXED_LINTS.get().is_some() && RUSTC_TRACK_DIAGNOSTIC.get().is_none() {
+        static TRACK_DIAGNOSTIC: TrackDiagnostic = track_diagnostic;
+        let previous = rustc_errors::TRACK_DIAGNOSTIC.swap(&TRACK_DIAGNOSTIC);
+        let _ = RUSTC_TRACK_DIAGNOSTIC.set(*previous);
+    }
+}
+
+fn track_diagnostic(
+    mut diag: DiagInner,
+    emit: &mut dyn FnMut(DiagInner) -> Option<ErrorGuaranteed>,
+) -> Option<ErrorGuaranteed> {
+    if !diag.args.contains_key(FIXED_ARG) {
+        make_unspecified(&mut diag.suggestions);
+    }
+    (RUSTC_TRACK_DIAGNOSTIC.get().unwrap())(diag, emit)
+}
+
+fn restrict_fixes(diag: &mut Diag<'_, ()>, lint: &'static Lint) {
+    if let Some(lints) = FIXED_LINTS.get() {
+        if lints.contains(lint.name_lower().strip_prefix("clippy::").unwrap_or_default()) {
+            diag.arg(FIXED_ARG, true);
+        } else {
+            make_unspecified(&mut diag.suggestions);
+        }
+    }
+}
+
+/// Makes the machine applicable suggestions no longer applied by `cargo fix`.
+fn make_unspecified(suggestions: &mut Suggestions) {
+    let suggestions = match suggestions {
+        Suggestions::Enabled(suggs) => &mut **suggs,
+        Suggestions::Sealed(suggs) => &mut **suggs,
+        Suggestions::Disabled => return,
+    };
+    for sugg in suggestions {
+        if sugg.applicability == Applicability::MachineApplicable {
+            sugg.applicability = Applicability::Unspecified;
+        }
+    }
+}
+
+/// The lints which are not documented on clippy's website, like the lints of the `custom-lints`
+/// configuration.
+static UNDOCUMENTED_LINTS: OnceLock<FxHashSet<LintId>> = OnceLock::new();
+
+/// Omits the link to clippy's website from the diagnostics of `lints`. Only the first call has an
+/// effect.
+pub fn set_undocumented_lints(lints: impl IntoIterator<Item = &'static Lint>) {
+    let _ = UNDOCUMENTED_LINTS.set(lints.into_iter().map(LintId::of).collect());
+}
 
 fn docs_link(diag: &mut Diag<'_, ()>, lint: &'static Lint) {
-    if env::var("CLIPPY_DISABLE_DOCS_LINKS").is_err() {
+    if env::var("CLIPPY_DISABLE_DOCS_LINKS").is_err()
+        && !IN_PLUGIN.load(Ordering::Relaxed)
+        && !UNDOCUMENTED_LINTS
+            .get()
+            .is_some_and(|lints| lints.contains(&LintId::of(lint)))
+    {
         if let Some(lint) = lint.name_lower().strip_prefix("clippy::") {
             diag.help(format!(
                 "for further information visit https://rust-lang.github.io/rust-clippy/{}/index.html#{lint}",
@@ -99,14 +295,25 @@ fn validate_diag(diag: &Diag<'_, impl EmissionGuarantee>) {
 ///    |     ^^^^^^^^^^^^^^^^^^^^^^^
 /// ```
 pub fn span_lint<T: LintContext>(cx: &T, lint: &'static Lint, sp: impl Into<MultiSpan>, msg: impl Into<DiagMessage>) {
+    let sp = sp.into();
+    if is_dropped(cx, lint, &sp) {
+        return;
+    }
+    let primary_span = sp.primary_span();
+    let mut emitted = false;
     #[expect(clippy::disallowed_methods)]
     cx.span_lint(lint, sp, |diag| {
+        emitted = true;
         diag.primary_message(msg);
+        restrict_fixes(diag, lint);
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
         validate_diag(diag);
     });
+    if !emitted {
+        record_emission(cx.sess(), lint, primary_span);
+    }
 }
 
 /// Same as `span_lint` but with an extra `help` message.
@@ -151,19 +358,30 @@ pub fn span_lint_and_help<T: LintContext>(
     help_span: Option<Span>,
     help: impl Into<SubdiagMessage>,
 ) {
+    let span = span.into();
+    if is_dropped(cx, lint, &span) {
+        return;
+    }
+    let primary_span = span.primary_span();
+    let mut emitted = false;
     #[expect(clippy::disallowed_methods)]
     cx.span_lint(lint, span, |diag| {
+        emitted = true;
         diag.primary_message(msg);
         if let Some(help_span) = help_span {
             diag.span_help(help_span, help.into());
         } else {
             diag.help(help.into());
         }
+        restrict_fixes(diag, lint);
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
         validate_diag(diag);
     });
+    if !emitted {
+        record_emission(cx.sess(), lint, primary_span);
+    }
 }
 
 /// Like `span_lint` but with a `note` section instead of a `help` message.
@@ -211,19 +429,30 @@ pub fn span_lint_and_note<T: LintContext>(
     note_span: Option<Span>,
     note: impl Into<SubdiagMessage>,
 ) {
+    let span = span.into();
+    if is_dropped(cx, lint, &span) {
+        return;
+    }
+    let primary_span = span.primary_span();
+    let mut emitted = false;
     #[expect(clippy::disallowed_methods)]
     cx.span_lint(lint, span, |diag| {
+        emitted = true;
         diag.primary_message(msg);
         if let Some(note_span) = note_span {
             diag.span_note(note_span, note.into());
         } else {
             diag.note(note.into());
         }
+        restrict_fixes(diag, lint);
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
         validate_diag(diag);
     });
+    if !emitted {
+        record_emission(cx.sess(), lint, primary_span);
+    }
 }
 
 /// Like `span_lint` but allows to add notes, help and suggestions using a closure.
@@ -251,15 +480,26 @@ where
     M: Into<DiagMessage>,
     F: FnOnce(&mut Diag<'_, ()>),
 {
+    let sp = sp.into();
+    if is_dropped(cx, lint, &sp) {
+        return;
+    }
+    let primary_span = sp.primary_span();
+    let mut emitted = false;
     #[expect(clippy::disallowed_methods)]
     cx.span_lint(lint, sp, |diag| {
+        emitted = true;
         diag.primary_message(msg);
         f(diag);
+        restrict_fixes(diag, lint);
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
         validate_diag(diag);
     });
+    if !emitted {
+        record_emission(cx.sess(), lint, primary_span);
+    }
 }
 
 /// Like [`span_lint`], but emits the lint at the node identified by the given `HirId`.
@@ -287,9 +527,16 @@ where
 /// the compiler check lint level attributes at the place of the expression and
 /// the `#[allow]` will work.
 pub fn span_lint_hir(cx: &LateContext<'_>, lint: &'static Lint, hir_id: HirId, sp: Span, msg: impl Into<DiagMessage>) {
-    #[expect(clippy::disallowed_methods)]
-    cx.tcx.node_span_lint(lint, hir_id, sp, |diag| {
+    let (level, source) = cx.tcx.lint_level_at_node(lint, hir_id);
+    record_allow((level, source));
+    let sp = sp.into();
+    if is_dropped_at(cx.sess().source_map(), level, &sp) {
+        return;
+    }
+    // What `TyCtxt::node_span_lint` does, without computing the level again
+    lint_level(cx.sess(), lint, level, source, Some(sp), |diag| {
         diag.primary_message(msg);
+        restrict_fixes(diag, lint);
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
@@ -329,10 +576,17 @@ pub fn span_lint_hir_and_then(
     msg: impl Into<DiagMessage>,
     f: impl FnOnce(&mut Diag<'_, ()>),
 ) {
-    #[expect(clippy::disallowed_methods)]
-    cx.tcx.node_span_lint(lint, hir_id, sp, |diag| {
+    let (level, source) = cx.tcx.lint_level_at_node(lint, hir_id);
+    record_allow((level, source));
+    let sp = sp.into();
+    if is_dropped_at(cx.sess().source_map(), level, &sp) {
+        return;
+    }
+    // What `TyCtxt::node_span_lint` does, without computing the level again
+    lint_level(cx.sess(), lint, level, source, Some(sp), |diag| {
         diag.primary_message(msg);
         f(diag);
+        restrict_fixes(diag, lint);
         docs_link(diag, lint);
 
         #[cfg(debug_assertions)]
@@ -390,3 +644,39 @@ pub fn span_lint_and_sugg<T: LintContext>(
         validate_diag(diag);
     });
 }
+
+/// Like [`span_lint_and_sugg`], but with a suggestion made of edits in several places, built with
+/// a [`SuggGroup`]. `--fix` applies the edits of the group together or not at all.
+///
+/// # Example
+///
+/// ```text
+/// error: consider using `sort_by_key`
+///   --> tests/ui/unnecessary_sort_by.rs:20:5
+///    |
+/// LL |     vec.sort_by(|a, b| (b + 5).abs().cmp(&(a + 5).abs()));
+///    |     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+///    |
+/// help: try
+///    |
+/// LL + use std::cmp::Reverse;
+///  ...
+/// LL ~     vec.sort_by_key(|b| Reverse((b + 5).abs()));
+///    |
+/// ```
+pub fn span_lint_and_sugg_group<T: LintContext>(
+    cx: &T,
+    lint: &'static Lint,
+    sp: Span,
+    msg: impl Into<DiagMessage>,
+    help: impl Into<SubdiagMessage>,
+    sugg: SuggGroup,
+    applicability: Applicability,
+) {
+    span_lint_and_then(cx, lint, sp, msg.into(), |diag| {
+        sugg.suggest(diag, help.into(), applicability);
+
+        #[cfg(debug_assertions)]
+        validate_diag(diag);
+    });
+}
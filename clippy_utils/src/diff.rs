@@ -0,0 +1,212 @@
+//! The lines changed since a git revision, used by `cargo clippy --diff-base` to only report the
+//! lints on new code.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::HirId;
+use rustc_hir::def::DefKind;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, Span, StableSourceFileId};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+/// The lines changed in each file, by absolute path. A range is empty where lines were only
+/// removed, it then starts at the line following the removed ones.
+pub(crate) struct ChangedLines {
+    files: FxHashMap<PathBuf, Vec<Range<usize>>>,
+    items: bool,
+    /// The spans of the items of the crate and whether they contain a changed line, set by
+    /// [`collect_items`]
+    item_spans: OnceLock<Vec<(Span, bool)>>,
+    /// The canonical path of each source file looked up so far, `None` for the files which are
+    /// not on the disk
+    paths: Mutex<FxHashMap<StableSourceFileId, Option<PathBuf>>>,
+}
+
+static CHANGED_LINES: OnceLock<&'static ChangedLines> = OnceLock::new();
+
+/// Only keeps the lints whose primary span touches a line changed since the git revision `base`.
+/// With `items`, the lints in an item containing a changed line are kept as well.
+///
+/// The changes are read from the git repository of the current directory. When there is none, or
+/// when `base` is not a revision of that repository, no lines are considered changed.
+pub fn filter_lints_by_diff(base: &str, items: bool) {
+    let files = changed_files(base).unwrap_or_default();
+    let _ = CHANGED_LINES.set(Box::leak(Box::new(ChangedLines {
+        files,
+        items,
+        item_spans: OnceLock::new(),
+        paths: Mutex::default(),
+    })));
+}
+
+/// Collects the items containing a changed line for `--diff-items`, once the HIR is available. The
+/// lints emitted before, by the early passes, are only kept on the changed lines.
+pub fn collect_items(tcx: TyCtxt<'_>) {
+    let Some(changes) = CHANGED_LINES.get().filter(|changes| changes.items) else {
+        return;
+    };
+    let sm = tcx.sess.source_map();
+    let items = tcx
+        .hir_crate_items(())
+        .owners()
+        .filter(|owner| tcx.def_kind(owner.def_id) != DefKind::Mod)
+        .map(|owner| {
+            let span = tcx.hir().span_with_body(HirId::make_owner(owner.def_id));
+            (span, changes.touches(sm, span))
+        })
+        .collect();
+    let _ = changes.item_spans.set(items);
+}
+
+/// Returns the lines set by [`filter_lints_by_diff`], which are shared with the plugins.
+pub(crate) fn changed_lines() -> Option<&'static ChangedLines> {
+    CHANGED_LINES.get().copied()
+}
+
+/// Uses the changed lines of the `clippy_utils` of `clippy-driver` in the copy of a plugin.
+pub(crate) fn share(changes: Option<&'static ChangedLines>) {
+    if let Some(changes) = changes {
+        let _ = CHANGED_LINES.set(changes);
+    }
+}
+
+/// Checks if a lint emitted at `span` is kept by [`filter_lints_by_diff`].
+pub(crate) fn is_kept(sm: &SourceMap, span: Option<Span>) -> bool {
+    let (Some(changes), Some(span)) = (CHANGED_LINES.get(), span) else {
+        return true;
+    };
+    changes.touches(sm, span) || changes.touches(sm, span.source_callsite()) || changes.in_changed_item(span)
+}
+
+impl ChangedLines {
+    /// Checks if the innermost item containing `span` contains a changed line
+    fn in_changed_item(&self, span: Span) -> bool {
+        let span = span.source_callsite();
+        self.item_spans
+            .get()
+            .into_iter()
+            .flatten()
+            .filter(|(item, _)| item.contains(span))
+            .min_by_key(|(item, _)| item.hi() - item.lo())
+            .is_some_and(|&(_, changed)| changed)
+    }
+
+    fn touches(&self, sm: &SourceMap, span: Span) -> bool {
+        let lo = sm.lookup_char_pos(span.lo());
+        let hi = sm.lookup_char_pos(span.hi());
+        let mut paths = self.paths.lock().unwrap();
+        let path = paths.entry(lo.file.stable_id).or_insert_with(|| {
+            let FileName::Real(name) = &lo.file.name else {
+                return None;
+            };
+            fs::canonicalize(name.local_path()?).ok()
+        });
+        path.as_ref()
+            .and_then(|path| self.files.get(path))
+            .is_some_and(|ranges| ranges.iter().any(|range| range.start <= hi.line && lo.line < range.end))
+    }
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").current_dir(dir).args(args).output().ok()?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+/// Runs `git diff` against `base`, untracked files are changed entirely
+fn changed_files(base: &str) -> Option<FxHashMap<PathBuf, Vec<Range<usize>>>> {
+    let root = git(Path::new("."), &["rev-parse", "--show-toplevel"])?;
+    let root = fs::canonicalize(root.trim_end()).ok()?;
+    let diff = git(&root, &[
+        "diff",
+        "--unified=0",
+        "--no-color",
+        "--no-ext-diff",
+        "--no-relative",
+        "--src-prefix=a/",
+        "--dst-prefix=b/",
+        base,
+    ])?;
+    let mut files = parse_diff(&diff, &root);
+    for path in git(&root, &["ls-files", "--others", "--exclude-standard", "--full-name"])?.lines() {
+        files.entry(root.join(path)).or_default().push(1..usize::MAX);
+    }
+    Some(files)
+}
+
+/// Collects the lines of the new files in the hunks of a diff produced with `--unified=0`
+fn parse_diff(diff: &str, root: &Path) -> FxHashMap<PathBuf, Vec<Range<usize>>> {
+    let mut files = FxHashMap::<PathBuf, Vec<_>>::default();
+    let mut file = None;
+    let mut in_header = false;
+    for line in diff.lines() {
+        if line.starts_with("diff ") {
+            in_header = true;
+            file = None;
+        } else if in_header && let Some(path) = line.strip_prefix("+++ ") {
+            file = path.strip_prefix("b/").map(|path| root.join(path));
+        } else if let Some(hunk) = line.strip_prefix("@@ ") {
+            in_header = false;
+            if let Some(file) = &file
+                && let Some(range) = parse_hunk(hunk)
+            {
+                files.entry(file.clone()).or_default().push(range);
+            }
+        }
+    }
+    files
+}
+
+/// Parses the lines of the new file from a hunk header, e.g. `-10,2 +12,3 @@`
+fn parse_hunk(hunk: &str) -> Option<Range<usize>> {
+    let new = hunk.split(' ').nth(1)?.strip_prefix('+')?;
+    let (start, len) = match new.split_once(',') {
+        Some((start, len)) => (start.parse::<usize>().ok()?, len.parse::<usize>().ok()?),
+        None => (new.parse().ok()?, 1),
+    };
+    // An empty hunk follows the line it starts at
+    let start = if len == 0 { start + 1 } else { start };
+    Some(start..start + len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_diff;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_diff() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3 +3 @@ fn a() {
+-    old();
++    new();
+@@ -10,2 +10,0 @@ fn b() {
+-    removed();
+-    removed();
+@@ -20,0 +19,3 @@ fn c() {
++++ added
++    added();
++    added();
+diff --git a/src/removed.rs b/src/removed.rs
+deleted file mode 100644
+--- a/src/removed.rs
++++ /dev/null
+@@ -1 +0,0 @@
+-fn removed() {}
+";
+        let files = parse_diff(diff, Path::new("/repo"));
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[Path::new("/repo/src/lib.rs")], [3..4, 11..11, 19..22]);
+    }
+}
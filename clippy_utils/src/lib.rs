@@ -42,6 +42,7 @@ extern crate rustc_hir;
 extern crate rustc_hir_typeck;
 extern crate rustc_index;
 extern crate rustc_infer;
+extern crate rustc_interface;
 extern crate rustc_lexer;
 extern crate rustc_lint;
 extern crate rustc_middle;
@@ -61,6 +62,7 @@ mod check_proc_macro;
 pub mod comparisons;
 pub mod consts;
 pub mod diagnostics;
+pub mod diff;
 pub mod eager_or_lazy;
 pub mod higher;
 mod hir_utils;
@@ -69,6 +71,7 @@ pub mod mir;
 pub mod msrvs;
 pub mod numeric_literal;
 pub mod paths;
+pub mod plugin;
 pub mod ptr;
 pub mod qualify_min_const_fn;
 pub mod source;
@@ -1855,7 +1858,9 @@ pub fn fulfill_or_allowed(cx: &LateContext<'_>, lint: &'static Lint, ids: impl I
     let mut suppress_lint = false;
 
     for id in ids {
-        let (level, _) = cx.tcx.lint_level_at_node(lint, id);
+        let level_and_source = cx.tcx.lint_level_at_node(lint, id);
+        let level = level_and_source.0;
+        diagnostics::record_allow(level_and_source);
         if let Some(expectation) = level.get_expectation_id() {
             cx.fulfill_expectation(expectation);
         }
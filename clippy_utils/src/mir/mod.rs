@@ -1,10 +1,16 @@
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::steal::Steal;
+use rustc_hir::def_id::LocalDefId;
 use rustc_hir::{Expr, HirId};
 use rustc_index::bit_set::BitSet;
 use rustc_middle::mir::visit::{MutatingUseContext, NonMutatingUseContext, PlaceContext, Visitor};
 use rustc_middle::mir::{
     BasicBlock, Body, InlineAsmOperand, Local, Location, Place, START_BLOCK, StatementKind, TerminatorKind, traversal,
 };
+use rustc_middle::query::Providers;
 use rustc_middle::ty::TyCtxt;
+use std::ptr;
+use std::sync::{Mutex, OnceLock};
 
 mod possible_borrower;
 pub use possible_borrower::PossibleBorrowerMap;
@@ -129,12 +135,69 @@ pub fn used_exactly_once(mir: &Body<'_>, local: Local) -> Option<bool> {
     })
 }
 
-/// Returns the `mir::Body` containing the node associated with `hir_id`.
+/// The default provider of `mir_drops_elaborated_and_const_checked`, wrapped by
+/// [`override_queries`]
+static DROPS_ELABORATED_PROVIDER: OnceLock<for<'tcx> fn(TyCtxt<'tcx>, LocalDefId) -> &'tcx Steal<Body<'tcx>>> =
+    OnceLock::new();
+
+/// The copies of the bodies made by [`override_queries`], by their owner
+static ANALYSIS_MIR: OnceLock<Mutex<FxHashMap<LocalDefId, AnalysisMir>>> = OnceLock::new();
+
+/// A body allocated in the arena of the `TyCtxt`, with its lifetime erased
+struct AnalysisMir(*const Body<'static>);
+
+// SAFETY: the body is never mutated, it is only read by the lint passes of the same `TyCtxt`
+unsafe impl Send for AnalysisMir {}
+
+/// Overrides the `mir_drops_elaborated_and_const_checked` query to keep a copy of each body for
+/// [`analysis_mir`], before `optimized_mir` steals and optimizes it. `clippy-driver` installs it
+/// with `Config::override_queries`.
+pub fn override_queries(providers: &mut Providers) {
+    let _ = DROPS_ELABORATED_PROVIDER.set(providers.mir_drops_elaborated_and_const_checked);
+    providers.mir_drops_elaborated_and_const_checked = mir_drops_elaborated_and_const_checked;
+}
+
+fn mir_drops_elaborated_and_const_checked(tcx: TyCtxt<'_>, def_id: LocalDefId) -> &Steal<Body<'_>> {
+    let body = DROPS_ELABORATED_PROVIDER.get().unwrap()(tcx, def_id);
+    let copy: &Body<'_> = tcx.arena.alloc(body.borrow().clone());
+    ANALYSIS_MIR
+        .get_or_init(|| Mutex::new(FxHashMap::default()))
+        .lock()
+        .unwrap()
+        .insert(def_id, AnalysisMir(ptr::from_ref(copy).cast()));
+    body
+}
+
+/// Returns the `mir::Body` lints should analyze for the body owned by `def_id`.
+///
+/// This is the MIR after drop elaboration, before the optimization passes selected by
+/// `-Zmir-opt-level` run. Those passes may remove or merge the locals and statements lints look
+/// for, so `optimized_mir` should not be used by lints. The body is copied once, when the
+/// `mir_drops_elaborated_and_const_checked` query overridden by [`override_queries`] is computed.
+///
+/// # Panics
+///
+/// Panics if the queries were not overridden with [`override_queries`].
+#[allow(clippy::module_name_repetitions)]
+pub fn analysis_mir(tcx: TyCtxt<'_>, def_id: LocalDefId) -> &Body<'_> {
+    // Computes the body if it wasn't yet, e.g. when its dependencies didn't change in an incremental
+    // build
+    let _ = tcx.mir_drops_elaborated_and_const_checked(def_id);
+    let body = ANALYSIS_MIR
+        .get()
+        .and_then(|bodies| bodies.lock().unwrap().get(&def_id).map(|body| body.0))
+        .expect("the queries are overridden by `clippy_utils::mir::override_queries`");
+    // SAFETY: the body was allocated in the arena of `tcx`, which lives as long as the compilation
+    // session. `clippy-driver` runs a single session, so `def_id` always refers to the same body.
+    unsafe { &*body.cast() }
+}
+
+/// Returns the `mir::Body` containing the node associated with `hir_id`, see [`analysis_mir`].
 #[allow(clippy::module_name_repetitions)]
 pub fn enclosing_mir(tcx: TyCtxt<'_>, hir_id: HirId) -> Option<&Body<'_>> {
     let body_owner_local_def_id = tcx.hir().enclosing_body_owner(hir_id);
     if tcx.hir().body_owner_kind(body_owner_local_def_id).is_fn_or_closure() {
-        Some(tcx.optimized_mir(body_owner_local_def_id.to_def_id()))
+        Some(analysis_mir(tcx, body_owner_local_def_id))
     } else {
         None
     }
@@ -0,0 +1,138 @@
+//! Lints maintained outside of clippy, e.g. the lints specific to an organization.
+//!
+//! A plugin is a crate declaring a [`Plugin`], which registers its lint passes in the `LintStore`
+//! of `clippy-driver`. It is either compiled as a `dylib` exporting its plugin with
+//! [`export_clippy_plugin!`] and listed in the `CLIPPY_PLUGINS` environment variable, or linked
+//! into `clippy-driver` as a companion crate and added to the `LINKED` plugins in `src/plugins.rs`.
+//!
+//! Plugins should only use the helpers re-exported by a versioned module like [`v1`], the other
+//! modules of `clippy_utils` change without notice. This only keeps the source of a plugin
+//! compiling with newer versions of clippy: like any crate using `rustc_private`, a dylib must be
+//! compiled again with the toolchain and the version of `clippy_utils` of `clippy-driver`, which
+//! refuses to load it otherwise, see [`build_id`].
+
+use crate::{diagnostics, diff};
+use rustc_data_structures::fx::FxHashSet;
+use rustc_lint::LintStore;
+use std::ffi::{CStr, CString, c_char};
+use std::sync::{Mutex, OnceLock};
+
+/// The version of the helpers re-exported for the plugins. It is increased when they change in a
+/// way that breaks the plugins using them.
+pub const API_VERSION: u32 = 1;
+
+/// A plugin, see the [module documentation](self).
+pub struct Plugin {
+    /// Registers the lints and the lint passes of the plugin
+    pub register: fn(&mut LintStore),
+}
+
+impl Plugin {
+    /// Creates a plugin registering its lints with `register`.
+    ///
+    /// The lints of a plugin should be declared in the `clippy` tool namespace with
+    /// `rustc_session::declare_tool_lint!`, so that they can be configured like clippy's lints.
+    pub const fn new(register: fn(&mut LintStore)) -> Self {
+        Self { register }
+    }
+}
+
+/// The state of `clippy_utils` in `clippy-driver`, shared with the copy of `clippy_utils` a dylib
+/// plugin is compiled with, e.g. the changed lines of `cargo clippy --diff-base`.
+pub struct Host {
+    changed_lines: Option<&'static diff::ChangedLines>,
+    fixed_lints: Option<&'static FxHashSet<String>>,
+    suppressions: &'static Mutex<diagnostics::Suppressions>,
+}
+
+impl Host {
+    /// Returns the state of this copy of `clippy_utils`, to be shared with the plugins.
+    pub fn current() -> Self {
+        Self {
+            changed_lines: diff::changed_lines(),
+            fixed_lints: diagnostics::fixed_lints(),
+            suppressions: diagnostics::suppressions(),
+        }
+    }
+}
+
+/// Uses the state of `host` in this copy of `clippy_utils`. Called by the entry point of
+/// [`export_clippy_plugin!`].
+#[doc(hidden)]
+pub fn init(host: &Host) {
+    diff::share(host.changed_lines);
+    diagnostics::share(host.suppressions, host.fixed_lints);
+}
+
+/// Identifies the version of rustc and of `clippy_utils` this copy of `clippy_utils` is linked
+/// with. The types of rustc and of this crate, like [`Host`] and the `LintStore`, only have the
+/// same layout in `clippy-driver` and in a dylib plugin when both are the same.
+pub fn build_id() -> &'static CStr {
+    static BUILD_ID: OnceLock<CString> = OnceLock::new();
+    BUILD_ID.get_or_init(|| {
+        let rustc = rustc_interface::util::rustc_version_str().unwrap_or("unknown");
+        let id = format!(
+            "rustc {rustc}, clippy_utils {} (plugin API {API_VERSION})",
+            env!("CARGO_PKG_VERSION")
+        );
+        CString::new(id).unwrap()
+    })
+}
+
+/// The name of the function returning the [`build_id`] of a dylib plugin. It uses the C ABI, so
+/// that it can be called before the plugin is known to be compiled like `clippy-driver`.
+pub const BUILD_ID_SYMBOL: &str = "__clippy_plugin_build_id";
+
+/// The type of the function returning the [`build_id`] of a dylib plugin.
+pub type BuildIdFn = extern "C" fn() -> *const c_char;
+
+/// The name of the entry point exported by a dylib plugin.
+pub const ENTRY_POINT: &str = "__clippy_plugin";
+
+/// The type of the entry point exported by a dylib plugin.
+pub type EntryPoint = fn(&Host) -> &'static Plugin;
+
+/// Exports a [`Plugin`] from a crate compiled as a `dylib`, so that `clippy-driver` can load it
+/// from `CLIPPY_PLUGINS`.
+///
+/// ```rust,ignore
+/// pub const PLUGIN: Plugin = Plugin::new(register);
+///
+/// clippy_utils::export_clippy_plugin!(PLUGIN);
+/// ```
+#[macro_export]
+macro_rules! export_clippy_plugin {
+    ($plugin:path) => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn __clippy_plugin_build_id() -> *const ::std::ffi::c_char {
+            $crate::plugin::build_id().as_ptr()
+        }
+
+        #[unsafe(no_mangle)]
+        pub fn __clippy_plugin(host: &$crate::plugin::Host) -> &'static $crate::plugin::Plugin {
+            $crate::plugin::init(host);
+            static EXPORTED: $crate::plugin::Plugin = $plugin;
+            &EXPORTED
+        }
+    };
+}
+
+/// The helpers supported by the first version of the plugin API.
+pub mod v1 {
+    pub use super::{API_VERSION, Host, Plugin};
+    pub use crate::diagnostics::{
+        span_lint, span_lint_and_help, span_lint_and_note, span_lint_and_sugg, span_lint_and_then, span_lint_hir,
+        span_lint_hir_and_then,
+    };
+    pub use crate::source::{snippet, snippet_opt, snippet_with_applicability};
+    pub use crate::sugg::Sugg;
+    pub use crate::{
+        export_clippy_plugin, get_parent_expr, get_trait_def_id, is_lint_allowed, is_trait_method, match_def_path,
+        path_res, paths, peel_blocks,
+    };
+
+    /// The helpers of [`crate::ty`] supported by the first version of the plugin API.
+    pub mod ty {
+        pub use crate::ty::{implements_trait, is_type_diagnostic_item, is_type_lang_item, match_type};
+    }
+}
@@ -3,19 +3,21 @@
 
 use crate::source::{snippet, snippet_opt, snippet_with_applicability, snippet_with_context};
 use crate::ty::expr_sig;
-use crate::{get_parent_expr_for_hir, higher};
+use crate::{def_path_res, get_parent_expr_for_hir, higher};
 use rustc_ast::util::parser::AssocOp;
 use rustc_ast::{ast, token};
 use rustc_ast_pretty::pprust::token_kind_to_string;
-use rustc_errors::Applicability;
+use rustc_errors::{Applicability, Diag, SubdiagMessage};
 use rustc_hir as hir;
-use rustc_hir::{Closure, ExprKind, HirId, MutTy, TyKind};
+use rustc_hir::def::Res;
+use rustc_hir::{Closure, ExprKind, HirId, ItemKind, MutTy, TyKind, UseKind};
 use rustc_hir_typeck::expr_use_visitor::{Delegate, ExprUseVisitor, PlaceBase, PlaceWithHirId};
 use rustc_lint::{EarlyContext, LateContext, LintContext};
 use rustc_middle::hir::place::ProjectionKind;
 use rustc_middle::mir::{FakeReadCause, Mutability};
 use rustc_middle::ty;
-use rustc_span::{BytePos, CharPos, Pos, Span, SyntaxContext};
+use rustc_span::symbol::kw;
+use rustc_span::{BytePos, CharPos, Pos, Span, Symbol, SyntaxContext};
 use std::borrow::Cow;
 use std::fmt::{self, Display, Write as _};
 use std::ops::{Add, Neg, Not, Sub};
@@ -734,7 +736,7 @@ pub trait DiagExt<T: LintContext> {
     fn suggest_remove_item(&mut self, cx: &T, item: Span, msg: &str, applicability: Applicability);
 }
 
-impl<T: LintContext> DiagExt<T> for rustc_errors::Diag<'_, ()> {
+impl<T: LintContext> DiagExt<T> for Diag<'_, ()> {
     fn suggest_item_with_attr<D: Display + ?Sized>(
         &mut self,
         cx: &T,
@@ -788,6 +790,175 @@ impl<T: LintContext> DiagExt<T> for rustc_errors::Diag<'_, ()> {
     }
 }
 
+/// A suggestion made of edits in several places of a file, e.g. importing an item and rewriting a
+/// call using it. The edits are emitted as a single multipart suggestion, which `--fix` applies
+/// all together or not at all.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut sugg = SuggGroup::default();
+/// let reverse = sugg.import(cx, expr.hir_id, &["std", "cmp", "Reverse"]);
+/// sugg.replace(arg.span, format!("{reverse}({})", snippet(cx, arg.span, "..")));
+/// span_lint_and_sugg_group(cx, LINT, expr.span, "msg", "try", sugg, Applicability::MachineApplicable);
+/// ```
+#[derive(Debug, Default)]
+pub struct SuggGroup {
+    /// The replacements of the suggestion, the insertions have an empty span
+    parts: Vec<(Span, String)>,
+}
+
+impl SuggGroup {
+    /// Replaces `span` by `replacement`.
+    pub fn replace(&mut self, span: Span, replacement: impl Into<String>) -> &mut Self {
+        self.parts.push((span, replacement.into()));
+        self
+    }
+
+    /// Imports the item at `path` in the module containing `hir_id`, unless it is imported there
+    /// already, and returns how the item can be named in the module: its name, or its full path
+    /// when another item of that name is in the module.
+    ///
+    /// The `use` is inserted on its own line among the other `use` items of the module, before the
+    /// first one with a greater path in the order of rustfmt, or before the first item of the
+    /// module when there are none. Every suggestion importing the item inserts the `use`, so each
+    /// of them can be applied on its own, see below for how the fix appliers make it only once.
+    pub fn import(&mut self, cx: &LateContext<'_>, hir_id: HirId, path: &[&str]) -> String {
+        let full_path = path.join("::");
+        let Some(def_id) = def_path_res(cx.tcx, path).iter().find_map(Res::opt_def_id) else {
+            return full_path;
+        };
+        let name = Symbol::intern(path.last().unwrap());
+        let (module, _, _) = cx.tcx.hir().get_module(cx.tcx.parent_module(hir_id));
+
+        // The `use` items of the module, with their spans including their attributes
+        let mut uses = Vec::new();
+        for &item_id in module.item_ids {
+            let item = cx.tcx.hir().item(item_id);
+            if item.span.from_expansion() {
+                continue;
+            }
+            if let ItemKind::Use(use_path, kind) = item.kind {
+                if kind == UseKind::Single
+                    && item.ident.name == name
+                    && use_path.res.iter().any(|res| res.opt_def_id() == Some(def_id))
+                {
+                    return name.to_string();
+                }
+                let lo = cx
+                    .tcx
+                    .hir()
+                    .attrs(item.hir_id())
+                    .iter()
+                    .map(|attr| attr.span.lo())
+                    .fold(item.span.lo(), BytePos::min);
+                let mut key: Vec<_> = use_path
+                    .segments
+                    .iter()
+                    .filter(|segment| segment.ident.name != kw::PathRoot)
+                    .map(|segment| UseSegment::new(segment.ident.as_str()))
+                    .collect();
+                match kind {
+                    UseKind::Single => {},
+                    UseKind::Glob => key.push(UseSegment::Glob),
+                    UseKind::ListStem => key.push(UseSegment::List),
+                }
+                uses.push((item.span.with_lo(lo), key));
+            }
+            if item.ident.name == name {
+                return full_path;
+            }
+        }
+
+        // The items of a `use` list are lowered to separate items, with the span of their part of the
+        // list, which is within the span of the whole `use`
+        uses.sort_by_key(|(span, _)| (span.lo(), std::cmp::Reverse(span.hi())));
+        uses.dedup_by(|(inner, _), (outer, _)| outer.contains(*inner));
+
+        let key: Vec<_> = path.iter().map(|segment| UseSegment::new(segment)).collect();
+        let source_map = cx.sess().source_map();
+        // The start of the line of `pos`, or of the line after it
+        let line_start = |pos: BytePos, next: usize| {
+            let file = source_map.lookup_source_file(pos);
+            let line = file.lookup_line(file.relative_position(pos))? + next;
+            (line < file.count_lines()).then(|| file.line_bounds(line).start)
+        };
+        let (pos, indent, insertion) = if let Some((next, _)) = uses.iter().find(|(_, use_key)| *use_key > key) {
+            (line_start(next.lo(), 0), indentation(cx, *next), String::new())
+        } else if let Some((last, _)) = uses.last() {
+            (line_start(last.hi(), 1), indentation(cx, *last), String::new())
+        } else {
+            let span = module.spans.inject_use_span;
+            if span.from_expansion() {
+                return full_path;
+            }
+            (line_start(span.lo(), 0), indentation(cx, span), "\n".to_string())
+        };
+        let (Some(pos), Some(indent)) = (pos, indent) else {
+            return full_path;
+        };
+
+        // rustfix makes an identical replacement of the same span only once, but makes identical
+        // insertions as many times as they are suggested. The `use` replaces the character it is
+        // inserted before, so several suggestions importing the item only import it once.
+        let file = source_map.lookup_source_file(pos);
+        let Some(next) = file
+            .src
+            .as_deref()
+            .and_then(|src| src[file.relative_position(pos).to_usize()..].chars().next())
+        else {
+            return full_path;
+        };
+        let part = (
+            Span::new(
+                pos,
+                pos + BytePos::from_usize(next.len_utf8()),
+                SyntaxContext::root(),
+                None,
+            ),
+            format!("{indent}use {full_path};\n{insertion}{next}"),
+        );
+        if !self.parts.contains(&part) {
+            self.parts.push(part);
+        }
+        name.to_string()
+    }
+
+    /// Adds the suggestion to `diag`.
+    pub fn suggest(self, diag: &mut Diag<'_, ()>, msg: impl Into<SubdiagMessage>, applicability: Applicability) {
+        diag.multipart_suggestion(msg, self.parts, applicability);
+    }
+}
+
+/// A segment of the path of a `use`, ordered like rustfmt orders them
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum UseSegment {
+    /// `self`
+    SelfLower,
+    /// `super`
+    Super,
+    /// `crate`
+    Crate,
+    /// Any other name
+    Name(String),
+    /// The `*` of a glob import
+    Glob,
+    /// The `{..}` of a `use` list
+    List,
+}
+
+impl UseSegment {
+    /// Returns the segment named `name`
+    fn new(name: &str) -> Self {
+        match name {
+            "self" => Self::SelfLower,
+            "super" => Self::Super,
+            "crate" => Self::Crate,
+            _ => Self::Name(name.to_string()),
+        }
+    }
+}
+
 /// Suggestion results for handling closure
 /// args dereferencing and borrowing
 pub struct DerefClosure {
@@ -3,6 +3,7 @@ use std::num::NonZero;
 use std::path::PathBuf;
 
 #[derive(Parser, Clone, Debug)]
+#[expect(clippy::struct_excessive_bools)]
 #[command(args_conflicts_with_subcommands = true)]
 pub(crate) struct LintcheckConfig {
     /// Number of threads to use (default: all unless --fix or --recursive)
@@ -27,12 +28,28 @@ pub(crate) struct LintcheckConfig {
     /// File to save the clippy lint results here
     #[clap(skip = "")]
     pub lintcheck_results_path: PathBuf, // Overridden in new()
+    /// File to save the `--verify` report
+    #[clap(skip = "")]
+    pub verify_results_path: PathBuf, // Overridden in new()
+    /// File to save the `--perf` report
+    #[clap(skip = "")]
+    pub perf_results_path: PathBuf, // Overridden in new()
+    /// Directory to save the reproducers of ICEs
+    #[clap(skip = "")]
+    pub ice_reproducers_path: PathBuf, // Overridden in new()
     /// Only process a single crate on the list
     #[clap(long, value_name = "CRATE")]
     pub only: Option<String>,
     /// Runs cargo clippy --fix and checks if all suggestions apply
     #[clap(long, conflicts_with("max_jobs"))]
     pub fix: bool,
+    /// Applies the suggestions to a copy of each crate and runs `cargo check` on it, reporting the
+    /// errors caused by each lint's suggestions
+    #[clap(long, requires("fix"))]
+    pub verify: bool,
+    /// Also runs `cargo test --no-run` on the fixed crates
+    #[clap(long, requires("verify"))]
+    pub verify_tests: bool,
     /// Apply a filter to only collect specified lints
     #[clap(long = "filter", value_name = "clippy_lint_name", use_value_delimiter = true)]
     pub lint_filter: Vec<String>,
@@ -46,6 +63,9 @@ pub(crate) struct LintcheckConfig {
     /// Run clippy on the dependencies of crates specified in crates-toml
     #[clap(long, conflicts_with("max_jobs"))]
     pub recursive: bool,
+    /// Measure the time spent in each lint pass, and save it in JSON format for `perf-diff`
+    #[clap(long, conflicts_with("fix"))]
+    pub perf: bool,
     #[command(subcommand)]
     pub subcommand: Option<Commands>,
 }
@@ -59,6 +79,17 @@ pub(crate) enum Commands {
         /// This will limit the number of warnings that will be printed for each lint
         #[clap(long)]
         truncate: bool,
+        /// Print an HTML page with the source of the warnings instead of markdown
+        #[clap(long)]
+        html: bool,
+    },
+    /// Display the lint passes which got slower between two `--perf` reports
+    PerfDiff {
+        old: PathBuf,
+        new: PathBuf,
+        /// Only show the passes whose total time grew by more than this percentage
+        #[clap(long, default_value_t = 10.0)]
+        threshold: f64,
     },
     /// Create a lintcheck crates TOML file containing the top N popular crates
     Popular {
@@ -68,6 +99,13 @@ pub(crate) enum Commands {
         #[clap(short, long, default_value_t = 100)]
         number: usize,
     },
+    /// Snapshot the crates in crates-toml and all their dependencies into a directory, from which
+    /// lintcheck can run without network access
+    Vendor {
+        /// The directory to create, the crates TOML to use is written to
+        /// `<DIR>/lintcheck_crates.toml`
+        dir: PathBuf,
+    },
 }
 
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
@@ -75,6 +113,7 @@ pub(crate) enum OutputFormat {
     Text,
     Markdown,
     Json,
+    Html,
 }
 
 impl OutputFormat {
@@ -83,6 +122,7 @@ impl OutputFormat {
             OutputFormat::Text => "txt",
             OutputFormat::Markdown => "md",
             OutputFormat::Json => "json",
+            OutputFormat::Html => "html",
         }
     }
 }
@@ -99,6 +139,13 @@ impl LintcheckConfig {
             filename.display(),
             config.format.file_extension(),
         ));
+        config.verify_results_path = PathBuf::from(format!(
+            "lintcheck-logs/{}_verify.{}",
+            filename.display(),
+            config.format.file_extension(),
+        ));
+        config.perf_results_path = PathBuf::from(format!("lintcheck-logs/{}_perf.json", filename.display()));
+        config.ice_reproducers_path = PathBuf::from(format!("lintcheck-logs/{}_ices", filename.display()));
 
         // look at the --threads arg, if 0 is passed, use the threads count
         if config.max_jobs == 0 {
@@ -1,13 +1,16 @@
+use crate::ice::Invocation;
+use crate::perf::PassTimings;
 use crate::recursive::{DriverInfo, deserialize_line, serialize_line};
 
 use std::io::{self, BufReader, Write};
 use std::net::TcpStream;
 use std::process::{self, Command, Stdio};
-use std::{env, mem};
+use std::{env, fs, mem};
 
 /// 1. Sends [`DriverInfo`] to the [`crate::recursive::LintcheckServer`] running on `addr`
 /// 2. Receives [bool] from the server, if `false` returns `None`
-/// 3. Otherwise sends the stderr of running `clippy-driver` to the server
+/// 3. Otherwise sends the pass timings, when profiling, and then the stderr of running
+///    `clippy-driver` to the server
 fn run_clippy(addr: &str) -> Option<i32> {
     let driver_info = DriverInfo {
         package_name: env::var("CARGO_PKG_NAME").ok()?,
@@ -34,11 +37,29 @@ fn run_clippy(addr: &str) -> Option<i32> {
         _ => mem::replace(&mut include_next, true),
     });
 
-    let output = Command::new(env::var("CLIPPY_DRIVER").expect("missing env CLIPPY_DRIVER"))
-        .args(args)
-        .stdout(Stdio::inherit())
-        .output()
-        .expect("failed to run clippy-driver");
+    let mut cmd = Command::new(env::var("CLIPPY_DRIVER").expect("missing env CLIPPY_DRIVER"));
+    cmd.args(args).stdout(Stdio::inherit());
+
+    // `CLIPPY_PROFILE` is set by `lintcheck --perf`, `clippy-driver` then writes its timings to a
+    // file in `CLIPPY_PROFILE_DIR`
+    let profile_dir = env::var_os("CLIPPY_PROFILE").map(|_| {
+        let dir = env::temp_dir().join(format!("lintcheck-profile-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        cmd.env("CLIPPY_PROFILE_DIR", &dir);
+        dir
+    });
+
+    let output = cmd.output().expect("failed to run clippy-driver");
+
+    let mut timings = Vec::new();
+    if let Some(dir) = profile_dir {
+        for entry in fs::read_dir(&dir).unwrap() {
+            let line = fs::read_to_string(entry.unwrap().path()).unwrap();
+            timings.extend(PassTimings::from_line(&line));
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+    serialize_line(&timings, stream.get_mut());
 
     stream
         .get_mut()
@@ -64,3 +85,30 @@ pub fn drive(addr: &str) {
             .unwrap()
     }))
 }
+
+/// Runs `clippy-driver` as the `RUSTC_WORKSPACE_WRAPPER`, and if it ICEs saves its arguments and
+/// environment to `path` for [`crate::ice`]
+pub fn record_ice(path: &str) {
+    let status = Command::new(env::var("CLIPPY_DRIVER").expect("missing env CLIPPY_DRIVER"))
+        .args(env::args().skip(1))
+        .status()
+        .expect("failed to run clippy-driver");
+
+    if status.code() == Some(101) {
+        let invocation = Invocation {
+            // Skips `lintcheck` and the path to `rustc`
+            args: env::args().skip(2).collect(),
+            env: env::vars()
+                .filter(|(name, _)| {
+                    (name.starts_with("CARGO_") && name != "CARGO_MAKEFLAGS")
+                        || name == "OUT_DIR"
+                        || name == "CLIPPY_ARGS"
+                })
+                .collect(),
+            cwd: env::current_dir().unwrap(),
+        };
+        fs::write(path, serde_json::to_vec(&invocation).unwrap()).unwrap();
+    }
+
+    process::exit(status.code().unwrap_or(1))
+}
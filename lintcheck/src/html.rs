@@ -0,0 +1,592 @@
+//! `--format html` writes a single page which also works offline. Each warning is shown with a
+//! snippet of the extracted sources around its span, and the warnings can be filtered by lint, lint
+//! group and crate. `diff --html` shows the removed and added warnings, and the old and new
+//! versions of the changed ones, side by side.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use walkdir::WalkDir;
+
+use crate::json::{LintJson, LintWarnings, to_html_id};
+use crate::output::{ClippyWarning, RustcIce};
+use crate::{LINTCHECK_SOURCES, clippy_project_root};
+
+/// The number of lines shown before and after the span of a warning
+const CONTEXT_LINES: usize = 3;
+
+const STYLE: &str = r"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+pre { background: #f6f8fa; padding: 0.5em; overflow-x: auto; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: left; }
+td.count { text-align: right; }
+tr.group th { background: #eee; }
+#filters { position: sticky; top: 0; background: white; padding: 0.5em 0; border-bottom: 1px solid #ccc; }
+#filters label { margin-right: 1em; }
+.warning { border: 1px solid #ddd; border-radius: 4px; padding: 0.5em; margin-bottom: 1em; }
+.snippet .number { color: #888; user-select: none; }
+.snippet .span { background: #fff3b0; display: inline-block; width: 100%; }
+.columns { display: grid; grid-template-columns: 1fr 1fr; gap: 1em; }
+.columns > * { min-width: 0; }
+.removed { background: #ffdce0; }
+.added { background: #dcffe4; }
+";
+
+const SCRIPT: &str = r#"
+const filters = document.querySelectorAll("select[data-filter]");
+function update() {
+    const selected = [];
+    for (const select of filters) {
+        if (select.value) selected.push([select.dataset.filter, select.value]);
+    }
+    for (const element of document.querySelectorAll("[data-lint], [data-group], [data-crate]")) {
+        element.hidden = selected.some(([key, value]) => key in element.dataset && element.dataset[key] !== value);
+    }
+}
+for (const select of filters) select.addEventListener("change", update);
+"#;
+
+/// A warning, either from the current run or from a JSON log file
+struct Entry<'a> {
+    /// The lint name e.g. `clippy::bytes_nth`
+    name: &'a str,
+    krate: &'a str,
+    /// The path relative to the extracted sources e.g. `anyhow-1.0.86/src/error.rs`
+    file: &'a str,
+    line_start: usize,
+    line_end: usize,
+    url: &'a str,
+    rendered: &'a str,
+}
+
+impl<'a> Entry<'a> {
+    fn from_warning(warning: &'a ClippyWarning) -> Self {
+        let span = warning.span();
+        Self {
+            name: &warning.name,
+            krate: &warning.krate,
+            file: span
+                .file_name
+                .strip_prefix("target/lintcheck/sources/")
+                .unwrap_or(&span.file_name),
+            line_start: span.line_start,
+            line_end: span.line_end,
+            url: &warning.url,
+            rendered: warning.diag.rendered.as_deref().unwrap_or_default().trim(),
+        }
+    }
+
+    fn from_json(json: &'a LintJson) -> Self {
+        let (file, line) = json.file_line.rsplit_once(':').unwrap_or((&json.file_line, ""));
+        let line = line.parse().unwrap_or(0);
+        Self {
+            name: &json.name,
+            // The file starts with the `<crate>-<version>` directory
+            krate: file.split('/').next().unwrap_or(file),
+            file,
+            line_start: line,
+            line_end: line,
+            url: &json.file_url,
+            rendered: &json.rendered,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Renderer {
+    /// The group of each Clippy lint, by the lint name
+    groups: HashMap<String, String>,
+    /// The absolute path and the contents of the source files, by their path in [`Entry::file`]
+    sources: HashMap<String, Option<(PathBuf, String)>>,
+    lints: BTreeSet<String>,
+    used_groups: BTreeSet<String>,
+    crates: BTreeSet<String>,
+}
+
+impl Renderer {
+    fn new() -> Self {
+        Self {
+            groups: lint_groups(),
+            ..Self::default()
+        }
+    }
+
+    fn group(&self, lint: &str) -> &str {
+        self.groups.get(lint).map_or("rustc", String::as_str)
+    }
+
+    fn write_warning(&mut self, out: &mut String, entry: &Entry<'_>) {
+        self.open_warning(out, entry);
+        writeln!(out, "<pre>{}</pre>\n</div>", escape(entry.rendered)).unwrap();
+    }
+
+    /// Opens the `div` of a warning, and writes its location and source snippet
+    fn open_warning(&mut self, out: &mut String, entry: &Entry<'_>) {
+        let group = self.group(entry.name).to_owned();
+        self.lints.insert(entry.name.to_owned());
+        self.crates.insert(entry.krate.to_owned());
+        writeln!(
+            out,
+            r#"<div class="warning" data-lint="{}" data-group="{}" data-crate="{}">"#,
+            escape(entry.name),
+            escape(&group),
+            escape(entry.krate),
+        )
+        .unwrap();
+        self.used_groups.insert(group);
+
+        self.write_location(out, entry);
+        self.write_snippet(out, entry);
+    }
+
+    fn write_location(&mut self, out: &mut String, entry: &Entry<'_>) {
+        let location = format!("{}:{}", entry.file, entry.line_start);
+        write!(out, "<div><code>{}</code> at ", escape(entry.name)).unwrap();
+        match self.source(entry.file) {
+            Some((path, _)) => write!(
+                out,
+                r#"<a href="file://{}#L{}">{}</a>"#,
+                escape(&path.display().to_string()),
+                entry.line_start,
+                escape(&location)
+            )
+            .unwrap(),
+            None => out.push_str(&escape(&location)),
+        }
+        if entry.url.starts_with("http") {
+            write!(out, r#" (<a href="{}">online</a>)"#, escape(entry.url)).unwrap();
+        }
+        out.push_str("</div>\n");
+    }
+
+    fn write_snippet(&mut self, out: &mut String, entry: &Entry<'_>) {
+        let Some((_, source)) = self.source(entry.file) else {
+            return;
+        };
+        if entry.line_start == 0 {
+            return;
+        }
+
+        let first = entry.line_start.saturating_sub(CONTEXT_LINES).max(1);
+        let last = entry.line_end + CONTEXT_LINES;
+        out.push_str(r#"<pre class="snippet">"#);
+        for (number, line) in (1..).zip(source.lines()).skip(first - 1).take(last + 1 - first) {
+            let class = if (entry.line_start..=entry.line_end).contains(&number) {
+                "span"
+            } else {
+                "line"
+            };
+            writeln!(
+                out,
+                r#"<span class="{class}"><span class="number">{number:>5} </span>{}</span>"#,
+                escape(line)
+            )
+            .unwrap();
+        }
+        out.push_str("</pre>\n");
+    }
+
+    fn source(&mut self, file: &str) -> Option<&(PathBuf, String)> {
+        self.sources
+            .entry(file.to_owned())
+            .or_insert_with(|| {
+                let path = find_source(file)?;
+                let contents = fs::read_to_string(&path).ok()?;
+                Some((path, contents))
+            })
+            .as_ref()
+    }
+
+    fn write_filters(&self, out: &mut String) {
+        out.push_str(r#"<div id="filters">"#);
+        for (key, label, values) in [
+            ("lint", "Lint", &self.lints),
+            ("group", "Group", &self.used_groups),
+            ("crate", "Crate", &self.crates),
+        ] {
+            write!(
+                out,
+                r#"<label>{label} <select data-filter="{key}"><option value="">All</option>"#
+            )
+            .unwrap();
+            for value in values {
+                let value = escape(value);
+                write!(out, r#"<option value="{value}">{value}</option>"#).unwrap();
+            }
+            out.push_str("</select></label>");
+        }
+        out.push_str("</div>\n");
+    }
+
+    fn page(&self, title: &str, body: &str) -> String {
+        let mut page = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        self.write_filters(&mut page);
+        page.push_str(body);
+        write!(page, "<script>{SCRIPT}</script>\n</body>\n</html>\n").unwrap();
+        page
+    }
+}
+
+/// Creates the log file output for [`crate::config::OutputFormat::Html`]
+pub(crate) fn output(warnings: &[ClippyWarning], ices: &[RustcIce], clippy_ver: &str) -> String {
+    let mut renderer = Renderer::new();
+    let mut entries: Vec<Entry<'_>> = warnings.iter().map(Entry::from_warning).collect();
+    entries.sort_by_key(|entry| (entry.name, entry.file, entry.line_start));
+
+    let mut body = format!("<p>{}</p>\n", escape(clippy_ver.trim()));
+
+    // The number of warnings of each lint, by lint group
+    let mut breakdown: BTreeMap<&str, BTreeMap<&str, usize>> = BTreeMap::new();
+    for entry in &entries {
+        *breakdown
+            .entry(renderer.group(entry.name))
+            .or_default()
+            .entry(entry.name)
+            .or_default() += 1;
+    }
+    body.push_str("<h2>Lint groups</h2>\n<table>\n");
+    for (group, lints) in &breakdown {
+        let group = escape(group);
+        writeln!(
+            body,
+            r#"<tr class="group" data-group="{group}"><th>{group}</th><th>{}</th></tr>"#,
+            lints.values().sum::<usize>()
+        )
+        .unwrap();
+        for (lint, count) in lints {
+            let lint = escape(lint);
+            writeln!(
+                body,
+                r#"<tr data-lint="{lint}" data-group="{group}"><td><code>{lint}</code></td><td class="count">{count}</td></tr>"#
+            )
+            .unwrap();
+        }
+    }
+    body.push_str("</table>\n");
+
+    body.push_str("<h2>Warnings</h2>\n");
+    for entry in &entries {
+        renderer.write_warning(&mut body, entry);
+    }
+
+    if !ices.is_empty() {
+        body.push_str("<h2>ICEs</h2>\n");
+        for ice in ices {
+            writeln!(body, "<pre>{}</pre>", escape(&ice.to_string())).unwrap();
+        }
+    }
+
+    renderer.page("Lintcheck", &body)
+}
+
+/// Prints the `diff` of two JSON log files as an HTML page
+pub(crate) fn diff(lints: &[LintWarnings], truncate_after: usize) -> String {
+    let mut renderer = Renderer::new();
+
+    let mut body =
+        String::from("<table>\n<tr><th>Lint</th><th>Group</th><th>Added</th><th>Removed</th><th>Changed</th></tr>\n");
+    for lint in lints {
+        let name = escape(&lint.name);
+        let group = escape(renderer.group(&lint.name));
+        writeln!(
+            body,
+            r##"<tr data-lint="{name}" data-group="{group}"><td><a href="#{}"><code>{name}</code></a></td><td>{group}</td><td class="count">{}</td><td class="count">{}</td><td class="count">{}</td></tr>"##,
+            to_html_id(&lint.name),
+            lint.added.len(),
+            lint.removed.len(),
+            lint.changed.len(),
+        )
+        .unwrap();
+    }
+    body.push_str("</table>\n");
+
+    for lint in lints {
+        writeln!(
+            body,
+            r#"<section id="{}" data-lint="{}" data-group="{}">"#,
+            to_html_id(&lint.name),
+            escape(&lint.name),
+            escape(renderer.group(&lint.name)),
+        )
+        .unwrap();
+        writeln!(body, "<h2><code>{}</code></h2>", escape(&lint.name)).unwrap();
+
+        if !lint.removed.is_empty() || !lint.added.is_empty() {
+            body.push_str(r#"<div class="columns">"#);
+            for (title, warnings) in [("Removed", &lint.removed), ("Added", &lint.added)] {
+                writeln!(body, "<div>\n<h3>{title} ({})</h3>", warnings.len()).unwrap();
+                for warning in truncate(&mut body, warnings, truncate_after / 3) {
+                    renderer.write_warning(&mut body, &Entry::from_json(warning));
+                }
+                body.push_str("</div>\n");
+            }
+            body.push_str("</div>\n");
+        }
+
+        if !lint.changed.is_empty() {
+            writeln!(body, "<h3>Changed ({})</h3>", lint.changed.len()).unwrap();
+            for (old, new) in truncate(&mut body, &lint.changed, truncate_after / 3) {
+                write_changed(&mut renderer, &mut body, old, new);
+            }
+        }
+
+        body.push_str("</section>\n");
+    }
+
+    renderer.page("Lintcheck diff", &body)
+}
+
+/// Writes the warning with both the old and new rendered diagnostics, with the differing lines
+/// highlighted
+fn write_changed(renderer: &mut Renderer, out: &mut String, old: &LintJson, new: &LintJson) {
+    renderer.open_warning(out, &Entry::from_json(new));
+
+    let mut old_lines = String::new();
+    let mut new_lines = String::new();
+    for change in diff::lines(&old.rendered, &new.rendered) {
+        match change {
+            diff::Result::Both(unchanged, _) => {
+                writeln!(old_lines, "{}", escape(unchanged)).unwrap();
+                writeln!(new_lines, "{}", escape(unchanged)).unwrap();
+            },
+            diff::Result::Left(removed) => {
+                writeln!(old_lines, r#"<span class="removed">{}</span>"#, escape(removed)).unwrap();
+            },
+            diff::Result::Right(added) => {
+                writeln!(new_lines, r#"<span class="added">{}</span>"#, escape(added)).unwrap();
+            },
+        }
+    }
+    writeln!(
+        out,
+        r#"<div class="columns"><pre>{old_lines}</pre><pre>{new_lines}</pre></div>"#
+    )
+    .unwrap();
+    out.push_str("</div>\n");
+}
+
+fn truncate<'a, T>(out: &mut String, list: &'a [T], truncate_after: usize) -> &'a [T] {
+    if list.len() > truncate_after {
+        writeln!(
+            out,
+            "<p>{} warnings have been truncated for this summary.</p>",
+            list.len() - truncate_after
+        )
+        .unwrap();
+        &list[..truncate_after]
+    } else {
+        list
+    }
+}
+
+/// Finds a file in the extracted sources, or in the cargo registry for the dependencies checked
+/// with `--recursive`
+fn find_source(file: &str) -> Option<PathBuf> {
+    let cargo_home = env!("CARGO_HOME");
+    [
+        Path::new(LINTCHECK_SOURCES).join(file),
+        PathBuf::from(file.replace("$CARGO_HOME", cargo_home)),
+        Path::new(cargo_home)
+            .join("registry/src/index.crates.io-6f17d22bba15001f")
+            .join(file),
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+    .and_then(|path| fs::canonicalize(path).ok())
+}
+
+/// Finds the group of each Clippy lint in its `declare_clippy_lint!` invocation, where the
+/// group follows the name, possibly after some comments:
+///
+/// ```ignore
+/// pub NEEDLESS_BOOL,
+/// complexity,
+/// ```
+fn lint_groups() -> HashMap<String, String> {
+    let mut groups = HashMap::new();
+    for entry in WalkDir::new(clippy_project_root().join("clippy_lints/src"))
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "rs"))
+    {
+        let Ok(contents) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let lines = contents.lines().map(str::trim).filter(|line| !line.starts_with("//"));
+        for (name, group) in lines.tuple_windows() {
+            if let Some(name) = name.strip_prefix("pub ").and_then(|name| name.strip_suffix(','))
+                && let Some(group) = group.strip_suffix(',')
+                && !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                && group.chars().all(|c| c.is_ascii_lowercase())
+            {
+                groups.insert(format!("clippy::{}", name.to_ascii_lowercase()), group.to_owned());
+            }
+        }
+    }
+    groups
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(name: &str, file_line: &str, rendered: &str) -> LintJson {
+        LintJson {
+            name: name.into(),
+            file_line: file_line.into(),
+            file_url: format!("https://docs.rs/crate/{file_line}"),
+            rendered: rendered.into(),
+        }
+    }
+
+    /// A renderer with the contents of `file`, so the snippets don't depend on the extracted
+    /// sources
+    fn renderer(file: &str, contents: &str) -> Renderer {
+        let mut renderer = Renderer::default();
+        renderer.groups.insert("clippy::needless_return".into(), "style".into());
+        renderer.sources.insert(
+            file.into(),
+            Some((PathBuf::from("/sources").join(file), contents.into())),
+        );
+        renderer
+    }
+
+    #[test]
+    fn entry_from_json() {
+        let lint = json("clippy::needless_return", "anyhow-1.0.86/src/error.rs:42", "warning");
+        let entry = Entry::from_json(&lint);
+        assert_eq!(entry.krate, "anyhow-1.0.86");
+        assert_eq!(entry.file, "anyhow-1.0.86/src/error.rs");
+        assert_eq!((entry.line_start, entry.line_end), (42, 42));
+
+        let lint = json("clippy::needless_return", "anyhow-1.0.86/src/error.rs", "warning");
+        let entry = Entry::from_json(&lint);
+        assert_eq!(entry.file, "anyhow-1.0.86/src/error.rs");
+        assert_eq!(entry.line_start, 0);
+    }
+
+    #[test]
+    fn escape_html() {
+        assert_eq!(
+            escape(r#"<a href="x">Vec<&'a str></a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Vec&lt;&amp;&#39;a str&gt;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn truncate_list() {
+        let mut out = String::new();
+        assert_eq!(truncate(&mut out, &[1, 2], 2), [1, 2]);
+        assert_eq!(out, "");
+        assert_eq!(truncate(&mut out, &[1, 2, 3, 4, 5], 2), [1, 2]);
+        assert_eq!(out, "<p>3 warnings have been truncated for this summary.</p>\n");
+    }
+
+    #[test]
+    fn warning_snippet() {
+        let source = "line 1\nline 2\nline 3\nline 4\nline 5\nline 6\nline 7\nline 8\nline 9\nline 10\n";
+        let mut renderer = renderer("krate-0.1.0/src/lib.rs", source);
+        let lint = json("clippy::needless_return", "krate-0.1.0/src/lib.rs:2", "warning: <msg>");
+
+        let mut out = String::new();
+        renderer.write_warning(&mut out, &Entry::from_json(&lint));
+        assert_eq!(
+            out,
+            r#"<div class="warning" data-lint="clippy::needless_return" data-group="style" data-crate="krate-0.1.0">
+<div><code>clippy::needless_return</code> at <a href="file:///sources/krate-0.1.0/src/lib.rs#L2">krate-0.1.0/src/lib.rs:2</a> (<a href="https://docs.rs/crate/krate-0.1.0/src/lib.rs:2">online</a>)</div>
+<pre class="snippet"><span class="line"><span class="number">    1 </span>line 1</span>
+<span class="span"><span class="number">    2 </span>line 2</span>
+<span class="line"><span class="number">    3 </span>line 3</span>
+<span class="line"><span class="number">    4 </span>line 4</span>
+<span class="line"><span class="number">    5 </span>line 5</span>
+</pre>
+<pre>warning: &lt;msg&gt;</pre>
+</div>
+"#
+        );
+        assert_eq!(renderer.lints, BTreeSet::from(["clippy::needless_return".into()]));
+        assert_eq!(renderer.used_groups, BTreeSet::from(["style".into()]));
+        assert_eq!(renderer.crates, BTreeSet::from(["krate-0.1.0".into()]));
+
+        // The warnings of rustc and the ones of files that weren't found have no snippet
+        let lint = json("unused_variables", "other-0.1.0/src/lib.rs:2", "warning");
+        let mut out = String::new();
+        renderer.write_warning(&mut out, &Entry::from_json(&lint));
+        assert!(out.starts_with(r#"<div class="warning" data-lint="unused_variables" data-group="rustc""#));
+        assert!(out.contains("at other-0.1.0/src/lib.rs:2 ("));
+        assert!(!out.contains("snippet"));
+    }
+
+    #[test]
+    fn changed_warning() {
+        let mut renderer = renderer("krate-0.1.0/src/lib.rs", "");
+        let old = json("clippy::needless_return", "krate-0.1.0/src/lib.rs:1", "same\nold");
+        let new = json("clippy::needless_return", "krate-0.1.0/src/lib.rs:1", "same\nnew");
+
+        let mut out = String::new();
+        write_changed(&mut renderer, &mut out, &old, &new);
+        assert!(out.ends_with(
+            "<div class=\"columns\"><pre>same\n<span class=\"removed\">old</span>\n</pre>\
+             <pre>same\n<span class=\"added\">new</span>\n</pre></div>\n</div>\n"
+        ));
+    }
+
+    #[test]
+    fn diff_report() {
+        let lints = [LintWarnings {
+            name: "clippy::needless_return".into(),
+            added: vec![json("clippy::needless_return", "krate-0.1.0/src/lib.rs:1", "added")],
+            removed: Vec::new(),
+            changed: vec![(
+                json("clippy::needless_return", "krate-0.1.0/src/lib.rs:2", "old"),
+                json("clippy::needless_return", "krate-0.1.0/src/lib.rs:2", "new"),
+            )],
+        }];
+        let page = diff(&lints, 3);
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains(
+            r##"<tr data-lint="clippy::needless_return" data-group="style"><td><a href="#needless-return"><code>clippy::needless_return</code></a></td><td>style</td><td class="count">1</td><td class="count">0</td><td class="count">1</td></tr>"##
+        ));
+        assert!(page.contains(r#"<option value="krate-0.1.0">krate-0.1.0</option>"#));
+        assert!(page.contains("<h3>Removed (0)</h3>"));
+        assert!(page.contains("<h3>Added (1)</h3>\n<div class=\"warning\""));
+        assert!(page.contains("<h3>Changed (1)</h3>"));
+
+        // Each kind of warnings is truncated to a third of the total
+        let page = diff(&lints, 2);
+        assert!(page.contains("<h3>Added (1)</h3>\n<p>1 warnings have been truncated for this summary.</p>"));
+        assert!(!page.contains(r#"class="warning""#));
+    }
+
+    #[test]
+    fn groups_of_the_lints() {
+        let groups = lint_groups();
+        assert_eq!(groups["clippy::needless_return"], "style");
+        assert_eq!(groups["clippy::implicit_return"], "restriction");
+    }
+}
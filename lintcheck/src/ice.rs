@@ -0,0 +1,510 @@
+//! Reduces the crates which caused an ICE to a single file reproducer. The modules of the crate are
+//! inlined into its root file, then items are removed and function bodies are replaced with
+//! `loop {}` for as long as clippy still panics at the same location.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+use syn::visit_mut::{self, VisitMut};
+use syn::{Block, File, Item, ItemFn, ItemImpl, ItemMod, ItemTrait, Lit, Meta};
+
+use crate::input::OfflineSource;
+use crate::output::RustcIce;
+use crate::{Crate, LINTCHECK_ICE, shared_target_dir};
+
+/// The arguments and environment of a `clippy-driver` invocation which caused an ICE, recorded by
+/// [`crate::driver::record_ice`]
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Invocation {
+    /// The arguments passed to rustc
+    pub args: Vec<String>,
+    /// The `CARGO_*` environment variables, `OUT_DIR` and `CLIPPY_ARGS`
+    pub env: BTreeMap<String, String>,
+    pub cwd: PathBuf,
+}
+
+impl Crate {
+    /// Reduces the ICE to a single file, and writes it to `dir` along with a script running
+    /// `clippy-driver` on it. Returns the path of the script.
+    pub fn reduce_ice(
+        &self,
+        ice: &RustcIce,
+        clippy_driver_path: &Path,
+        lint_levels_args: &[String],
+        dir: &Path,
+    ) -> Result<PathBuf, String> {
+        println!("Reducing the ICE of {} {}", self.name, self.version);
+
+        let work_dir = Path::new(LINTCHECK_ICE).join(format!("{}-{}", self.name, self.version));
+        fs::create_dir_all(&work_dir).unwrap();
+        let work_dir = fs::canonicalize(work_dir).unwrap();
+        let invocation = self.record_invocation(clippy_driver_path, lint_levels_args, &work_dir)?;
+
+        let input_index = invocation
+            .args
+            .iter()
+            .position(|arg| {
+                let path = invocation.cwd.join(arg);
+                path.extension().is_some_and(|ext| ext == "rs") && path.is_file()
+            })
+            .ok_or("could not find the root file of the crate")?;
+        let root = invocation.cwd.join(&invocation.args[input_index]);
+        let key = panic_location(&ice.ice_content).ok_or("could not find where clippy panicked")?;
+
+        let mut file = parse(&root)?;
+        inline_modules(&mut file.items, root.parent().unwrap())?;
+
+        // Modules which were not inlined, e.g. because they are disabled by a `cfg`, and
+        // `include!`s are relative to the root file
+        let reproducer = root.with_file_name("lintcheck_ice_reproducer.rs");
+        let is_reproduced = |file: &File| {
+            fs::write(&reproducer, prettyplease::unparse(file)).unwrap();
+            let output = Command::new(clippy_driver_path)
+                .args(reproducer_args(&invocation.args, input_index, &reproducer, &work_dir))
+                .envs(&invocation.env)
+                .current_dir(&invocation.cwd)
+                .stdout(Stdio::null())
+                .output()
+                .unwrap();
+            output.status.code() == Some(101) && String::from_utf8_lossy(&output.stderr).contains(&key)
+        };
+
+        if !is_reproduced(&file) {
+            let _ = fs::remove_file(&reproducer);
+            return Err(String::from(
+                "the ICE does not happen with the modules inlined into a single file",
+            ));
+        }
+
+        // Removing items may allow stubbing more bodies and vice versa
+        loop {
+            let before = file.clone();
+            for transform in [remove_items, stub_bodies] {
+                let (_, count) = transform(&file, &BTreeSet::new());
+                let applied = bisect(count, |changes| is_reproduced(&transform(&file, changes).0));
+                file = transform(&file, &applied).0;
+            }
+            if file == before {
+                break;
+            }
+        }
+        fs::remove_file(&reproducer).unwrap();
+
+        fs::create_dir_all(dir).unwrap();
+        let dir = fs::canonicalize(dir).unwrap();
+        let reproducer = dir.join(format!("{}-{}.rs", self.name, self.version));
+        fs::write(&reproducer, prettyplease::unparse(&file)).unwrap();
+
+        let mut script = format!("#!/bin/sh\n# ICE in {} {}: {key}\n", self.name, self.version);
+        writeln!(script, "cd {}", quote(&invocation.cwd.display().to_string())).unwrap();
+        for (name, value) in &invocation.env {
+            writeln!(script, "export {name}={}", quote(value)).unwrap();
+        }
+        script.push_str(&quote(&clippy_driver_path.display().to_string()));
+        for arg in reproducer_args(&invocation.args, input_index, &reproducer, &work_dir) {
+            write!(script, " {}", quote(&arg)).unwrap();
+        }
+        script.push('\n');
+
+        let script_path = reproducer.with_extension("sh");
+        fs::write(&script_path, script).unwrap();
+        Ok(script_path)
+    }
+
+    /// Runs clippy on the crate again, with lintcheck as the `RUSTC_WORKSPACE_WRAPPER` to record
+    /// the invocation which ICEs
+    fn record_invocation(
+        &self,
+        clippy_driver_path: &Path,
+        lint_levels_args: &[String],
+        work_dir: &Path,
+    ) -> Result<Invocation, String> {
+        let invocation_path = work_dir.join("invocation.json");
+        let _ = fs::remove_file(&invocation_path);
+
+        let mut clippy_args: Vec<&str> = self.options.iter().flatten().map(String::as_str).collect();
+        clippy_args.extend(lint_levels_args.iter().map(String::as_str));
+        Command::new("cargo")
+            .args(["check", "--quiet"])
+            .args(self.offline.iter().flat_map(OfflineSource::cargo_args))
+            .current_dir(&self.path)
+            .env("CARGO_TARGET_DIR", shared_target_dir("ice"))
+            .env("RUSTC_WORKSPACE_WRAPPER", std::env::current_exe().unwrap())
+            .env("CLIPPY_DRIVER", clippy_driver_path)
+            .env("LINTCHECK_ICE_INVOCATION", &invocation_path)
+            .env("CLIPPY_ARGS", clippy_args.join("__CLIPPY_HACKERY__"))
+            .env("CLIPPY_DISABLE_DOCS_LINKS", "1")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .expect("failed to run cargo");
+
+        let invocation = fs::read(&invocation_path).map_err(|_| "the ICE did not happen again")?;
+        Ok(serde_json::from_slice(&invocation).unwrap())
+    }
+}
+
+/// Where clippy panicked, e.g. `clippy_lints/src/foo.rs:12:5`, or the message of a delayed bug
+fn panic_location(ice_content: &str) -> Option<String> {
+    ice_content.lines().find_map(|line| {
+        if let Some((_, location)) = line.split_once("panicked at ") {
+            Some(format!("panicked at {}", location.trim_end_matches(':')))
+        } else if line.starts_with("error: internal compiler error: ") {
+            Some(line.to_owned())
+        } else {
+            None
+        }
+    })
+}
+
+/// The arguments of the invocation with the reproducer as the input file, without the JSON
+/// output and incremental compilation
+fn reproducer_args(args: &[String], input_index: usize, reproducer: &Path, out_dir: &Path) -> Vec<String> {
+    let mut new_args = Vec::with_capacity(args.len());
+    let mut args = args.iter().enumerate();
+    while let Some((i, arg)) = args.next() {
+        if i == input_index {
+            new_args.push(reproducer.display().to_string());
+        } else if arg == "--out-dir" {
+            args.next();
+            new_args.extend(["--out-dir".into(), out_dir.display().to_string()]);
+        } else if arg == "-C"
+            && args
+                .clone()
+                .next()
+                .is_some_and(|(_, arg)| arg.starts_with("incremental="))
+        {
+            args.next();
+        } else if !["--error-format=", "--json=", "--color=", "--diagnostic-width="]
+            .iter()
+            .any(|flag| arg.starts_with(flag))
+        {
+            new_args.push(arg.clone());
+        }
+    }
+    new_args
+}
+
+fn quote(arg: &str) -> String {
+    if arg
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '=' | '/' | '.' | ',' | ':'))
+    {
+        arg.to_owned()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+fn parse(path: &Path) -> Result<File, String> {
+    let src = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    syn::parse_file(&src).map_err(|e| format!("failed to parse {}: {e}", path.display()))
+}
+
+/// Replaces `mod foo;` with the contents of its file, `dir` is where the files of the submodules
+/// are
+fn inline_modules(items: &mut [Item], dir: &Path) -> Result<(), String> {
+    for item in items {
+        let Item::Mod(module) = item else {
+            continue;
+        };
+        let name = module.ident.to_string();
+
+        if let Some((_, items)) = &mut module.content {
+            inline_modules(items, &dir.join(&name))?;
+            continue;
+        }
+
+        let path_attr = module.attrs.iter().position(|attr| attr.path().is_ident("path"));
+        let path = match path_attr.map(|i| &module.attrs[i].meta) {
+            Some(Meta::NameValue(meta)) => match &meta.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(path), ..
+                }) => Some(dir.join(path.value())),
+                _ => None,
+            },
+            _ => [dir.join(format!("{name}.rs")), dir.join(&name).join("mod.rs")]
+                .into_iter()
+                .find(|path| path.is_file()),
+        };
+        // e.g. the module is disabled by a `cfg`
+        let Some(path) = path.filter(|path| path.is_file()) else {
+            continue;
+        };
+
+        let mut file = parse(&path)?;
+        let nested_dir = if path.ends_with("mod.rs") || path_attr.is_some() {
+            path.parent().unwrap().to_owned()
+        } else {
+            path.with_extension("")
+        };
+        inline_modules(&mut file.items, &nested_dir)?;
+
+        if let Some(i) = path_attr {
+            module.attrs.remove(i);
+        }
+        module.attrs.extend(file.attrs);
+        module.content = Some((syn::token::Brace::default(), file.items));
+        module.semi = None;
+    }
+    Ok(())
+}
+
+/// Finds a large set of changes which can be applied while the ICE still happens, by trying to
+/// apply all of them, then halves, quarters, ... of the remaining ones
+fn bisect(count: usize, mut reproduces: impl FnMut(&BTreeSet<usize>) -> bool) -> BTreeSet<usize> {
+    let mut applied = BTreeSet::new();
+    let mut remaining: Vec<usize> = (0..count).collect();
+    let mut chunk_size = count;
+    while chunk_size > 0 && !remaining.is_empty() {
+        let mut rejected = Vec::new();
+        for chunk in remaining.chunks(chunk_size) {
+            let mut changes = applied.clone();
+            changes.extend(chunk);
+            if reproduces(&changes) {
+                applied = changes;
+            } else {
+                rejected.extend(chunk);
+            }
+        }
+        remaining = rejected;
+        chunk_size /= 2;
+    }
+    applied
+}
+
+/// Removes the items with the given indices, returns the new file and the number of items which
+/// could be removed
+fn remove_items(file: &File, remove: &BTreeSet<usize>) -> (File, usize) {
+    struct RemoveItems<'a> {
+        remove: &'a BTreeSet<usize>,
+        next: usize,
+    }
+
+    impl RemoveItems<'_> {
+        // The children are visited before removing anything so that the indices don't depend on
+        // what is removed
+        fn retain<T>(&mut self, items: &mut Vec<T>, mut visit: impl FnMut(&mut Self, &mut T)) {
+            let mut keep = Vec::with_capacity(items.len());
+            for item in &mut *items {
+                keep.push(!self.remove.contains(&self.next));
+                self.next += 1;
+                visit(self, item);
+            }
+            let mut keep = keep.into_iter();
+            items.retain(|_| keep.next().unwrap());
+        }
+    }
+
+    impl VisitMut for RemoveItems<'_> {
+        fn visit_file_mut(&mut self, file: &mut File) {
+            self.retain(&mut file.items, Self::visit_item_mut);
+        }
+
+        fn visit_item_mod_mut(&mut self, module: &mut ItemMod) {
+            if let Some((_, items)) = &mut module.content {
+                self.retain(items, Self::visit_item_mut);
+            }
+        }
+
+        fn visit_item_impl_mut(&mut self, item: &mut ItemImpl) {
+            self.retain(&mut item.items, Self::visit_impl_item_mut);
+        }
+
+        fn visit_item_trait_mut(&mut self, item: &mut ItemTrait) {
+            self.retain(&mut item.items, Self::visit_trait_item_mut);
+        }
+    }
+
+    let mut file = file.clone();
+    let mut visitor = RemoveItems { remove, next: 0 };
+    visitor.visit_file_mut(&mut file);
+    (file, visitor.next)
+}
+
+/// Replaces the bodies of the functions with the given indices with `loop {}`, returns the new file
+/// and the number of bodies which could be replaced
+fn stub_bodies(file: &File, stub: &BTreeSet<usize>) -> (File, usize) {
+    struct StubBodies<'a> {
+        stub: &'a BTreeSet<usize>,
+        next: usize,
+        loop_block: Block,
+    }
+
+    impl StubBodies<'_> {
+        // The body is visited before stubbing it so that the indices don't depend on what is stubbed
+        fn stub(&mut self, block: &mut Block, visit: impl FnOnce(&mut Self, &mut Block)) {
+            if *block == self.loop_block {
+                return;
+            }
+            let index = self.next;
+            self.next += 1;
+            visit(self, block);
+            if self.stub.contains(&index) {
+                *block = self.loop_block.clone();
+            }
+        }
+    }
+
+    impl VisitMut for StubBodies<'_> {
+        fn visit_item_fn_mut(&mut self, item: &mut ItemFn) {
+            self.stub(&mut item.block, Self::visit_block_mut);
+        }
+
+        fn visit_impl_item_fn_mut(&mut self, item: &mut syn::ImplItemFn) {
+            self.stub(&mut item.block, Self::visit_block_mut);
+        }
+
+        fn visit_trait_item_fn_mut(&mut self, item: &mut syn::TraitItemFn) {
+            if let Some(block) = &mut item.default {
+                self.stub(block, Self::visit_block_mut);
+            }
+        }
+    }
+
+    let mut file = file.clone();
+    let mut visitor = StubBodies {
+        stub,
+        next: 0,
+        loop_block: syn::parse_str("{ loop {} }").unwrap(),
+    };
+    visit_mut::visit_file_mut(&mut visitor, &mut file);
+    (file, visitor.next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unparse(file: &File) -> String {
+        prettyplease::unparse(file)
+    }
+
+    #[test]
+    fn parse_panic_location() {
+        let ice = "thread 'rustc' panicked at clippy_lints/src/foo.rs:12:5:\nexplicit panic\nstack backtrace:";
+        assert_eq!(
+            panic_location(ice).as_deref(),
+            Some("panicked at clippy_lints/src/foo.rs:12:5")
+        );
+        let ice = "note: no errors encountered even though delayed bugs were created\n\
+                   error: internal compiler error: broken MIR\n";
+        assert_eq!(
+            panic_location(ice).as_deref(),
+            Some("error: internal compiler error: broken MIR")
+        );
+        assert_eq!(panic_location("error: could not compile `foo`"), None);
+    }
+
+    #[test]
+    fn args_of_reproducer() {
+        let args: Vec<String> = [
+            "--crate-name",
+            "foo",
+            "src/lib.rs",
+            "--error-format=json",
+            "--json=diagnostic-rendered-ansi",
+            "-C",
+            "incremental=/tmp/incr",
+            "-C",
+            "debuginfo=2",
+            "--out-dir",
+            "/tmp/out",
+        ]
+        .map(String::from)
+        .into();
+        assert_eq!(
+            reproducer_args(&args, 2, Path::new("/ice/foo.rs"), Path::new("/ice/out")),
+            [
+                "--crate-name",
+                "foo",
+                "/ice/foo.rs",
+                "-C",
+                "debuginfo=2",
+                "--out-dir",
+                "/ice/out"
+            ]
+        );
+    }
+
+    #[test]
+    fn quote_args() {
+        assert_eq!(quote("--cfg=feature"), "--cfg=feature");
+        assert_eq!(quote("feature=\"std\""), "'feature=\"std\"'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn inline_module_files() {
+        let dir = PathBuf::from("target/lintcheck/ice_test");
+        fs::create_dir_all(dir.join("a")).unwrap();
+        fs::write(
+            dir.join("lib.rs"),
+            "mod a;\nmod b { mod c; }\n#[cfg(any())]\nmod missing;\n",
+        )
+        .unwrap();
+        fs::write(dir.join("a.rs"), "#![allow(dead_code)]\nmod nested;\nfn a() {}\n").unwrap();
+        fs::write(dir.join("a/nested.rs"), "fn nested() {}\n").unwrap();
+        fs::create_dir_all(dir.join("b")).unwrap();
+        fs::write(dir.join("b/c.rs"), "fn c() {}\n").unwrap();
+
+        let mut file = parse(&dir.join("lib.rs")).unwrap();
+        inline_modules(&mut file.items, &dir).unwrap();
+        assert_eq!(
+            unparse(&file),
+            "\
+mod a {
+    #![allow(dead_code)]
+    mod nested {
+        fn nested() {}
+    }
+    fn a() {}
+}
+mod b {
+    mod c {
+        fn c() {}
+    }
+}
+#[cfg(any())]
+mod missing;
+"
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn bisect_changes() {
+        // The ICE happens as long as the changes 3 and 5 are not applied
+        let applied = bisect(8, |changes| !changes.contains(&3) && !changes.contains(&5));
+        assert_eq!(applied, BTreeSet::from([0, 1, 2, 4, 6, 7]));
+        assert_eq!(bisect(4, |_| false), BTreeSet::new());
+    }
+
+    #[test]
+    fn remove_and_stub() {
+        let file = syn::parse_file("fn a() { 1; }\nmod m { fn b() {} }\nimpl S { fn c() { 2; } }\n").unwrap();
+
+        let (_, count) = remove_items(&file, &BTreeSet::new());
+        assert_eq!(count, 5);
+        // The indices are in visiting order: `a`, `m`, `b`, the `impl` and `c`
+        let (removed, _) = remove_items(&file, &BTreeSet::from([0, 2]));
+        assert_eq!(
+            unparse(&removed),
+            "mod m {}\nimpl S {\n    fn c() {\n        2;\n    }\n}\n"
+        );
+
+        let (_, count) = stub_bodies(&file, &BTreeSet::new());
+        assert_eq!(count, 3);
+        let (stubbed, _) = stub_bodies(&file, &BTreeSet::from([0, 2]));
+        assert_eq!(
+            unparse(&stubbed),
+            "fn a() {\n    loop {}\n}\nmod m {\n    fn b() {}\n}\nimpl S {\n    fn c() {\n        loop {}\n    }\n}\n"
+        );
+        // Stubbed bodies are not counted again
+        assert_eq!(stub_bodies(&stubbed, &BTreeSet::new()).1, 1);
+    }
+}
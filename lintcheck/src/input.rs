@@ -20,6 +20,7 @@ pub struct SourceList {
     crates: HashMap<String, TomlCrate>,
     #[serde(default)]
     recursive: RecursiveOptions,
+    offline: Option<OfflineOptions>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -27,6 +28,42 @@ pub struct RecursiveOptions {
     pub ignore: HashSet<String>,
 }
 
+/// Where to take the crates.io crates and their dependencies from, instead of downloading them
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct OfflineOptions {
+    /// A directory created by `cargo vendor` or `cargo lintcheck vendor`
+    vendor: Option<PathBuf>,
+    /// A local registry, as created by e.g. `cargo local-registry`
+    local_registry: Option<PathBuf>,
+}
+
+/// A directory which replaces crates.io, so that no network access is needed
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Ord, PartialOrd)]
+pub enum OfflineSource {
+    Vendor(PathBuf),
+    LocalRegistry(PathBuf),
+}
+
+impl OfflineSource {
+    /// The arguments which make cargo use this source instead of crates.io
+    pub fn cargo_args(&self) -> Vec<String> {
+        let (kind, dir) = match self {
+            OfflineSource::Vendor(dir) => ("directory", dir),
+            OfflineSource::LocalRegistry(dir) => ("local-registry", dir),
+        };
+        // cargo runs in the directory of the crate
+        let dir = fs::canonicalize(dir).unwrap_or_else(|e| panic!("failed to find {}: {e}", dir.display()));
+        vec![
+            "--offline".into(),
+            "--config".into(),
+            "source.crates-io.replace-with='lintcheck-offline'".into(),
+            "--config".into(),
+            format!("source.lintcheck-offline.{kind}='{}'", dir.display()),
+        ]
+    }
+}
+
 /// A crate source stored inside the .toml
 /// will be translated into on one of the `CrateSource` variants
 #[derive(Debug, Deserialize)]
@@ -74,7 +111,8 @@ impl TomlCrate {
     }
 }
 
-/// Represents an archive we download from crates.io, or a git repo, or a local repo/folder
+/// Represents an archive we download from crates.io or take from a vendor directory or local
+/// registry, or a git repo, or a local repo/folder
 /// Once processed (downloaded/extracted/cloned/copied...), this will be translated into a `Crate`
 #[derive(Debug, Deserialize, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct CrateWithSource {
@@ -82,13 +120,31 @@ pub struct CrateWithSource {
     pub source: CrateSource,
     pub file_link: String,
     pub options: Option<Vec<String>>,
+    pub offline: Option<OfflineSource>,
 }
 
 #[derive(Debug, Deserialize, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub enum CrateSource {
-    CratesIo { version: String },
-    Git { url: String, commit: String },
-    Path { path: PathBuf },
+    CratesIo {
+        version: String,
+    },
+    Git {
+        url: String,
+        commit: String,
+    },
+    Path {
+        path: PathBuf,
+    },
+    /// A crate in a `cargo vendor` directory
+    Vendor {
+        version: String,
+        dir: PathBuf,
+    },
+    /// A `.crate` archive in a local registry
+    LocalRegistry {
+        version: String,
+        dir: PathBuf,
+    },
 }
 
 /// Read a `lintcheck_crates.toml` file
@@ -99,6 +155,17 @@ pub fn read_crates(toml_path: &Path) -> (Vec<CrateWithSource>, RecursiveOptions)
         toml::from_str(&toml_content).unwrap_or_else(|e| panic!("Failed to parse {}: \n{e}", toml_path.display()));
     // parse the hashmap of the toml file into a list of crates
     let toml_crates: Vec<TomlCrate> = crate_list.crates.into_values().collect();
+    let offline = crate_list.offline.map(|offline| match offline {
+        OfflineOptions {
+            vendor: Some(dir),
+            local_registry: None,
+        } => OfflineSource::Vendor(dir),
+        OfflineOptions {
+            vendor: None,
+            local_registry: Some(dir),
+        } => OfflineSource::LocalRegistry(dir),
+        _ => panic!("Error: `[offline]` needs exactly one of `vendor` or `local-registry`"),
+    });
 
     // flatten TomlCrates into CrateSources (one TomlCrates may represent several versions of a crate =>
     // multiple CrateSources)
@@ -112,15 +179,26 @@ pub fn read_crates(toml_path: &Path) -> (Vec<CrateWithSource>, RecursiveOptions)
                 },
                 file_link: tk.file_link(DEFAULT_PATH_LINK),
                 options: tk.options.clone(),
+                offline: offline.clone(),
             });
         } else if let Some(ref version) = tk.version {
+            let version = version.to_string();
             crate_sources.push(CrateWithSource {
                 name: tk.name.clone(),
-                source: CrateSource::CratesIo {
-                    version: version.to_string(),
+                source: match &offline {
+                    Some(OfflineSource::Vendor(dir)) => CrateSource::Vendor {
+                        version,
+                        dir: dir.clone(),
+                    },
+                    Some(OfflineSource::LocalRegistry(dir)) => CrateSource::LocalRegistry {
+                        version,
+                        dir: dir.clone(),
+                    },
+                    None => CrateSource::CratesIo { version },
                 },
                 file_link: tk.file_link(DEFAULT_DOCS_LINK),
                 options: tk.options.clone(),
+                offline: offline.clone(),
             });
         } else if tk.git_url.is_some() && tk.git_hash.is_some() {
             // otherwise, we should have a git source
@@ -132,6 +210,7 @@ pub fn read_crates(toml_path: &Path) -> (Vec<CrateWithSource>, RecursiveOptions)
                 },
                 file_link: tk.file_link(DEFAULT_GITHUB_LINK),
                 options: tk.options.clone(),
+                offline: offline.clone(),
             });
         } else {
             panic!("Invalid crate source: {tk:?}");
@@ -199,6 +278,7 @@ impl CrateWithSource {
         let name = &self.name;
         let options = &self.options;
         let file_link = &self.file_link;
+        let offline = &self.offline;
         match &self.source {
             CrateSource::CratesIo { version } => {
                 let extract_dir = PathBuf::from(LINTCHECK_SOURCES);
@@ -218,11 +298,7 @@ impl CrateWithSource {
                     // copy the crate into the file
                     io::copy(&mut krate_req, &mut krate_dest).unwrap();
 
-                    // unzip the tarball
-                    let ungz_tar = flate2::read::GzDecoder::new(fs::File::open(&krate_file_path).unwrap());
-                    // extract the tar archive
-                    let mut archive = tar::Archive::new(ungz_tar);
-                    archive.unpack(&extract_dir).expect("Failed to extract!");
+                    extract_crate(&krate_file_path, &extract_dir);
                 }
                 // crate is extracted, return a new Krate object which contains the path to the extracted
                 // sources that clippy can check
@@ -232,6 +308,7 @@ impl CrateWithSource {
                     path: extract_dir.join(format!("{name}-{version}/")),
                     options: options.clone(),
                     base_url: file_link.clone(),
+                    offline: offline.clone(),
                 }
             },
             CrateSource::Git { url, commit } => {
@@ -243,6 +320,10 @@ impl CrateWithSource {
                 };
                 // clone the repo if we have not done so
                 if !repo_path.is_dir() {
+                    assert!(
+                        offline.is_none(),
+                        "Error: cannot clone {url} offline, use `cargo lintcheck vendor` to snapshot it"
+                    );
                     println!("Cloning {url} and checking out {commit}");
                     if !Command::new("git")
                         .arg("clone")
@@ -274,18 +355,10 @@ impl CrateWithSource {
                     path: repo_path,
                     options: options.clone(),
                     base_url: file_link.clone(),
+                    offline: offline.clone(),
                 }
             },
             CrateSource::Path { path } => {
-                fn is_cache_dir(entry: &DirEntry) -> bool {
-                    fs::read(entry.path().join("CACHEDIR.TAG"))
-                        .map(|x| x.starts_with(b"Signature: 8a477f597d28d172789f06886806bc55"))
-                        .unwrap_or(false)
-                }
-
-                // copy path into the dest_crate_root but skip directories that contain a CACHEDIR.TAG file.
-                // The target/ directory contains a CACHEDIR.TAG file so it is the most commonly skipped directory
-                // as a result of this filter.
                 let dest_crate_root = PathBuf::from(LINTCHECK_SOURCES).join(name);
                 if dest_crate_root.exists() {
                     println!("Deleting existing directory at {dest_crate_root:?}");
@@ -293,29 +366,101 @@ impl CrateWithSource {
                 }
 
                 println!("Copying {path:?} to {dest_crate_root:?}");
+                copy_dir(path, &dest_crate_root);
 
-                for entry in WalkDir::new(path).into_iter().filter_entry(|e| !is_cache_dir(e)) {
-                    let entry = entry.unwrap();
-                    let entry_path = entry.path();
-                    let relative_entry_path = entry_path.strip_prefix(path).unwrap();
-                    let dest_path = dest_crate_root.join(relative_entry_path);
-                    let metadata = entry_path.symlink_metadata().unwrap();
-
-                    if metadata.is_dir() {
-                        fs::create_dir(dest_path).unwrap();
-                    } else if metadata.is_file() {
-                        fs::copy(entry_path, dest_path).unwrap();
-                    }
+                Crate {
+                    version: String::from("local"),
+                    name: name.clone(),
+                    path: dest_crate_root,
+                    options: options.clone(),
+                    base_url: file_link.clone(),
+                    offline: offline.clone(),
+                }
+            },
+            CrateSource::Vendor { version, dir } => {
+                // `cargo vendor` only adds the version to the directory name with `--versioned-dirs`, or
+                // for all but the latest version of a crate
+                let vendored = [dir.join(format!("{name}-{version}")), dir.join(name)]
+                    .into_iter()
+                    .find(|vendored| vendored_version(vendored).as_ref() == Some(version))
+                    .unwrap_or_else(|| panic!("Error: could not find {name} {version} in {}", dir.display()));
+
+                let dest_crate_root = PathBuf::from(LINTCHECK_SOURCES).join(format!("{name}-{version}"));
+                if !dest_crate_root.is_dir() {
+                    println!("Copying {name} {version} from {vendored:?}");
+                    copy_dir(&vendored, &dest_crate_root);
                 }
 
                 Crate {
-                    version: String::from("local"),
+                    version: version.clone(),
                     name: name.clone(),
                     path: dest_crate_root,
                     options: options.clone(),
                     base_url: file_link.clone(),
+                    offline: offline.clone(),
                 }
             },
+            CrateSource::LocalRegistry { version, dir } => {
+                let extract_dir = PathBuf::from(LINTCHECK_SOURCES);
+                let dest_crate_root = extract_dir.join(format!("{name}-{version}/"));
+                if !dest_crate_root.is_dir() {
+                    let krate_file_path = dir.join(format!("{name}-{version}.crate"));
+                    println!("Extracting {name} {version} from {krate_file_path:?}");
+                    fs::create_dir_all(&extract_dir).unwrap();
+                    extract_crate(&krate_file_path, &extract_dir);
+                }
+
+                Crate {
+                    version: version.clone(),
+                    name: name.clone(),
+                    path: dest_crate_root,
+                    options: options.clone(),
+                    base_url: file_link.clone(),
+                    offline: offline.clone(),
+                }
+            },
+        }
+    }
+}
+
+/// Extracts a `.crate` archive, which contains the `<name>-<version>` directory of the crate
+fn extract_crate(krate_file_path: &Path, extract_dir: &Path) {
+    // unzip the tarball
+    let ungz_tar = flate2::read::GzDecoder::new(
+        fs::File::open(krate_file_path).unwrap_or_else(|e| panic!("Failed to open {}: {e}", krate_file_path.display())),
+    );
+    // extract the tar archive
+    let mut archive = tar::Archive::new(ungz_tar);
+    archive.unpack(extract_dir).expect("Failed to extract!");
+}
+
+/// Reads the version of the crate in a `cargo vendor` directory
+fn vendored_version(dir: &Path) -> Option<String> {
+    let manifest: toml::Value = toml::from_str(&fs::read_to_string(dir.join("Cargo.toml")).ok()?).ok()?;
+    Some(manifest.get("package")?.get("version")?.as_str()?.to_owned())
+}
+
+/// Copies the directory `src` to `dest`, but skips directories that contain a CACHEDIR.TAG file.
+/// The target/ directory contains a CACHEDIR.TAG file so it is the most commonly skipped directory
+/// as a result of this filter.
+pub fn copy_dir(src: &Path, dest: &Path) {
+    fn is_cache_dir(entry: &DirEntry) -> bool {
+        fs::read(entry.path().join("CACHEDIR.TAG"))
+            .map(|x| x.starts_with(b"Signature: 8a477f597d28d172789f06886806bc55"))
+            .unwrap_or(false)
+    }
+
+    for entry in WalkDir::new(src).into_iter().filter_entry(|e| !is_cache_dir(e)) {
+        let entry = entry.unwrap();
+        let entry_path = entry.path();
+        let relative_entry_path = entry_path.strip_prefix(src).unwrap();
+        let dest_path = dest.join(relative_entry_path);
+        let metadata = entry_path.symlink_metadata().unwrap();
+
+        if metadata.is_dir() {
+            fs::create_dir_all(dest_path).unwrap();
+        } else if metadata.is_file() {
+            fs::copy(entry_path, dest_path).unwrap();
         }
     }
 }
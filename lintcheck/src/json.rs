@@ -11,13 +11,13 @@ const DEFAULT_LIMIT_PER_LINT: usize = 300;
 const TRUNCATION_TOTAL_TARGET: usize = 1000;
 
 #[derive(Debug, Deserialize, Serialize)]
-struct LintJson {
+pub(crate) struct LintJson {
     /// The lint name e.g. `clippy::bytes_nth`
-    name: String,
+    pub name: String,
     /// The filename and line number e.g. `anyhow-1.0.86/src/error.rs:42`
-    file_line: String,
-    file_url: String,
-    rendered: String,
+    pub file_line: String,
+    pub file_url: String,
+    pub rendered: String,
 }
 
 impl LintJson {
@@ -59,7 +59,7 @@ fn load_warnings(path: &Path) -> Vec<LintJson> {
     serde_json::from_slice(&file).unwrap_or_else(|e| panic!("failed to deserialize {}: {e}", path.display()))
 }
 
-pub(crate) fn diff(old_path: &Path, new_path: &Path, truncate: bool) {
+pub(crate) fn diff(old_path: &Path, new_path: &Path, truncate: bool, html: bool) {
     let old_warnings = load_warnings(old_path);
     let new_warnings = load_warnings(new_path);
 
@@ -93,17 +93,10 @@ pub(crate) fn diff(old_path: &Path, new_path: &Path, truncate: bool) {
         }
     }
 
-    print_summary_table(&lint_warnings);
-    println!();
-
-    if lint_warnings.is_empty() {
-        return;
-    }
-
     let truncate_after = if truncate {
         // Max 15 ensures that we at least have five messages per lint
         DEFAULT_LIMIT_PER_LINT
-            .min(TRUNCATION_TOTAL_TARGET / lint_warnings.len())
+            .min(TRUNCATION_TOTAL_TARGET / lint_warnings.len().max(1))
             .max(15)
     } else {
         // No lint should ever each this number of lint emissions, so this is equivialent to
@@ -111,17 +104,29 @@ pub(crate) fn diff(old_path: &Path, new_path: &Path, truncate: bool) {
         usize::MAX
     };
 
+    if html {
+        print!("{}", crate::html::diff(&lint_warnings, truncate_after));
+        return;
+    }
+
+    print_summary_table(&lint_warnings);
+    println!();
+
+    if lint_warnings.is_empty() {
+        return;
+    }
+
     for lint in lint_warnings {
         print_lint_warnings(&lint, truncate_after);
     }
 }
 
 #[derive(Debug)]
-struct LintWarnings {
-    name: String,
-    added: Vec<LintJson>,
-    removed: Vec<LintJson>,
-    changed: Vec<(LintJson, LintJson)>,
+pub(crate) struct LintWarnings {
+    pub name: String,
+    pub added: Vec<LintJson>,
+    pub removed: Vec<LintJson>,
+    pub changed: Vec<(LintJson, LintJson)>,
 }
 
 fn print_lint_warnings(lint: &LintWarnings, truncate_after: usize) {
@@ -235,7 +240,7 @@ fn print_h3(lint: &str, title: &str) {
 
 /// GitHub's markdown parsers doesn't like IDs with `::` and `_`. This simplifies
 /// the lint name for the HTML ID.
-fn to_html_id(lint_name: &str) -> String {
+pub(crate) fn to_html_id(lint_name: &str) -> String {
     lint_name.replace("clippy::", "").replace('_', "-")
 }
 
@@ -22,11 +22,16 @@
 
 mod config;
 mod driver;
+mod html;
+mod ice;
 mod input;
 mod json;
 mod output;
+mod perf;
 mod popular_crates;
 mod recursive;
+mod vendor;
+mod verify;
 
 use crate::config::{Commands, LintcheckConfig, OutputFormat};
 use crate::recursive::LintcheckServer;
@@ -36,15 +41,19 @@ use std::io::{self};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{env, fs};
 
 use cargo_metadata::Message;
-use input::read_crates;
+use input::{OfflineSource, read_crates};
 use output::{ClippyCheckOutput, ClippyWarning, RustcIce};
+use perf::PassTimings;
 use rayon::prelude::*;
 
 const LINTCHECK_DOWNLOADS: &str = "target/lintcheck/downloads";
 const LINTCHECK_SOURCES: &str = "target/lintcheck/sources";
+const LINTCHECK_VERIFY: &str = "target/lintcheck/verify";
+const LINTCHECK_ICE: &str = "target/lintcheck/ice";
 
 /// Represents the actual source code of a crate that we ran "cargo clippy" on
 #[derive(Debug)]
@@ -55,6 +64,8 @@ struct Crate {
     path: PathBuf,
     options: Option<Vec<String>>,
     base_url: String,
+    // replaces crates.io when running cargo
+    offline: Option<OfflineSource>,
 }
 
 impl Crate {
@@ -118,10 +129,18 @@ impl Crate {
         let mut cmd = Command::new("cargo");
         cmd.arg(if config.fix { "fix" } else { "check" })
             .arg("--quiet")
+            .args(self.offline.iter().flat_map(OfflineSource::cargo_args))
             .current_dir(&self.path)
             .env("CLIPPY_ARGS", clippy_args.join("__CLIPPY_HACKERY__"))
             .env("CLIPPY_DISABLE_DOCS_LINKS", "1");
 
+        if config.perf {
+            // Any value enables the profiling, a new one makes cargo re-run clippy on crates which
+            // were checked before
+            let run_id = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+            cmd.env("CLIPPY_PROFILE", run_id.to_string());
+        }
+
         if let Some(server) = server {
             // `cargo clippy` is a wrapper around `cargo check` that mainly sets `RUSTC_WORKSPACE_WRAPPER` to
             // `clippy-driver`. We do the same thing here with a couple changes:
@@ -184,17 +203,20 @@ impl Crate {
             return Vec::new();
         }
 
-        // get all clippy warnings and ICEs
+        // get all clippy warnings, pass timings and ICEs
         let mut entries: Vec<ClippyCheckOutput> = Message::parse_stream(stdout.as_bytes())
             .filter_map(|msg| match msg {
                 Ok(Message::CompilerMessage(message)) => ClippyWarning::new(
                     normalize_diag(message.message, shared_target_dir.to_str().unwrap()),
                     &self.base_url,
                     &self.name,
-                ),
+                )
+                .map(ClippyCheckOutput::ClippyWarning),
+                // The timings are not a diagnostic, so they can't be parsed as a `CompilerMessage`
+                Ok(Message::TextLine(line)) => PassTimings::from_cargo_line(&line)
+                    .map(|timings| ClippyCheckOutput::PassTimings(self.name.clone(), timings)),
                 _ => None,
             })
-            .map(ClippyCheckOutput::ClippyWarning)
             .collect();
 
         if let Some(ice) = RustcIce::from_stderr_and_status(&self.name, *status, &stderr) {
@@ -252,6 +274,11 @@ fn main() {
         driver::drive(&addr);
     }
 
+    // We're being executed as a `RUSTC_WORKSPACE_WRAPPER` to record an ICE
+    if let Ok(path) = env::var("LINTCHECK_ICE_INVOCATION") {
+        driver::record_ice(&path);
+    }
+
     // assert that we launch lintcheck from the repo root (via cargo lintcheck)
     if fs::metadata("lintcheck/Cargo.toml").is_err() {
         eprintln!("lintcheck needs to be run from clippy's repo root!\nUse `cargo lintcheck` alternatively.");
@@ -261,8 +288,15 @@ fn main() {
     let config = LintcheckConfig::new();
 
     match config.subcommand {
-        Some(Commands::Diff { old, new, truncate }) => json::diff(&old, &new, truncate),
+        Some(Commands::Diff {
+            old,
+            new,
+            truncate,
+            html,
+        }) => json::diff(&old, &new, truncate, html),
+        Some(Commands::PerfDiff { old, new, threshold }) => perf::diff(&old, &new, threshold),
         Some(Commands::Popular { output, number }) => popular_crates::fetch(output, number).unwrap(),
+        Some(Commands::Vendor { ref dir }) => vendor::snapshot(&config, dir),
         None => lintcheck(config),
     }
 }
@@ -344,6 +378,19 @@ fn lintcheck(config: LintcheckConfig) {
         .build_global()
         .unwrap();
 
+    if config.verify {
+        let reports: Vec<_> = crates
+            .par_iter()
+            .map(|krate| krate.verify_fixes(&clippy_driver_path, &config, &lint_level_args))
+            .collect();
+        let text = verify::output(&reports, &config);
+
+        println!("Writing the report to {}", config.verify_results_path.display());
+        fs::create_dir_all(config.verify_results_path.parent().unwrap()).unwrap();
+        fs::write(&config.verify_results_path, text).unwrap();
+        return;
+    }
+
     let server = config.recursive.then(|| {
         let _: io::Result<()> = fs::remove_dir_all("target/lintcheck/shared_target_dir/recursive");
 
@@ -365,9 +412,7 @@ fn lintcheck(config: LintcheckConfig) {
         .collect();
 
     if let Some(server) = server {
-        let server_clippy_entries = server.warnings().map(ClippyCheckOutput::ClippyWarning);
-
-        clippy_entries.extend(server_clippy_entries);
+        clippy_entries.extend(server.outputs());
     }
 
     // if we are in --fix mode, don't change the log files, terminate here
@@ -375,17 +420,43 @@ fn lintcheck(config: LintcheckConfig) {
         return;
     }
 
-    // split up warnings and ices
+    // split up warnings, ices and timings
     let mut warnings: Vec<ClippyWarning> = vec![];
     let mut raw_ices: Vec<RustcIce> = vec![];
+    let mut timings: Vec<(String, PassTimings)> = vec![];
     for entry in clippy_entries {
-        if let ClippyCheckOutput::ClippyWarning(x) = entry {
-            warnings.push(x);
-        } else if let ClippyCheckOutput::RustcIce(x) = entry {
-            raw_ices.push(x);
+        match entry {
+            ClippyCheckOutput::ClippyWarning(x) => warnings.push(x),
+            ClippyCheckOutput::RustcIce(x) => raw_ices.push(x),
+            ClippyCheckOutput::PassTimings(krate, x) => timings.push((krate, x)),
+        }
+    }
+
+    // reduce the ICEs to single file reproducers
+    for ice in &mut raw_ices {
+        if let Some(krate) = crates.iter().find(|krate| krate.name == ice.crate_name) {
+            match krate.reduce_ice(ice, &clippy_driver_path, &lint_level_args, &config.ice_reproducers_path) {
+                Ok(reproducer) => {
+                    println!(
+                        "Wrote the reproducer for the ICE in {} to {}",
+                        krate.name,
+                        reproducer.display()
+                    );
+                    ice.reproducer = Some(reproducer);
+                },
+                Err(e) => eprintln!("Failed to reduce the ICE in {}: {e}", krate.name),
+            }
         }
     }
 
+    if config.perf {
+        let text = perf::output(&timings);
+
+        println!("Writing the timings to {}", config.perf_results_path.display());
+        fs::create_dir_all(config.perf_results_path.parent().unwrap()).unwrap();
+        fs::write(&config.perf_results_path, text).unwrap();
+    }
+
     let text = match config.format {
         OutputFormat::Text | OutputFormat::Markdown => {
             output::summarize_and_print_changes(&warnings, &raw_ices, clippy_ver, &config)
@@ -400,6 +471,7 @@ fn lintcheck(config: LintcheckConfig) {
 
             json::output(warnings)
         },
+        OutputFormat::Html => html::output(&warnings, &raw_ices, &clippy_ver),
     };
 
     println!("Writing logs to {}", config.lintcheck_results_path.display());
@@ -3,33 +3,39 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{self, Write as _};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
 
 use crate::config::{LintcheckConfig, OutputFormat};
+use crate::perf::PassTimings;
 
 /// A single emitted output from clippy being executed on a crate. It may either be a
 /// `ClippyWarning`, or a `RustcIce` caused by a panic within clippy. A crate may have many
 /// `ClippyWarning`s but a maximum of one `RustcIce` (at which point clippy halts execution).
+/// With `--perf`, each compilation also reports the time spent in the lint passes.
 #[derive(Debug)]
 pub enum ClippyCheckOutput {
     ClippyWarning(ClippyWarning),
     RustcIce(RustcIce),
+    /// The name of the crate, and the timings of one of its compilations
+    PassTimings(String, PassTimings),
 }
 
 #[derive(Debug)]
 pub struct RustcIce {
     pub crate_name: String,
     pub ice_content: String,
+    /// The script running clippy on the reduced reproducer, see [`crate::ice`]
+    pub reproducer: Option<PathBuf>,
 }
 
 impl fmt::Display for RustcIce {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}:\n{}\n========================================\n",
-            self.crate_name, self.ice_content
-        )
+        write!(f, "{}:\n{}\n", self.crate_name, self.ice_content)?;
+        if let Some(reproducer) = &self.reproducer {
+            writeln!(f, "Reproducer: {}", reproducer.display())?;
+        }
+        writeln!(f, "========================================")
     }
 }
 
@@ -41,6 +47,7 @@ impl RustcIce {
             Some(Self {
                 crate_name: crate_name.to_owned(),
                 ice_content: stderr.to_owned(),
+                reproducer: None,
             })
         } else {
             None
@@ -121,6 +128,7 @@ impl ClippyWarning {
                 output
             },
             OutputFormat::Json => unreachable!("JSON output is handled via serde"),
+            OutputFormat::Html => unreachable!("HTML output is handled in `crate::html`"),
         }
     }
 }
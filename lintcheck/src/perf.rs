@@ -0,0 +1,214 @@
+//! `--perf` collects the time spent in each lint pass, as measured by clippy-driver when
+//! `CLIPPY_PROFILE` is set. `perf-diff` compares two of the resulting reports to find the passes
+//! which got slower.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Passes which took less than this in both reports are ignored by `perf-diff`, the differences
+/// are mostly noise
+const MIN_SECONDS: f64 = 0.01;
+
+/// The timings clippy-driver prints for a single compilation
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PassTimings {
+    #[serde(rename = "$message_type")]
+    message_type: String,
+    /// The seconds spent in each pass, by the name of the pass
+    passes: BTreeMap<String, f64>,
+}
+
+impl PassTimings {
+    /// Parses a line of clippy-driver's stderr, or a file it wrote to `CLIPPY_PROFILE_DIR`
+    pub fn from_line(line: &str) -> Option<Self> {
+        serde_json::from_str::<Self>(line)
+            .ok()
+            .filter(|timings| timings.message_type == "clippy_profile")
+    }
+
+    /// Parses a line of `cargo check --message-format=json`'s output, in which cargo wraps the
+    /// timings in a `compiler-message`
+    pub fn from_cargo_line(line: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct CompilerMessage {
+            message: PassTimings,
+        }
+
+        serde_json::from_str::<CompilerMessage>(line)
+            .ok()
+            .map(|msg| msg.message)
+            .filter(|timings| timings.message_type == "clippy_profile")
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct PerfReport {
+    /// The seconds spent in each pass, summed over all crates
+    passes: BTreeMap<String, f64>,
+    /// The seconds spent in each pass for each crate
+    crates: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+/// Creates the `--perf` report, and prints the slowest passes
+pub fn output(timings: &[(String, PassTimings)]) -> String {
+    let mut report = PerfReport::default();
+    for (krate, timings) in timings {
+        let crate_passes = report.crates.entry(krate.clone()).or_default();
+        for (pass, seconds) in &timings.passes {
+            *report.passes.entry(pass.clone()).or_default() += seconds;
+            *crate_passes.entry(pass.clone()).or_default() += seconds;
+        }
+    }
+
+    let mut slowest: Vec<_> = report.passes.iter().collect();
+    slowest.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    println!("\nSlowest lint passes:");
+    for (pass, seconds) in slowest.into_iter().take(10) {
+        println!("{pass:<50} {seconds:>9.3}s");
+    }
+
+    serde_json::to_string_pretty(&report).unwrap()
+}
+
+fn load_report(path: &Path) -> PerfReport {
+    let file = fs::read(path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+
+    serde_json::from_slice(&file).unwrap_or_else(|e| panic!("failed to deserialize {}: {e}", path.display()))
+}
+
+/// Prints a markdown table of the passes whose total time grew by more than `threshold` percent
+pub fn diff(old_path: &Path, new_path: &Path, threshold: f64) {
+    print!("{}", compare(&load_report(old_path), &load_report(new_path), threshold));
+}
+
+/// Compares the total time of each pass in the two reports
+fn compare(old: &PerfReport, new: &PerfReport, threshold: f64) -> String {
+    let old_total: f64 = old.passes.values().sum();
+    let new_total: f64 = new.passes.values().sum();
+    let mut text = format!(
+        "Total time in lint passes: {old_total:.3}s -> {new_total:.3}s ({})\n\n",
+        percent_change(old_total, new_total)
+    );
+
+    let mut regressions: Vec<(&str, f64, f64)> = new
+        .passes
+        .iter()
+        .map(|(pass, &new_seconds)| (pass.as_str(), old.passes.get(pass).copied().unwrap_or(0.0), new_seconds))
+        .filter(|&(_, old_seconds, new_seconds)| {
+            new_seconds >= MIN_SECONDS && new_seconds > old_seconds * (1.0 + threshold / 100.0)
+        })
+        .collect();
+
+    if regressions.is_empty() {
+        writeln!(text, "No lint pass got more than {threshold}% slower").unwrap();
+        return text;
+    }
+
+    // The biggest absolute regressions first
+    regressions.sort_by(|(_, old_a, new_a), (_, old_b, new_b)| (new_b - old_b).total_cmp(&(new_a - old_a)));
+
+    text.push_str("| Pass                                               | Old       | New       | Change   |\n");
+    text.push_str("| -------------------------------------------------- | --------: | --------: | -------: |\n");
+    for (pass, old_seconds, new_seconds) in regressions {
+        writeln!(
+            text,
+            "| {pass:<50} | {old_seconds:>8.3}s | {new_seconds:>8.3}s | {:>8} |",
+            percent_change(old_seconds, new_seconds)
+        )
+        .unwrap();
+    }
+    text
+}
+
+fn percent_change(old: f64, new: f64) -> String {
+    if old == 0.0 {
+        String::from("new")
+    } else {
+        format!("{:+.1}%", (new - old) / old * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timings() {
+        let line = r#"{"$message_type":"clippy_profile","passes":{"Foo":0.5,"Bar":0.25}}"#;
+        let timings = PassTimings::from_line(line).unwrap();
+        assert_eq!(
+            timings.passes,
+            BTreeMap::from([(String::from("Bar"), 0.25), (String::from("Foo"), 0.5)])
+        );
+        assert!(PassTimings::from_line(r#"{"$message_type":"diagnostic","passes":{}}"#).is_none());
+        assert!(PassTimings::from_line("warning: unused variable").is_none());
+
+        let cargo_line = format!(r#"{{"reason":"compiler-message","message":{line}}}"#);
+        assert_eq!(
+            PassTimings::from_cargo_line(&cargo_line).unwrap().passes,
+            timings.passes
+        );
+        assert!(PassTimings::from_cargo_line(line).is_none());
+    }
+
+    #[test]
+    fn sum_timings() {
+        let timings = |line: &str| PassTimings::from_line(line).unwrap();
+        let report = output(&[
+            (
+                String::from("a"),
+                timings(r#"{"$message_type":"clippy_profile","passes":{"Foo":0.5}}"#),
+            ),
+            (
+                String::from("a"),
+                timings(r#"{"$message_type":"clippy_profile","passes":{"Foo":0.25,"Bar":1}}"#),
+            ),
+            (
+                String::from("b"),
+                timings(r#"{"$message_type":"clippy_profile","passes":{"Foo":1}}"#),
+            ),
+        ]);
+        let report: PerfReport = serde_json::from_str(&report).unwrap();
+        assert_eq!(
+            report.passes,
+            BTreeMap::from([(String::from("Bar"), 1.0), (String::from("Foo"), 1.75)])
+        );
+        assert_eq!(
+            report.crates["a"],
+            BTreeMap::from([(String::from("Bar"), 1.0), (String::from("Foo"), 0.75)])
+        );
+    }
+
+    #[test]
+    fn compare_reports() {
+        let report = |passes: &[(&str, f64)]| PerfReport {
+            passes: passes
+                .iter()
+                .map(|&(pass, seconds)| (pass.to_string(), seconds))
+                .collect(),
+            crates: BTreeMap::new(),
+        };
+        let old = report(&[("Slower", 1.0), ("Same", 2.0), ("Noise", 0.001)]);
+        let new = report(&[("Slower", 1.5), ("Same", 2.0), ("Noise", 0.005), ("New", 0.5)]);
+
+        assert_eq!(
+            compare(&old, &new, 10.0),
+            "\
+Total time in lint passes: 3.001s -> 4.005s (+33.5%)
+
+| Pass                                               | Old       | New       | Change   |
+| -------------------------------------------------- | --------: | --------: | -------: |
+| New                                                |    0.000s |    0.500s |      new |
+| Slower                                             |    1.000s |    1.500s |   +50.0% |
+"
+        );
+        assert_eq!(
+            compare(&old, &old, 10.0),
+            "Total time in lint passes: 3.001s -> 3.001s (+0.0%)\n\nNo lint pass got more than 10% slower\n"
+        );
+    }
+}
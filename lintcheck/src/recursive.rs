@@ -1,10 +1,12 @@
 //! In `--recursive` mode we set the `lintcheck` binary as the `RUSTC_WRAPPER` of `cargo check`,
 //! this allows [`crate::driver`] to be run for every dependency. The driver connects to
-//! [`LintcheckServer`] to ask if it should be skipped, and if not sends the stderr of running
-//! clippy on the crate to the server
+//! [`LintcheckServer`] to ask if it should be skipped, and if not sends the pass timings and the
+//! stderr of running clippy on the crate to the server
 
 use crate::ClippyWarning;
 use crate::input::RecursiveOptions;
+use crate::output::ClippyCheckOutput;
+use crate::perf::PassTimings;
 
 use std::collections::HashSet;
 use std::io::{BufRead, BufReader, Read, Write};
@@ -45,7 +47,7 @@ where
 
 fn process_stream(
     stream: TcpStream,
-    sender: &Sender<ClippyWarning>,
+    sender: &Sender<ClippyCheckOutput>,
     options: &RecursiveOptions,
     seen: &Mutex<HashSet<DriverInfo>>,
 ) {
@@ -58,6 +60,19 @@ fn process_stream(
     let should_run = unseen && !ignored;
 
     serialize_line(&should_run, stream.get_mut());
+    if !should_run {
+        return;
+    }
+
+    let timings: Vec<PassTimings> = deserialize_line(&mut stream);
+    for timings in timings {
+        sender
+            .send(ClippyCheckOutput::PassTimings(
+                driver_info.package_name.clone(),
+                timings,
+            ))
+            .unwrap();
+    }
 
     let mut stderr = String::new();
     stream.read_to_string(&mut stderr).unwrap();
@@ -72,7 +87,8 @@ fn process_stream(
     let messages = stderr
         .lines()
         .filter_map(|json_msg| serde_json::from_str::<Diagnostic>(json_msg).ok())
-        .filter_map(|diag| ClippyWarning::new(diag, &base_url, &driver_info.package_name));
+        .filter_map(|diag| ClippyWarning::new(diag, &base_url, &driver_info.package_name))
+        .map(ClippyCheckOutput::ClippyWarning);
 
     for message in messages {
         sender.send(message).unwrap();
@@ -81,8 +97,8 @@ fn process_stream(
 
 pub(crate) struct LintcheckServer {
     pub local_addr: SocketAddr,
-    receiver: Receiver<ClippyWarning>,
-    sender: Arc<Sender<ClippyWarning>>,
+    receiver: Receiver<ClippyCheckOutput>,
+    sender: Arc<Sender<ClippyCheckOutput>>,
 }
 
 impl LintcheckServer {
@@ -90,7 +106,7 @@ impl LintcheckServer {
         let listener = TcpListener::bind("localhost:0").unwrap();
         let local_addr = listener.local_addr().unwrap();
 
-        let (sender, receiver) = crossbeam_channel::unbounded::<ClippyWarning>();
+        let (sender, receiver) = crossbeam_channel::unbounded::<ClippyCheckOutput>();
         let sender = Arc::new(sender);
         // The spawned threads hold a `Weak<Sender>` so that they don't keep the channel connected
         // indefinitely
@@ -120,7 +136,7 @@ impl LintcheckServer {
         }
     }
 
-    pub fn warnings(self) -> impl Iterator<Item = ClippyWarning> {
+    pub fn outputs(self) -> impl Iterator<Item = ClippyCheckOutput> {
         // causes the channel to become disconnected so that the receiver iterator ends
         drop(self.sender);
 
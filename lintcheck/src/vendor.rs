@@ -0,0 +1,186 @@
+//! `lintcheck vendor` snapshots the crates of a crates TOML and all their dependencies, so that
+//! lintcheck can be run on them without network access:
+//!
+//! - `<dir>/vendor` is a `cargo vendor` directory, which also contains the crates.io crates
+//! - `<dir>/sources` contains the git and local crates
+//! - `<dir>/lintcheck_crates.toml` lists the crates, and uses `<dir>/vendor` instead of crates.io
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::config::LintcheckConfig;
+use crate::input::{CrateSource, CrateWithSource, copy_dir, read_crates};
+
+pub(crate) fn snapshot(config: &LintcheckConfig, dir: &Path) {
+    let (crates, recursive_options) = read_crates(&config.sources_toml_path);
+    let vendor_dir = dir.join("vendor");
+    let sources_dir = dir.join("sources");
+
+    let prepared: Vec<_> = crates
+        .iter()
+        .map(|krate| (krate, krate.download_and_prepare()))
+        .collect();
+
+    println!("Vendoring the dependencies into {}", vendor_dir.display());
+    let mut cmd = Command::new("cargo");
+    cmd.args(["vendor", "--versioned-dirs"]);
+    // Allows taking a new snapshot from an existing one
+    if let Some((source, _)) = prepared.first()
+        && let Some(offline) = &source.offline
+    {
+        cmd.arg("--respect-source-config").args(offline.cargo_args());
+    }
+    for (i, (_, krate)) in prepared.iter().enumerate() {
+        cmd.arg(if i == 0 { "--manifest-path" } else { "--sync" })
+            .arg(krate.path.join("Cargo.toml"));
+    }
+    // `cargo vendor` prints the configuration to use the directory to stdout
+    let status = cmd.arg(&vendor_dir).stdout(Stdio::null()).status().unwrap();
+    assert!(status.success(), "failed to vendor the dependencies");
+
+    let mut toml = String::from("[crates]\n");
+    for (source, krate) in &prepared {
+        match &source.source {
+            CrateSource::CratesIo { version }
+            | CrateSource::Vendor { version, .. }
+            | CrateSource::LocalRegistry { version, .. } => {
+                // A crate which is also a dependency is already vendored
+                let dest = vendor_dir.join(format!("{}-{version}", source.name));
+                if !dest.is_dir() {
+                    copy_dir(&krate.path, &dest);
+                    // Directory sources need a checksum file, an empty one skips the verification
+                    fs::write(dest.join(".cargo-checksum.json"), r#"{"files":{},"package":null}"#).unwrap();
+                }
+                toml.push_str(&toml_entry(source, None));
+            },
+            CrateSource::Git { .. } | CrateSource::Path { .. } => {
+                let dest = sources_dir.join(krate.path.file_name().unwrap());
+                if dest.exists() {
+                    fs::remove_dir_all(&dest).unwrap();
+                }
+                copy_dir(&krate.path, &dest);
+                toml.push_str(&toml_entry(source, Some(&dest)));
+            },
+        }
+    }
+    toml.push_str(&toml_footer(&recursive_options.ignore, &vendor_dir));
+
+    let toml_path = dir.join("lintcheck_crates.toml");
+    fs::write(&toml_path, toml).unwrap();
+    println!(
+        "Wrote {}, run `cargo lintcheck --crates-toml {0}` to use it",
+        toml_path.display()
+    );
+}
+
+/// The entry of a crate in the new crates TOML, `copy` is where a git or local crate was copied to
+fn toml_entry(source: &CrateWithSource, copy: Option<&Path>) -> String {
+    let name = &source.name;
+    let options = source
+        .options
+        .as_ref()
+        .map(|options| format!(", options = {options:?}"))
+        .unwrap_or_default();
+
+    match (&source.source, copy) {
+        (
+            CrateSource::CratesIo { version }
+            | CrateSource::Vendor { version, .. }
+            | CrateSource::LocalRegistry { version, .. },
+            _,
+        ) => format!("'{name}-{version}' = {{ name = '{name}', version = '{version}'{options} }}\n"),
+        (CrateSource::Git { .. } | CrateSource::Path { .. }, Some(copy)) => format!(
+            "'{}' = {{ name = '{name}', path = '{}', online_link = '{}'{options} }}\n",
+            copy.file_name().unwrap().to_str().unwrap(),
+            copy.display(),
+            source.file_link,
+        ),
+        (CrateSource::Git { .. } | CrateSource::Path { .. }, None) => {
+            panic!("the git and local crates are copied into the snapshot")
+        },
+    }
+}
+
+/// The end of the new crates TOML, with the crates ignored by `--recursive` and the vendor
+/// directory
+fn toml_footer(ignore: &HashSet<String>, vendor_dir: &Path) -> String {
+    let mut toml = String::new();
+    if !ignore.is_empty() {
+        let mut ignore: Vec<_> = ignore.iter().collect();
+        ignore.sort();
+        writeln!(toml, "\n[recursive]\nignore = {ignore:?}").unwrap();
+    }
+    writeln!(toml, "\n[offline]\nvendor = '{}'", vendor_dir.display()).unwrap();
+    toml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::OfflineSource;
+    use std::path::PathBuf;
+
+    #[test]
+    fn snapshot_toml() {
+        let dir = PathBuf::from("target/lintcheck/vendor_test");
+        fs::create_dir_all(&dir).unwrap();
+        let vendor_dir = dir.join("vendor");
+
+        let crates_io = CrateWithSource {
+            name: String::from("foo"),
+            source: CrateSource::CratesIo {
+                version: String::from("1.0.0"),
+            },
+            file_link: String::new(),
+            options: Some(vec![String::from("-Wclippy::pedantic")]),
+            offline: None,
+        };
+        let git = CrateSource::Git {
+            url: String::from("https://github.com/rust-lang/bar"),
+            commit: String::from("abc"),
+        };
+        let git = CrateWithSource {
+            name: String::from("bar"),
+            source: git,
+            file_link: String::from("https://github.com/rust-lang/bar/blob/abc/{file}#L{line}"),
+            options: None,
+            offline: None,
+        };
+        assert_eq!(
+            toml_entry(&crates_io, None),
+            "'foo-1.0.0' = { name = 'foo', version = '1.0.0', options = [\"-Wclippy::pedantic\"] }\n"
+        );
+
+        let mut toml = String::from("[crates]\n");
+        toml.push_str(&toml_entry(&crates_io, None));
+        toml.push_str(&toml_entry(&git, Some(&dir.join("sources/bar-abc"))));
+        toml.push_str(&toml_footer(&HashSet::from([String::from("baz")]), &vendor_dir));
+        let toml_path = dir.join("lintcheck_crates.toml");
+        fs::write(&toml_path, toml).unwrap();
+
+        // The crates.io crates are taken from the vendor directory, the git ones from their copy
+        let (crates, recursive) = read_crates(&toml_path);
+        let sources: Vec<_> = crates.iter().map(|krate| (&krate.name, &krate.source)).collect();
+        assert_eq!(sources, [
+            (&String::from("bar"), &CrateSource::Path {
+                path: dir.join("sources/bar-abc")
+            }),
+            (&String::from("foo"), &CrateSource::Vendor {
+                version: String::from("1.0.0"),
+                dir: vendor_dir.clone()
+            }),
+        ]);
+        assert_eq!(crates[0].file_link, git.file_link);
+        assert_eq!(crates[1].options, crates_io.options);
+        assert!(
+            crates
+                .iter()
+                .all(|krate| krate.offline == Some(OfflineSource::Vendor(vendor_dir.clone())))
+        );
+        assert_eq!(recursive.ignore, HashSet::from([String::from("baz")]));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
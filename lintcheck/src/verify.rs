@@ -0,0 +1,422 @@
+//! `--fix --verify`: applies the suggestions `cargo clippy --fix` would apply to a copy of each
+//! crate, the ones of clippy and of rustc, checks that the result still compiles and attributes the
+//! new errors to the lints whose suggestions touched them.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use cargo_metadata::Message;
+use cargo_metadata::diagnostic::{Applicability, Diagnostic, DiagnosticLevel, DiagnosticSpan};
+use serde::Serialize;
+
+use crate::config::{LintcheckConfig, OutputFormat};
+use crate::input::{OfflineSource, copy_dir};
+use crate::{Crate, LINTCHECK_VERIFY, shared_target_dir};
+
+/// A replacement in a source file, taken from a suggestion
+struct Edit {
+    byte_start: usize,
+    byte_end: usize,
+    replacement: String,
+}
+
+/// An edit of a suggestion which was applied, with the lines of the fixed source it replaced
+struct AppliedFix {
+    lint: String,
+    file: String,
+    line_start: usize,
+    line_end: usize,
+}
+
+/// An error in the fixed source which was not there before
+#[derive(Debug, Serialize)]
+pub struct BrokenSuggestion {
+    pub krate: String,
+    /// The lint whose suggestion touched the span of the error, if any
+    pub lint: Option<String>,
+    /// `file:line:column` of the error in the fixed source
+    pub location: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct VerifyReport {
+    /// The number of suggestions applied for each lint
+    pub applied: BTreeMap<String, usize>,
+    pub broken: Vec<BrokenSuggestion>,
+}
+
+impl Crate {
+    /// Applies the suggestions to a scratch copy of the crate, and checks that the fixed crate
+    /// still builds
+    pub fn verify_fixes(
+        &self,
+        clippy_driver_path: &Path,
+        config: &LintcheckConfig,
+        lint_levels_args: &[String],
+    ) -> VerifyReport {
+        println!("Verifying the suggestions for {} {}", self.name, self.version);
+
+        let scratch = PathBuf::from(LINTCHECK_VERIFY).join(format!("{}-{}", self.name, self.version));
+        if scratch.exists() {
+            fs::remove_dir_all(&scratch).unwrap();
+        }
+        copy_dir(&self.path, &scratch);
+
+        // Lint the unmodified copy to collect the suggestions, and the errors which were there before
+        let mut clippy_args: Vec<&str> = self.options.iter().flatten().map(String::as_str).collect();
+        clippy_args.extend(lint_levels_args.iter().map(String::as_str));
+        let diags = cargo_diagnostics(
+            cargo(self, &scratch, &["check"])
+                .env("RUSTC_WORKSPACE_WRAPPER", clippy_driver_path)
+                .env("CLIPPY_ARGS", clippy_args.join("__CLIPPY_HACKERY__"))
+                .env("CLIPPY_DISABLE_DOCS_LINKS", "1"),
+        );
+        let errors_before: HashSet<String> = diags.iter().filter_map(error_key).collect();
+
+        let mut report = VerifyReport::default();
+        let fixes = apply_suggestions(&scratch, &diags, &mut report.applied);
+
+        let mut commands = vec![&["check"][..]];
+        if config.verify_tests {
+            commands.push(&["test", "--no-run"]);
+        }
+        let mut seen = HashSet::new();
+        for args in commands {
+            for diag in cargo_diagnostics(&mut cargo(self, &scratch, args)) {
+                let Some(key) = error_key(&diag) else {
+                    continue;
+                };
+                let span = diag.spans.iter().find(|span| span.is_primary);
+                let location = span.map_or_else(
+                    || String::from("<no location>"),
+                    |span| format!("{}:{}:{}", span.file_name, span.line_start, span.column_start),
+                );
+                if errors_before.contains(&key) || !seen.insert((key.clone(), location.clone())) {
+                    continue;
+                }
+
+                report.broken.push(BrokenSuggestion {
+                    krate: format!("{}-{}", self.name, self.version),
+                    lint: span
+                        .and_then(|span| attribute(&fixes, span))
+                        .map(|fix| fix.lint.clone()),
+                    location,
+                    error: key,
+                });
+            }
+        }
+
+        report
+    }
+}
+
+fn cargo(krate: &Crate, dir: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.args(args)
+        .args(krate.offline.iter().flat_map(OfflineSource::cargo_args))
+        // `cargo clippy --fix` implies `--all-targets`
+        .args(["--all-targets", "--message-format=json", "--quiet"])
+        .current_dir(dir)
+        .env("CARGO_TARGET_DIR", shared_target_dir("verify"));
+    cmd
+}
+
+fn cargo_diagnostics(cmd: &mut Command) -> Vec<Diagnostic> {
+    let output = cmd.output().expect("failed to run cargo");
+    Message::parse_stream(output.stdout.as_slice())
+        .filter_map(|msg| match msg {
+            Ok(Message::CompilerMessage(message)) => Some(message.message),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Identifies an error independent of its position, which changes when suggestions are applied
+fn error_key(diag: &Diagnostic) -> Option<String> {
+    if !matches!(diag.level, DiagnosticLevel::Error | DiagnosticLevel::Ice)
+        // e.g. "aborting due to 2 previous errors"
+        || diag.spans.is_empty()
+    {
+        return None;
+    }
+
+    Some(match &diag.code {
+        Some(code) => format!("error[{}]: {}", code.code, diag.message),
+        None => format!("error: {}", diag.message),
+    })
+}
+
+/// The edits of the first machine applicable suggestion of a diagnostic, if they are all in the
+/// same file of the crate
+fn suggestion(diag: &Diagnostic) -> Option<(&str, Vec<Edit>)> {
+    diag.children.iter().find_map(|child| {
+        let file = &child.spans.first()?.file_name;
+        if Path::new(file).is_absolute() {
+            return None;
+        }
+
+        let edits = child
+            .spans
+            .iter()
+            .map(|span| {
+                if span.file_name != *file || span.suggestion_applicability != Some(Applicability::MachineApplicable) {
+                    return None;
+                }
+                Some(Edit {
+                    byte_start: span.byte_start as usize,
+                    byte_end: span.byte_end as usize,
+                    replacement: span.suggested_replacement.clone()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some((file.as_str(), edits))
+    })
+}
+
+/// Applies the machine applicable suggestions of clippy and rustc which don't overlap with each
+/// other, the same way `cargo fix` does. Counts the suggestions applied for each lint in `applied`,
+/// the ones of rustc diagnostics without a lint name are counted as `rustc`.
+fn apply_suggestions(root: &Path, diags: &[Diagnostic], applied: &mut BTreeMap<String, usize>) -> Vec<AppliedFix> {
+    let mut by_file: BTreeMap<&str, Vec<(&str, Vec<Edit>)>> = BTreeMap::new();
+    for diag in diags {
+        if let Some((file, edits)) = suggestion(diag) {
+            let lint = diag.code.as_ref().map_or("rustc", |code| code.code.as_str());
+            by_file.entry(file).or_default().push((lint, edits));
+        }
+    }
+
+    let mut fixes = Vec::new();
+    for (file, suggestions) in by_file {
+        let path = root.join(file);
+        let Ok(src) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        // The same suggestion is emitted once for each target the file is part of
+        let mut accepted: Vec<(&str, Edit)> = Vec::new();
+        for (lint, edits) in suggestions {
+            let overlaps = |a: &Edit, b: &Edit| {
+                a.byte_start == b.byte_start || (a.byte_start < b.byte_end && b.byte_start < a.byte_end)
+            };
+            if edits
+                .iter()
+                .any(|edit| accepted.iter().any(|(_, other)| overlaps(edit, other)))
+                || edits.iter().any(|edit| edit.byte_end > src.len())
+            {
+                continue;
+            }
+            *applied.entry(lint.to_owned()).or_default() += 1;
+            accepted.extend(edits.into_iter().map(|edit| (lint, edit)));
+        }
+        accepted.sort_by_key(|(_, edit)| edit.byte_start);
+
+        let mut new_src = String::with_capacity(src.len());
+        let mut ranges = Vec::new();
+        let mut pos = 0;
+        for (lint, edit) in &accepted {
+            new_src.push_str(&src[pos..edit.byte_start]);
+            ranges.push((*lint, new_src.len(), new_src.len() + edit.replacement.len()));
+            new_src.push_str(&edit.replacement);
+            pos = edit.byte_end;
+        }
+        new_src.push_str(&src[pos..]);
+        fs::write(&path, &new_src).unwrap();
+
+        let line_of = |offset: usize| new_src[..offset].matches('\n').count() + 1;
+        fixes.extend(ranges.into_iter().map(|(lint, start, end)| AppliedFix {
+            lint: lint.to_owned(),
+            file: file.to_owned(),
+            line_start: line_of(start),
+            line_end: line_of(end),
+        }));
+    }
+    fixes
+}
+
+/// Finds the applied suggestion with the smallest range which overlaps the lines of `span`
+fn attribute<'a>(fixes: &'a [AppliedFix], span: &DiagnosticSpan) -> Option<&'a AppliedFix> {
+    fixes
+        .iter()
+        .filter(|fix| fix.file == span.file_name && fix.line_start <= span.line_end && span.line_start <= fix.line_end)
+        .min_by_key(|fix| fix.line_end - fix.line_start)
+}
+
+/// Creates the "broken suggestion" report, and prints a summary
+pub fn output(reports: &[VerifyReport], config: &LintcheckConfig) -> String {
+    let mut applied: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut broken: BTreeMap<&str, Vec<&BrokenSuggestion>> = BTreeMap::new();
+    for report in reports {
+        for (lint, count) in &report.applied {
+            *applied.entry(lint).or_default() += count;
+        }
+        for suggestion in &report.broken {
+            broken
+                .entry(suggestion.lint.as_deref().unwrap_or("<unattributed>"))
+                .or_default()
+                .push(suggestion);
+        }
+    }
+
+    let broken_count: usize = broken.values().map(Vec::len).sum();
+    println!(
+        "\nApplied {} suggestions, found {broken_count} new errors",
+        applied.values().sum::<usize>()
+    );
+
+    if config.format == OutputFormat::Json {
+        return serde_json::to_string_pretty(reports).unwrap();
+    }
+
+    let mut text = String::from("### Applied suggestions\n\n");
+    text.push_str("| lint                                               | applied | errors |\n");
+    text.push_str("| -------------------------------------------------- | ------- | ------ |\n");
+    for (lint, count) in &applied {
+        let errors = broken.get(lint).map_or(0, Vec::len);
+        writeln!(text, "| {lint:<50} |  {count:>6} |  {errors:>5} |").unwrap();
+    }
+
+    text.push_str("\n### Broken suggestions\n");
+    for (lint, suggestions) in &broken {
+        println!("{lint}: {} new errors", suggestions.len());
+        writeln!(text, "\n#### {lint}\n").unwrap();
+        for suggestion in suggestions {
+            let location = format!("{}/{}", suggestion.krate, suggestion.location);
+            if config.format == OutputFormat::Markdown {
+                writeln!(text, "- `{location}` {}", suggestion.error).unwrap();
+            } else {
+                writeln!(text, "{location} {}", suggestion.error).unwrap();
+            }
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A span of `src/lib.rs`, with the fields of a suggestion given by `suggestion`
+    fn span(line: usize, start: usize, end: usize, suggestion: &str) -> String {
+        format!(
+            r#"{{"file_name":"src/lib.rs","byte_start":{start},"byte_end":{end},"line_start":{line},
+            "line_end":{line},"column_start":1,"column_end":1,"is_primary":true,"text":[],"label":null,
+            "expansion":null,{suggestion}}}"#
+        )
+    }
+
+    fn diag(level: &str, code: Option<&str>, spans: &[String], children: &[String]) -> Diagnostic {
+        let code = code.map_or(String::from("null"), |code| {
+            format!(r#"{{"code":"{code}","explanation":null}}"#)
+        });
+        serde_json::from_str(&format!(
+            r#"{{"message":"message","code":{code},"level":"{level}","spans":[{}],"children":[{}],"rendered":null}}"#,
+            spans.join(","),
+            children.join(","),
+        ))
+        .unwrap()
+    }
+
+    const NO_SUGGESTION: &str = r#""suggested_replacement":null,"suggestion_applicability":null"#;
+
+    /// A warning of `code` with a suggestion replacing `start..end` on `line`
+    fn warning(code: &str, line: usize, start: usize, end: usize, replacement: &str) -> Diagnostic {
+        let suggestion = span(
+            line,
+            start,
+            end,
+            &format!(r#""suggested_replacement":"{replacement}","suggestion_applicability":"MachineApplicable""#),
+        );
+        let help = format!(
+            r#"{{"message":"try","code":null,"level":"help","spans":[{suggestion}],"children":[],"rendered":null}}"#
+        );
+        diag("warning", Some(code), &[span(line, start, end, NO_SUGGESTION)], &[help])
+    }
+
+    #[test]
+    fn error_keys() {
+        let error = diag("error", Some("E0308"), &[span(1, 0, 1, NO_SUGGESTION)], &[]);
+        assert_eq!(error_key(&error).as_deref(), Some("error[E0308]: message"));
+        let error = diag("error", None, &[span(1, 0, 1, NO_SUGGESTION)], &[]);
+        assert_eq!(error_key(&error).as_deref(), Some("error: message"));
+
+        // e.g. "aborting due to 1 previous error"
+        assert_eq!(error_key(&diag("error", None, &[], &[])), None);
+        assert_eq!(error_key(&warning("clippy::needless_return", 1, 0, 1, "x")), None);
+    }
+
+    #[test]
+    fn applies_clippy_and_rustc_suggestions() {
+        let root = PathBuf::from("target/lintcheck/verify_test");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "let mut a = 1;\nreturn a;\n").unwrap();
+
+        let diags = [
+            warning("unused_mut", 1, 4, 9, "a"),
+            warning("clippy::needless_return", 2, 15, 24, "a"),
+            // Overlaps the suggestion of `needless_return`, so it isn't applied
+            warning("clippy::other", 2, 22, 23, "b"),
+        ];
+        let mut applied = BTreeMap::new();
+        let fixes = apply_suggestions(&root, &diags, &mut applied);
+
+        assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), "let a = 1;\na\n");
+        assert_eq!(
+            applied,
+            BTreeMap::from([
+                (String::from("clippy::needless_return"), 1),
+                (String::from("unused_mut"), 1)
+            ])
+        );
+        let fixed_lines: Vec<_> = fixes
+            .iter()
+            .map(|fix| (fix.lint.as_str(), fix.line_start, fix.line_end))
+            .collect();
+        assert_eq!(fixed_lines, [("unused_mut", 1, 1), ("clippy::needless_return", 2, 2)]);
+
+        let error = diag("error", Some("E0308"), &[span(2, 11, 12, NO_SUGGESTION)], &[]);
+        assert_eq!(
+            attribute(&fixes, &error.spans[0]).map(|fix| fix.lint.as_str()),
+            Some("clippy::needless_return")
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn report() {
+        let reports = [VerifyReport {
+            applied: BTreeMap::from([
+                (String::from("clippy::needless_return"), 2),
+                (String::from("unused_mut"), 1),
+            ]),
+            broken: vec![BrokenSuggestion {
+                krate: String::from("foo-1.0.0"),
+                lint: Some(String::from("clippy::needless_return")),
+                location: String::from("src/lib.rs:2:1"),
+                error: String::from("error[E0308]: mismatched types"),
+            }],
+        }];
+        let config = LintcheckConfig::parse_from(["lintcheck", "--format", "markdown"]);
+        assert_eq!(
+            output(&reports, &config),
+            "\
+### Applied suggestions
+
+| lint                                               | applied | errors |
+| -------------------------------------------------- | ------- | ------ |
+| clippy::needless_return                            |       2 |      1 |
+| unused_mut                                         |       1 |      0 |
+
+### Broken suggestions
+
+#### clippy::needless_return
+
+- `foo-1.0.0/src/lib.rs:2:1` error[E0308]: mismatched types
+"
+        );
+    }
+}
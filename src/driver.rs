@@ -9,12 +9,21 @@
 
 // FIXME: switch to something more ergonomic here, once available.
 // (Currently there is no way to opt into sysroot crates without `extern crate`.)
+extern crate rustc_ast;
+extern crate rustc_data_structures;
 extern crate rustc_driver;
+extern crate rustc_errors;
+extern crate rustc_hir;
 extern crate rustc_interface;
+extern crate rustc_lint;
+extern crate rustc_metadata;
+extern crate rustc_middle;
+extern crate rustc_serialize;
 extern crate rustc_session;
 extern crate rustc_span;
 
-use rustc_interface::interface;
+use rustc_driver::Compilation;
+use rustc_interface::{Queries, interface};
 use rustc_session::EarlyDiagCtxt;
 use rustc_session::config::ErrorOutputType;
 use rustc_session::parse::ParseSess;
@@ -27,6 +36,10 @@ use std::process::exit;
 
 use anstream::println;
 
+mod incremental;
+mod plugins;
+mod profile;
+
 /// If a command-line option matches `find_arg`, then apply the predicate `pred` on its value. If
 /// true, then return it. The parameter is assumed to be either `--arg=value` or `--arg value`.
 fn arg_value<'a>(args: &'a [String], find_arg: &str, pred: impl Fn(&str) -> bool) -> Option<&'a str> {
@@ -129,18 +142,33 @@ impl rustc_driver::Callbacks for RustcCallbacks {
 
 struct ClippyCallbacks {
     clippy_args_var: Option<String>,
+    /// The arguments of `cargo clippy` changing the diagnostics that are emitted, like
+    /// `--diff-base`
+    emission_args: Vec<String>,
 }
 
 impl rustc_driver::Callbacks for ClippyCallbacks {
-    // JUSTIFICATION: necessary in clippy driver to set `mir_opt_level`
-    #[allow(rustc::bad_opt_access)]
     fn config(&mut self, config: &mut interface::Config) {
         let conf_path = clippy_config::lookup_conf_file();
+        incremental::configure(
+            &config.opts,
+            conf_path.as_ref().ok().and_then(|(path, _)| path.as_deref()),
+            &self.emission_args,
+        );
+        // The diagnostics filtered by `--diff-base` depend on the changes in the repository, and the
+        // timings of the passes would be meaningless if some items were skipped
+        let cache_diagnostics = config.opts.incremental.is_some()
+            && !self.emission_args.iter().any(|arg| arg.starts_with("--diff-base="))
+            && !profile::enabled();
         let previous = config.register_lints.take();
         let clippy_args_var = self.clippy_args_var.take();
         config.psess_created = Some(Box::new(move |psess| {
             track_clippy_args(psess, clippy_args_var.as_deref());
             track_files(psess);
+            clippy_utils::diagnostics::restrict_rustc_fixes();
+            if cache_diagnostics {
+                incremental::track_diagnostics();
+            }
 
             // Trigger a rebuild if CLIPPY_CONF_DIR changes. The value must be a valid string so
             // changes between dirs that are invalid UTF-8 will not trigger rebuilds
@@ -148,6 +176,25 @@ impl rustc_driver::Callbacks for ClippyCallbacks {
                 Symbol::intern("CLIPPY_CONF_DIR"),
                 env::var("CLIPPY_CONF_DIR").ok().map(|dir| Symbol::intern(&dir)),
             ));
+            // Trigger a rebuild if the plugins change
+            psess.env_depinfo.get_mut().insert((
+                Symbol::intern("CLIPPY_PLUGINS"),
+                env::var("CLIPPY_PLUGINS").ok().map(|paths| Symbol::intern(&paths)),
+            ));
+            if let Some(paths) = plugins::dylib_paths() {
+                for path in env::split_paths(&paths) {
+                    if let Some(path) = path.to_str()
+                        && !path.is_empty()
+                    {
+                        psess.file_depinfo.get_mut().insert(Symbol::intern(path));
+                    }
+                }
+            }
+            // Re-run clippy to collect the timings when profiling is enabled
+            psess.env_depinfo.get_mut().insert((
+                Symbol::intern("CLIPPY_PROFILE"),
+                env::var("CLIPPY_PROFILE").ok().map(|value| Symbol::intern(&value)),
+            ));
         }));
         config.register_lints = Some(Box::new(move |sess, lint_store| {
             // technically we're ~guaranteed that this is none but might as well call anything that
@@ -157,19 +204,36 @@ impl rustc_driver::Callbacks for ClippyCallbacks {
             }
 
             let conf = clippy_config::Conf::read(sess, &conf_path);
+            let module_passes = lint_store.late_module_passes.len();
             clippy_lints::register_lints(lint_store, conf);
+            plugins::register(sess, lint_store);
+            clippy_lints::register_final_passes(lint_store);
             clippy_lints::register_pre_expansion_lints(lint_store, conf);
+
+            if profile::enabled() {
+                profile::wrap_passes(lint_store);
+            }
+            incremental::register_item_passes(lint_store, module_passes, cache_diagnostics);
         }));
 
-        // FIXME: #4825; This is required, because Clippy lints that are based on MIR have to be
-        // run on the unoptimized MIR. On the other hand this results in some false negatives. If
-        // MIR passes can be enabled / disabled separately, we should figure out, what passes to
-        // use for Clippy.
-        config.opts.unstable_opts.mir_opt_level = Some(0);
+        // The MIR optimization level is left as is, MIR based lints analyze the MIR from before the
+        // optimization passes with `clippy_utils::mir::analysis_mir`.
+        config.override_queries = Some(|_, providers| {
+            clippy_utils::mir::override_queries(&mut providers.queries);
+        });
 
         // Disable flattening and inlining of format_args!(), so the HIR matches with the AST.
         config.opts.unstable_opts.flatten_format_args = false;
     }
+
+    fn after_expansion<'tcx>(&mut self, _: &interface::Compiler, queries: &'tcx Queries<'tcx>) -> Compilation {
+        // The items of `--diff-items` are known once the HIR is built, after the early passes ran
+        queries
+            .global_ctxt()
+            .unwrap()
+            .enter(|tcx| clippy_utils::diff::collect_items(tcx));
+        Compilation::Continue
+    }
 }
 
 #[allow(clippy::ignored_unit_patterns)]
@@ -260,10 +324,20 @@ pub fn main() {
             exit(0);
         }
 
+        // `-Zclippy-profile` is not a rustc flag, it is removed from the arguments passed to rustc
+        if let Some(pos) = orig_args.iter().position(|arg| arg == "-Zclippy-profile") {
+            orig_args.remove(pos);
+            profile::enable();
+        }
+
         let mut args: Vec<String> = orig_args.clone();
         pass_sysroot_env_if_given(&mut args, sys_root_env);
 
         let mut no_deps = false;
+        let mut diff_base = None;
+        let mut diff_items = false;
+        let mut fix_only = None;
+        let mut emission_args = Vec::new();
         let clippy_args_var = env::var("CLIPPY_ARGS").ok();
         let clippy_args = clippy_args_var
             .as_deref()
@@ -275,6 +349,25 @@ pub fn main() {
                     no_deps = true;
                     None
                 },
+                "-Zclippy-profile" => {
+                    profile::enable();
+                    None
+                },
+                "--diff-items" => {
+                    diff_items = true;
+                    emission_args.push(s.to_string());
+                    None
+                },
+                _ if s.starts_with("--diff-base=") => {
+                    diff_base = s.strip_prefix("--diff-base=");
+                    emission_args.push(s.to_string());
+                    None
+                },
+                _ if s.starts_with("--fix-only=") => {
+                    fix_only = s.strip_prefix("--fix-only=");
+                    emission_args.push(s.to_string());
+                    None
+                },
                 _ => Some(s.to_string()),
             })
             .chain(vec!["--cfg".into(), "clippy".into()])
@@ -294,9 +387,22 @@ pub fn main() {
         let clippy_enabled = !cap_lints_allow && relevant_package && !info_query;
         if clippy_enabled {
             args.extend(clippy_args);
-            rustc_driver::RunCompiler::new(&args, &mut ClippyCallbacks { clippy_args_var })
-                .set_using_internal_features(using_internal_features)
-                .run()
+            if let Some(base) = diff_base {
+                clippy_utils::diff::filter_lints_by_diff(base, diff_items);
+            }
+            if let Some(lints) = fix_only {
+                clippy_utils::diagnostics::restrict_fixes_to(lints.split(',').map(ToString::to_string));
+            }
+            let result = rustc_driver::RunCompiler::new(&args, &mut ClippyCallbacks {
+                clippy_args_var,
+                emission_args,
+            })
+            .set_using_internal_features(using_internal_features)
+            .run();
+            if profile::enabled() {
+                profile::print(arg_value(&orig_args, "--crate-name", |_| true).unwrap_or_default());
+            }
+            result
         } else {
             rustc_driver::RunCompiler::new(&args, &mut RustcCallbacks { clippy_args_var })
                 .set_using_internal_features(using_internal_features)
@@ -318,6 +424,7 @@ Run <cyan>clippy-driver</> with the same arguments you use for <cyan>rustc</>
     <cyan,bold>-h</>, <cyan,bold>--help</>               Print this message
     <cyan,bold>-V</>, <cyan,bold>--version</>            Print version info and exit
     <cyan,bold>--rustc</>                  Pass all arguments to <cyan>rustc</>
+    <cyan,bold>-Zclippy-profile</>         Print the time spent in each lint pass as JSON to stderr
 
 <green,bold>Allowing / Denying lints</>
 You can use tool lints to allow or deny lints from your code, e.g.:
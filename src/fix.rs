@@ -0,0 +1,511 @@
+//! The modes of `cargo clippy` handling the suggestions itself instead of `cargo fix`:
+//! - `--fix-dry-run` prints the edits of the suggestions as a unified diff
+//! - `--fix-unsafe-verified` also applies the suggestions which are not machine applicable, one
+//!   lint at a time, and reverts them when the code does not compile anymore
+//!
+//! Both run `cargo check` with `--message-format=json` to collect the suggestions.
+
+use rustfix::diagnostics::{Applicability, Diagnostic};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::{env, fs};
+
+/// The replacement of the bytes `lo..hi` of a file, as `(lo, hi, replacement)`
+type Part = (usize, usize, String);
+
+/// The parts of a suggestion, replacing byte ranges within the lines `first_line..=last_line` of a
+/// file
+#[derive(PartialEq, Eq)]
+struct Edit {
+    first_line: usize,
+    last_line: usize,
+    parts: Vec<Part>,
+    lint: String,
+    message: String,
+    applicability: Option<Applicability>,
+}
+
+impl Edit {
+    fn sort_key(&self) -> impl Ord + '_ {
+        (self.first_line, self.last_line, &self.parts, &self.lint, &self.message)
+    }
+}
+
+/// The result of a `cargo check`
+struct Check {
+    status: ExitStatus,
+    /// The edits of the suggestions in each file, sorted by line
+    edits: BTreeMap<String, Vec<Edit>>,
+    /// The number of diagnostics with the `error` level, which are compilation errors as long as
+    /// the lints are capped with `--cap-lints=warn`
+    errors: usize,
+}
+
+/// Runs `cmd` and collects the suggestions it reports. When `only` is not empty, only the
+/// suggestions of these clippy lints are collected, like `--fix-only` would apply them.
+fn check(mut cmd: Command, only: &[String]) -> Check {
+    let mut child = cmd.stdout(Stdio::piped()).spawn().expect("could not run cargo");
+    let mut edits = BTreeMap::<String, Vec<Edit>>::new();
+    let mut errors = 0;
+    for line in BufReader::new(child.stdout.take().unwrap()).lines() {
+        let mut message: serde_json::Value = match serde_json::from_str(&line.expect("could not read cargo output")) {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+        if message["reason"] == "compiler-message" {
+            if message["message"]["level"] == "error" {
+                errors += 1;
+            }
+            let Ok(diag) = serde_json::from_value::<Diagnostic>(message["message"].take()) else {
+                continue;
+            };
+            collect_edits(&diag, only, &mut edits);
+        }
+    }
+    let status = child.wait().expect("failed to wait for cargo?");
+
+    for edits in edits.values_mut() {
+        edits.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+        // The same diagnostic is reported for every target the file is a part of
+        edits.dedup();
+    }
+    Check { status, edits, errors }
+}
+
+fn collect_edits(diag: &Diagnostic, only: &[String], edits: &mut BTreeMap<String, Vec<Edit>>) {
+    let lint = diag.code.as_ref().map_or("", |code| &code.code);
+    if !only.is_empty()
+        && !lint
+            .strip_prefix("clippy::")
+            .is_some_and(|name| only.iter().any(|lint| lint == name))
+    {
+        return;
+    }
+
+    for child in &diag.children {
+        let spans: Vec<_> = child
+            .spans
+            .iter()
+            .filter(|span| span.suggested_replacement.is_some())
+            .collect();
+        let Some(first) = spans.first() else {
+            continue;
+        };
+        if spans.iter().any(|span| span.file_name != first.file_name) {
+            continue;
+        }
+        edits.entry(first.file_name.clone()).or_default().push(Edit {
+            first_line: spans.iter().map(|span| span.line_start).min().unwrap(),
+            last_line: spans.iter().map(|span| span.line_end).max().unwrap(),
+            parts: spans
+                .iter()
+                .map(|span| {
+                    let replacement = span.suggested_replacement.clone().unwrap();
+                    (span.byte_start as usize, span.byte_end as usize, replacement)
+                })
+                .collect(),
+            lint: lint.to_string(),
+            message: child.message.clone(),
+            applicability: first.suggestion_applicability,
+        });
+    }
+}
+
+/// The lines `start..end` of a file replaced by an edit
+struct Hunk<'a> {
+    edit: &'a Edit,
+    start: usize,
+    end: usize,
+    new: String,
+}
+
+/// Computes the hunks of the `edits` to `source`, which are sorted by line. The parts of an edit on
+/// distant lines, like an import and the code using it, are in separate hunks, but the edit is
+/// skipped as a whole when one of its parts overlaps with the previous edits. A part which a
+/// previous edit already made, like the same import, is not made again.
+///
+/// Returns the hunks sorted by line, and the number of skipped edits.
+fn hunks<'a>(source: &str, line_starts: &[usize], edits: impl IntoIterator<Item = &'a Edit>) -> (Vec<Hunk<'a>>, usize) {
+    // The line of the byte `pos`, starting at 1
+    let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos);
+
+    let mut hunks: Vec<Hunk<'a>> = Vec::new();
+    let mut made_parts = Vec::new();
+    let mut skipped = 0;
+    for edit in edits {
+        let mut parts: Vec<_> = edit.parts.iter().filter(|part| !made_parts.contains(part)).collect();
+        parts.sort();
+        if parts.windows(2).any(|parts| parts[0].1 > parts[1].0) {
+            skipped += 1;
+            continue;
+        }
+
+        // The parts on the same lines, with the lines `start..end` they are on
+        let mut groups: Vec<(usize, usize, Vec<&Part>)> = Vec::new();
+        for part in &parts {
+            let start = line_of(part.0);
+            // The hunk ends with the line of the last replaced byte, or also has the next line when
+            // the newline ending it is replaced by text not ending a line
+            let (lo, hi, replacement) = part;
+            let ends_line = if replacement.is_empty() {
+                source[..*lo].ends_with('\n') || *lo == 0
+            } else {
+                replacement.ends_with('\n')
+            };
+            let last = if hi > lo && (ends_line || *hi == source.len()) {
+                hi - 1
+            } else {
+                *hi
+            };
+            let end = line_of(last) + 1;
+            match groups.last_mut() {
+                Some((_, group_end, group)) if start < *group_end => {
+                    *group_end = (*group_end).max(end);
+                    group.push(part);
+                },
+                _ => groups.push((start, end, vec![part])),
+            }
+        }
+        if groups.iter().any(|&(start, end, _)| {
+            end > line_starts.len() || hunks.iter().any(|hunk| start < hunk.end && hunk.start < end)
+        }) {
+            skipped += 1;
+            continue;
+        }
+
+        for (start, end, group) in groups {
+            let mut new = String::new();
+            let mut pos = line_starts[start - 1];
+            for (lo, hi, replacement) in group {
+                new.push_str(&source[pos..*lo]);
+                new.push_str(replacement);
+                pos = *hi;
+            }
+            new.push_str(&source[pos..line_starts[end - 1]]);
+            hunks.push(Hunk { edit, start, end, new });
+        }
+        made_parts.extend(parts);
+    }
+    hunks.sort_by_key(|hunk| hunk.start);
+    (hunks, skipped)
+}
+
+/// The offsets of the lines of `source`, followed by its length
+fn line_starts(source: &str) -> Vec<usize> {
+    source
+        .split_inclusive('\n')
+        .scan(0, |start, line| {
+            let line_start = *start;
+            *start += line.len();
+            Some(line_start)
+        })
+        .chain([source.len()])
+        .collect()
+}
+
+/// Runs `cmd`, a `cargo check` with `--message-format=json`, and prints the edits of the
+/// suggestions it reports
+pub fn dry_run(cmd: Command, manifest_path: Option<&str>, only: &[String]) -> Result<(), i32> {
+    let check = check(cmd, only);
+
+    let root = workspace_root(manifest_path);
+    let mut overlapping = 0;
+    for (file_name, edits) in &check.edits {
+        overlapping += print_diff(&root, file_name, edits);
+    }
+    if overlapping > 0 {
+        eprintln!("note: skipped {overlapping} suggestion(s) overlapping with the ones above");
+    }
+
+    if check.status.success() {
+        Ok(())
+    } else {
+        Err(check.status.code().unwrap_or(-1))
+    }
+}
+
+/// Prints the hunks of the `edits` to the file. Returns the number of edits which were not printed
+/// as they overlap with the previous ones.
+fn print_diff(root: &Path, file_name: &str, edits: &[Edit]) -> usize {
+    let Ok(source) = fs::read_to_string(root.join(file_name)) else {
+        return 0;
+    };
+    let (diff, skipped) = diff(&source, file_name, edits);
+    print!("{diff}");
+    skipped
+}
+
+/// The unified diff of the hunks of the `edits` to `source`, and the number of edits left out as
+/// they overlap with the previous ones
+fn diff(source: &str, file_name: &str, edits: &[Edit]) -> (String, usize) {
+    let lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let (hunks, skipped) = hunks(source, &line_starts(source), edits);
+
+    let mut diff = format!("--- a/{file_name}\n+++ b/{file_name}\n");
+    let (mut removed_lines, mut added_lines) = (0, 0);
+    for Hunk { edit, start, end, new } in &hunks {
+        let old_lines = &lines[start - 1..end - 1];
+        let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+        // A hunk without lines in the new file starts at the line before it
+        let new_start = start + added_lines - removed_lines - usize::from(new_lines.is_empty());
+        let applicability = match edit.applicability {
+            Some(Applicability::MachineApplicable) => "",
+            Some(Applicability::MaybeIncorrect) => "[maybe incorrect] ",
+            Some(Applicability::HasPlaceholders) => "[has placeholders] ",
+            Some(Applicability::Unspecified) | None => "[unspecified] ",
+        };
+        writeln!(
+            diff,
+            "@@ -{start},{} +{new_start},{} @@ {applicability}{}: {}",
+            old_lines.len(),
+            new_lines.len(),
+            edit.lint,
+            edit.message,
+        )
+        .unwrap();
+        for line in old_lines {
+            push_line(&mut diff, '-', line);
+        }
+        for line in &new_lines {
+            push_line(&mut diff, '+', line);
+        }
+
+        removed_lines += old_lines.len();
+        added_lines += new_lines.len();
+    }
+    (diff, skipped)
+}
+
+fn push_line(diff: &mut String, prefix: char, line: &str) {
+    writeln!(diff, "{prefix}{}", line.strip_suffix('\n').unwrap_or(line)).unwrap();
+    if !line.ends_with('\n') {
+        diff.push_str("\\ No newline at end of file\n");
+    }
+}
+
+/// Applies the machine applicable and maybe incorrect suggestions reported by `cmd`, a
+/// `cargo check` with `--message-format=json`, one lint at a time. After each lint, the code is
+/// checked again and its suggestions are reverted if they introduced errors.
+pub fn apply_verified(cmd: impl Fn() -> Command, manifest_path: Option<&str>, only: &[String]) -> Result<(), i32> {
+    let root = workspace_root(manifest_path);
+    let mut tried = BTreeSet::new();
+    let mut applied = Vec::new();
+    let mut failed = Vec::new();
+    // The lint whose suggestions were applied last, with the previous contents of the files
+    let mut batch: Option<(String, Vec<(PathBuf, String)>)> = None;
+    loop {
+        let check = check(cmd(), only);
+        if check.errors > 0 {
+            let Some((lint, sources)) = batch.take() else {
+                eprintln!("error: the code must compile before its suggestions can be verified");
+                return Err(check.status.code().unwrap_or(-1));
+            };
+            for (path, source) in sources {
+                fs::write(&path, source).unwrap_or_else(|e| panic!("could not revert `{}`: {e}", path.display()));
+            }
+            failed.push(lint);
+            continue;
+        }
+        if let Some((lint, _)) = batch.take() {
+            applied.push(lint);
+        }
+
+        // The suggestions of rustc are left to `cargo fix`
+        let is_applied = |edit: &Edit| {
+            edit.lint.starts_with("clippy::")
+                && matches!(
+                    edit.applicability,
+                    Some(Applicability::MachineApplicable | Applicability::MaybeIncorrect)
+                )
+        };
+        let Some(lint) = check
+            .edits
+            .values()
+            .flatten()
+            .find(|edit| is_applied(edit) && !tried.contains(&edit.lint))
+        else {
+            break;
+        };
+        let lint = lint.lint.clone();
+        tried.insert(lint.clone());
+
+        let mut sources = Vec::new();
+        for (file_name, edits) in &check.edits {
+            let path = root.join(file_name);
+            let edits = edits.iter().filter(|edit| edit.lint == lint && is_applied(edit));
+            let Ok(source) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let line_starts = line_starts(&source);
+            let (hunks, _) = hunks(&source, &line_starts, edits);
+            if hunks.is_empty() {
+                continue;
+            }
+            let mut new = String::new();
+            let mut pos = 0;
+            for hunk in hunks {
+                new.push_str(&source[pos..line_starts[hunk.start - 1]]);
+                new.push_str(&hunk.new);
+                pos = line_starts[hunk.end - 1];
+            }
+            new.push_str(&source[pos..]);
+            fs::write(&path, new).unwrap_or_else(|e| panic!("could not write `{}`: {e}", path.display()));
+            sources.push((path, source));
+        }
+        if !sources.is_empty() {
+            batch = Some((lint, sources));
+        }
+    }
+
+    if !applied.is_empty() {
+        eprintln!("applied the suggestions of `{}`", applied.join("`, `"));
+    }
+    for lint in &failed {
+        eprintln!("warning: the suggestions of `{lint}` introduced errors and were reverted");
+    }
+    Ok(())
+}
+
+/// The paths of the diagnostics are relative to the root of the workspace
+fn workspace_root(manifest_path: Option<&str>) -> PathBuf {
+    let mut cmd = Command::new(env::var("CARGO").unwrap_or("cargo".into()));
+    cmd.args(["locate-project", "--workspace", "--message-format", "plain"]);
+    if let Some(manifest_path) = manifest_path {
+        cmd.args(["--manifest-path", manifest_path]);
+    }
+    let output = cmd.stderr(Stdio::inherit()).output().expect("could not run cargo");
+    let manifest = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim_end());
+    manifest.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+use std::fmt;
+
+fn f() -> i32 {
+    let x = 1;
+    return x;
+}
+";
+
+    /// The part replacing the first occurrence of `old` in `SOURCE`
+    fn part(old: &str, new: &str) -> Part {
+        let lo = SOURCE.find(old).unwrap();
+        (lo, lo + old.len(), new.to_string())
+    }
+
+    fn edit(parts: Vec<Part>, lint: &str, applicability: Applicability) -> Edit {
+        let line_starts = line_starts(SOURCE);
+        let line_of = |pos: usize| line_starts.partition_point(|&start| start <= pos);
+        Edit {
+            first_line: parts.iter().map(|part| line_of(part.0)).min().unwrap(),
+            last_line: parts.iter().map(|part| line_of(part.1)).max().unwrap(),
+            parts,
+            lint: lint.to_string(),
+            message: "try".to_string(),
+            applicability: Some(applicability),
+        }
+    }
+
+    /// Imports `Reverse` before `use std::fmt;`, the way `SuggGroup::import` does
+    fn import() -> Part {
+        part("u", "use std::cmp::Reverse;\nu")
+    }
+
+    #[test]
+    fn skips_overlapping_edits() {
+        let edits = [
+            edit(vec![part("1", "2")], "clippy::a", Applicability::MachineApplicable),
+            edit(
+                vec![part("return x;", "x")],
+                "clippy::b",
+                Applicability::MachineApplicable,
+            ),
+            // Overlaps with the previous edit, so its import isn't made either
+            edit(
+                vec![import(), part("return", "Reverse")],
+                "clippy::c",
+                Applicability::MachineApplicable,
+            ),
+        ];
+        let (hunks, skipped) = hunks(SOURCE, &line_starts(SOURCE), &edits);
+        assert_eq!(skipped, 1);
+        let hunks: Vec<_> = hunks
+            .iter()
+            .map(|hunk| (hunk.start, hunk.end, hunk.new.as_str(), hunk.edit.lint.as_str()))
+            .collect();
+        assert_eq!(hunks, [
+            (4, 5, "    let x = 2;\n", "clippy::a"),
+            (5, 6, "    x\n", "clippy::b"),
+        ]);
+    }
+
+    #[test]
+    fn makes_identical_parts_once() {
+        let edits = [
+            edit(
+                vec![import(), part("1", "Reverse(1)")],
+                "clippy::a",
+                Applicability::MachineApplicable,
+            ),
+            edit(
+                vec![import(), part("x;", "Reverse(x);")],
+                "clippy::a",
+                Applicability::MachineApplicable,
+            ),
+        ];
+        let (hunks, skipped) = hunks(SOURCE, &line_starts(SOURCE), &edits);
+        assert_eq!(skipped, 0);
+        let hunks: Vec<_> = hunks
+            .iter()
+            .map(|hunk| (hunk.start, hunk.end, hunk.new.as_str()))
+            .collect();
+        assert_eq!(hunks, [
+            (1, 2, "use std::cmp::Reverse;\nuse std::fmt;\n"),
+            (4, 5, "    let x = Reverse(1);\n"),
+            (5, 6, "    return Reverse(x);\n"),
+        ]);
+    }
+
+    #[test]
+    fn diff() {
+        let edits = [
+            edit(
+                vec![import(), part("1", "Reverse(1)")],
+                "clippy::a",
+                Applicability::MaybeIncorrect,
+            ),
+            edit(
+                vec![part("return x;\n", "")],
+                "clippy::b",
+                Applicability::HasPlaceholders,
+            ),
+        ];
+        let (diff, skipped) = super::diff(SOURCE, "src/lib.rs", &edits);
+        assert_eq!(skipped, 0);
+        assert_eq!(
+            diff,
+            "\
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,1 +1,2 @@ [maybe incorrect] clippy::a: try
+-use std::fmt;
++use std::cmp::Reverse;
++use std::fmt;
+@@ -4,1 +5,1 @@ [maybe incorrect] clippy::a: try
+-    let x = 1;
++    let x = Reverse(1);
+@@ -5,2 +6,1 @@ [has placeholders] clippy::b: try
+-    return x;
+-}
++    }
+"
+        );
+    }
+}
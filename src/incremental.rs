@@ -0,0 +1,479 @@
+//! Reuse of clippy's diagnostics between incremental compilations.
+//!
+//! The late passes looking at one item at a time are registered with `register_late_mod_pass`.
+//! When compiling incrementally, [`ItemPasses`] runs them over the whole crate but skips the units
+//! which did not change since the previous compilation, [`DiagnosticCache`] replays their
+//! diagnostics from the `clippy-diagnostics` file of the incremental directory of the crate
+//! instead. A unit is an item directly in a module, with the items nested in it.
+//!
+//! A unit is linted again when its source or its HIR changed, and every unit is linted again when
+//! anything else which could change their diagnostics changed:
+//! - clippy or its configuration, see [`configure`]
+//! - the options of the compiler, including the lint levels given on the command line
+//! - the dependencies
+//! - any item other than a function, or the signature of a function
+//!
+//! The lints of a function can't depend on the body of another function, the passes which look at
+//! them are registered with `register_late_pass` and run over the whole crate every time, like the
+//! passes implementing `check_crate` or `check_crate_post`.
+//!
+//! The diagnostics of a unit are only cached when they all point into the unit outside of macro
+//! expansions, see [`encoding`]. Nothing is cached when `unused_clippy_allows` can be emitted, it
+//! has to see every `#[allow]` used by the other lints.
+
+mod encoding;
+
+use crate::plugins;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::stable_hasher::{Hash128, HashStable, StableHasher};
+use rustc_errors::{DiagInner, ErrorGuaranteed, TRACK_DIAGNOSTIC};
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::{HirId, ItemKind, ItemLocalId, Node, OwnerId, OwnerNode, OwnerNodes};
+use rustc_lint::{LateContext, LateLintPass, LintId, LintPass, LintStore, LintVec};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::Options;
+use rustc_session::{Session, impl_lint_pass};
+use rustc_span::Span;
+use std::cell::{Cell, RefCell};
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::{env, fs, process};
+
+/// The hash of clippy's configuration, set by [`configure`]
+static CONFIGURATION: OnceLock<Hash128> = OnceLock::new();
+
+type TrackDiagnostic = fn(DiagInner, &mut dyn FnMut(DiagInner) -> Option<ErrorGuaranteed>) -> Option<ErrorGuaranteed>;
+
+/// The `TRACK_DIAGNOSTIC` hook replaced by [`track_diagnostics`]
+static RUSTC_TRACK_DIAGNOSTIC: OnceLock<&'static TrackDiagnostic> = OnceLock::new();
+
+thread_local! {
+    /// The units of the crate being linted, set by [`DiagnosticCache`] when their diagnostics are
+    /// cached
+    static UNITS: RefCell<Option<Units>> = const { RefCell::new(None) };
+    /// The unit whose diagnostics [`ItemPasses`] is emitting
+    static CAPTURING: Cell<Option<LocalDefId>> = const { Cell::new(None) };
+    /// The diagnostics emitted for each unit
+    static CAPTURED: RefCell<FxHashMap<LocalDefId, Vec<DiagInner>>> = RefCell::default();
+}
+
+/// Hashes the configuration of clippy which changes its diagnostics: its version, the
+/// `emission_args` of `cargo clippy`, the configuration file, the plugins and the environment
+/// variables read by the lints.
+///
+/// Also removes the incremental directories used for each configuration by earlier versions of
+/// clippy, named `clippy-` followed by a hash.
+pub fn configure(opts: &Options, conf_path: Option<&Path>, emission_args: &[String]) {
+    let Some(dir) = &opts.incremental else {
+        return;
+    };
+
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let is_clippy_dir = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix("clippy-"))
+            .is_some_and(|hash| hash.len() == 16 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')));
+        // Unlike the directories of the crates, they don't contain any session directory
+        let has_sessions = || {
+            fs::read_dir(entry.path())
+                .into_iter()
+                .flatten()
+                .flatten()
+                .any(|entry| entry.file_name().to_str().is_some_and(|name| name.starts_with("s-")))
+        };
+        if is_clippy_dir && !has_sessions() {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+
+    let mut hasher = StableHasher::new();
+    rustc_tools_util::get_version_info!().to_string().hash(&mut hasher);
+    emission_args.hash(&mut hasher);
+    if let Some(path) = conf_path {
+        path.hash(&mut hasher);
+        fs::read(path).ok().hash(&mut hasher);
+    }
+    if let Some(paths) = plugins::dylib_paths() {
+        for path in env::split_paths(&paths) {
+            let metadata = fs::metadata(&path).ok();
+            (
+                &path,
+                metadata.map(|metadata| (metadata.len(), metadata.modified().ok())),
+            )
+                .hash(&mut hasher);
+        }
+    }
+    for var in ["CARGO_PKG_RUST_VERSION", "CLIPPY_DISABLE_DOCS_LINKS"] {
+        env::var_os(var).hash(&mut hasher);
+    }
+    let _ = CONFIGURATION.set(hasher.finish());
+}
+
+/// Replaces the `TRACK_DIAGNOSTIC` hook set by rustc, to capture the diagnostics of the units. It
+/// must be called once rustc's hook is set, e.g. when the parse session is created.
+pub fn track_diagnostics() {
+    let rustc = TRACK_DIAGNOSTIC.swap(&(track_diagnostic as _));
+    let _ = RUSTC_TRACK_DIAGNOSTIC.set(rustc);
+}
+
+fn track_diagnostic(
+    diag: DiagInner,
+    emit: &mut dyn FnMut(DiagInner) -> Option<ErrorGuaranteed>,
+) -> Option<ErrorGuaranteed> {
+    if let Some(unit) = CAPTURING.get() {
+        CAPTURED.with_borrow_mut(|captured| captured.entry(unit).or_default().push(diag.clone()));
+    }
+    RUSTC_TRACK_DIAGNOSTIC.get().unwrap()(diag, emit)
+}
+
+/// Runs the late passes registered by clippy from `first` with `register_late_mod_pass` over the
+/// whole crate, the passes before are rustc's. When `cache` is set their diagnostics are cached for
+/// each unit, see the module documentation.
+pub fn register_item_passes(store: &mut LintStore, first: usize, cache: bool) {
+    let passes = store.late_module_passes.split_off(first);
+    if !cache {
+        store.late_passes.extend(passes);
+        return;
+    }
+
+    let Ok(&[unused_allows]) = store.find_lints("clippy::unused_clippy_allows").as_deref() else {
+        unreachable!("`unused_clippy_allows` is registered by `clippy_lints::register_lints`");
+    };
+    store.register_late_pass(move |_| Box::new(DiagnosticCache { unused_allows }));
+    store.register_late_pass(move |tcx| Box::new(ItemPasses::new(tcx, &passes)));
+}
+
+/// The items directly in a module, with the items nested in them, whose diagnostics are cached
+/// together. The modules are not part of any unit, they are always linted.
+struct Units {
+    /// The unit of each owner which isn't a module
+    of_owner: FxHashMap<LocalDefId, LocalDefId>,
+    /// The key and the span of the units whose diagnostics can be cached
+    keys: FxIndexMap<LocalDefId, (u128, Span)>,
+    /// The encoded diagnostics of the units which were replayed
+    replayed: FxHashMap<LocalDefId, Vec<u8>>,
+    /// The number of units
+    count: usize,
+}
+
+impl Units {
+    fn new(tcx: TyCtxt<'_>) -> Self {
+        let mut session = StableHasher::new();
+        CONFIGURATION.get().hash(&mut session);
+        tcx.sess.opts.dep_tracking_hash(false).hash(&mut session);
+        let mut crates: Vec<_> = tcx
+            .crates(())
+            .iter()
+            .map(|&krate| (tcx.stable_crate_id(krate), tcx.crate_hash(krate).as_u128()))
+            .collect();
+        crates.sort_unstable();
+        crates.hash(&mut session);
+
+        let mut interface = Vec::new();
+        let mut contents = FxHashMap::default();
+        let mut units: FxIndexMap<LocalDefId, Vec<OwnerId>> = FxIndexMap::default();
+        let mut of_owner = FxHashMap::default();
+        tcx.with_stable_hashing_context(|mut hcx| {
+            hcx.while_hashing_spans(false, |hcx| {
+                for owner in tcx.hir_crate_items(()).owners() {
+                    let nodes = tcx.hir_owner_nodes(owner);
+                    let attrs = tcx.hir_attrs(owner);
+                    let mut hasher = StableHasher::new();
+                    nodes.node().hash_stable(hcx, &mut hasher);
+                    for (id, body) in nodes.bodies.iter() {
+                        (id, body).hash_stable(hcx, &mut hasher);
+                    }
+                    for (id, attrs) in attrs.map.iter() {
+                        (id, attrs).hash_stable(hcx, &mut hasher);
+                    }
+                    let content: Hash128 = hasher.finish();
+                    contents.insert(owner, content);
+
+                    let interface_hash = if let Some(signature) = signature(tcx, owner, nodes) {
+                        let mut hasher = StableHasher::new();
+                        signature.hash(&mut hasher);
+                        attrs.get(ItemLocalId::ZERO).hash_stable(hcx, &mut hasher);
+                        hasher.finish()
+                    } else {
+                        content
+                    };
+                    interface.push((tcx.def_path_hash(owner.to_def_id()), interface_hash));
+
+                    if let Some(unit) = unit(tcx, owner) {
+                        units.entry(unit).or_default().push(owner);
+                        of_owner.insert(owner.def_id, unit);
+                    }
+                }
+            });
+        });
+        interface.sort_unstable();
+        interface.hash(&mut session);
+        let session: Hash128 = session.finish();
+
+        let source_map = tcx.sess.source_map();
+        let keys = units
+            .iter()
+            .filter_map(|(&unit, owners)| {
+                let span = unit_span(tcx, unit)?;
+                let mut hasher = StableHasher::new();
+                session.hash(&mut hasher);
+                source_map.lookup_source_file(span.lo()).stable_id.hash(&mut hasher);
+                source_map.span_to_snippet(span).ok()?.hash(&mut hasher);
+                for owner in owners {
+                    (tcx.def_path_hash(owner.to_def_id()), contents[owner]).hash(&mut hasher);
+                }
+                Some((unit, (hasher.finish::<Hash128>().as_u128(), span)))
+            })
+            .collect();
+
+        Self {
+            of_owner,
+            keys,
+            replayed: FxHashMap::default(),
+            count: units.len(),
+        }
+    }
+}
+
+fn is_module(node: OwnerNode<'_>) -> bool {
+    matches!(node, OwnerNode::Crate(_))
+        || matches!(node, OwnerNode::Item(item) if matches!(item.kind, ItemKind::Mod(_)))
+}
+
+/// The unit of `owner`, its outermost ancestor directly in a module
+fn unit(tcx: TyCtxt<'_>, owner: OwnerId) -> Option<LocalDefId> {
+    if is_module(tcx.hir_owner_node(owner)) {
+        return None;
+    }
+    let mut unit = owner.def_id;
+    for (parent, node) in tcx.hir().parent_owner_iter(HirId::make_owner(owner.def_id)) {
+        if is_module(node) {
+            break;
+        }
+        unit = parent.def_id;
+    }
+    Some(unit)
+}
+
+/// The span of the source of `unit`, including its attributes, if its diagnostics can be cached
+fn unit_span(tcx: TyCtxt<'_>, unit: LocalDefId) -> Option<Span> {
+    let mut span = tcx.source_span(unit);
+    for attr in tcx.hir().attrs(HirId::make_owner(unit)) {
+        if attr.span.from_expansion() {
+            return None;
+        }
+        span = span.to(attr.span);
+    }
+    (!span.from_expansion()).then_some(span)
+}
+
+/// The source of the signature of `owner` if it's a function whose body doesn't change the
+/// diagnostics of the other units
+fn signature(tcx: TyCtxt<'_>, owner: OwnerId, nodes: &OwnerNodes<'_>) -> Option<String> {
+    let node = nodes.node();
+    node.fn_decl()?;
+    let body = tcx.hir().body(node.body_id()?);
+    let span = tcx.source_span(owner.def_id);
+    // The bodies of constant functions are evaluated by the other items, and the types returned by
+    // the functions returning an `impl Trait` depend on their bodies
+    if tcx.hir().body_const_context(owner.def_id).is_some()
+        || nodes.nodes.iter().any(|node| matches!(node.node, Node::OpaqueTy(_)))
+        || span.from_expansion()
+        || body.value.span.from_expansion()
+        || !span.contains(body.value.span)
+    {
+        return None;
+    }
+    tcx.sess
+        .source_map()
+        .span_to_snippet(span.with_hi(body.value.span.lo()))
+        .ok()
+}
+
+/// The file the diagnostics are cached in, in the incremental directory of the crate, next to the
+/// directories of the sessions
+fn cache_path(sess: &Session) -> Option<PathBuf> {
+    Some(sess.incr_comp_session_dir_opt()?.parent()?.join("clippy-diagnostics"))
+}
+
+/// Reads the encoded diagnostics of each unit by the key of the unit
+fn read_cache(path: &Path) -> FxHashMap<u128, Vec<u8>> {
+    let mut cache = FxHashMap::default();
+    let Ok(bytes) = fs::read(path) else {
+        return cache;
+    };
+    let mut rest = &bytes[..];
+    while let Some((key, tail)) = rest.split_first_chunk()
+        && let Some((len, tail)) = tail.split_first_chunk()
+        && let Ok(len) = usize::try_from(u64::from_le_bytes(*len))
+        && let Some((diags, tail)) = tail.split_at_checked(len)
+    {
+        cache.insert(u128::from_le_bytes(*key), diags.to_vec());
+        rest = tail;
+    }
+    cache
+}
+
+/// Replays the diagnostics of the units which did not change, then caches the diagnostics of the
+/// other units once the crate is linted
+struct DiagnosticCache {
+    unused_allows: LintId,
+}
+
+impl_lint_pass!(DiagnosticCache => []);
+
+impl LateLintPass<'_> for DiagnosticCache {
+    fn check_crate(&mut self, cx: &LateContext<'_>) {
+        // Unless `unused_clippy_allows` is allowed
+        if !cx.tcx.lints_that_dont_need_to_run(()).contains(&self.unused_allows) {
+            return;
+        }
+        let Some(path) = cache_path(cx.tcx.sess) else {
+            return;
+        };
+
+        let mut units = Units::new(cx.tcx);
+        let mut cache = read_cache(&path);
+        for (&unit, &(key, span)) in &units.keys {
+            if let Some(bytes) = cache.remove(&key)
+                && let Some(diags) = encoding::decode(cx.tcx, span, &bytes)
+            {
+                for diag in diags {
+                    cx.tcx.dcx().emit_diagnostic(diag);
+                }
+                units.replayed.insert(unit, bytes);
+            }
+        }
+        UNITS.set(Some(units));
+    }
+
+    fn check_crate_post(&mut self, cx: &LateContext<'_>) {
+        let Some(units) = UNITS.take() else {
+            return;
+        };
+        let Some(path) = cache_path(cx.tcx.sess) else {
+            return;
+        };
+
+        let captured = CAPTURED.take();
+        let mut cache = Vec::new();
+        for (unit, &(key, span)) in &units.keys {
+            let encoded;
+            let diags = if let Some(bytes) = units.replayed.get(unit) {
+                bytes
+            } else if let Some(bytes) = encoding::encode(cx.tcx, span, captured.get(unit).map_or(&[], Vec::as_slice)) {
+                encoded = bytes;
+                &encoded
+            } else {
+                continue;
+            };
+            cache.extend_from_slice(&key.to_le_bytes());
+            cache.extend_from_slice(&(diags.len() as u64).to_le_bytes());
+            cache.extend_from_slice(diags);
+        }
+        // Written to another file first, the cache is never read partially written
+        let tmp = path.with_extension(process::id().to_string());
+        if let Err(e) = fs::write(&tmp, cache).and_then(|()| fs::rename(&tmp, &path)) {
+            cx.tcx.dcx().warn(format!(
+                "could not write clippy's diagnostics to `{}`: {e}",
+                path.display()
+            ));
+        }
+
+        if cx.tcx.sess.opts.unstable_opts.incremental_info {
+            eprintln!(
+                "[incremental] clippy: {} items linted, {} items replayed",
+                units.count - units.replayed.len(),
+                units.replayed.len(),
+            );
+        }
+    }
+}
+
+/// Runs the passes registered with `register_late_mod_pass`, except on the units replayed by
+/// [`DiagnosticCache`], capturing the diagnostics of the other units
+struct ItemPasses<'tcx> {
+    passes: Vec<Box<dyn LateLintPass<'tcx> + 'tcx>>,
+    /// The owner of the node last visited
+    owner: Option<LocalDefId>,
+    /// Whether the unit of `owner` was replayed
+    replayed: bool,
+    /// The unit of `owner`, if its diagnostics are cached
+    capture: Option<LocalDefId>,
+}
+
+impl<'tcx> ItemPasses<'tcx> {
+    fn new<F>(tcx: TyCtxt<'tcx>, factories: &[F]) -> Self
+    where
+        F: Fn(TyCtxt<'tcx>) -> Box<dyn LateLintPass<'tcx> + 'tcx>,
+    {
+        // Like rustc does for the passes run over the whole crate
+        let not_needed = tcx.lints_that_dont_need_to_run(());
+        let passes = factories
+            .iter()
+            .map(|factory| factory(tcx))
+            .filter(|pass| {
+                let lints = pass.get_lints();
+                lints.is_empty() || !lints.iter().all(|lint| not_needed.contains(&LintId::of(lint)))
+            })
+            .collect();
+        Self {
+            passes,
+            owner: None,
+            replayed: false,
+            capture: None,
+        }
+    }
+
+    /// Whether the passes have to be run for the current node, sets the unit whose diagnostics are
+    /// captured if they do
+    fn enter(&mut self, cx: &LateContext<'tcx>) -> bool {
+        let owner = cx.last_node_with_lint_attrs.owner.def_id;
+        if self.owner != Some(owner) {
+            self.owner = Some(owner);
+            (self.replayed, self.capture) = UNITS.with_borrow(|units| {
+                let Some(units) = units else {
+                    return (false, None);
+                };
+                match units.of_owner.get(&owner) {
+                    Some(unit) if units.replayed.contains_key(unit) => (true, None),
+                    Some(unit) if units.keys.contains_key(unit) => (false, Some(*unit)),
+                    _ => (false, None),
+                }
+            });
+        }
+        CAPTURING.set(self.capture);
+        !self.replayed
+    }
+}
+
+// The lints are the ones of the wrapped passes
+#[allow(rustc::lint_pass_impl_without_macro)]
+impl LintPass for ItemPasses<'_> {
+    fn name(&self) -> &'static str {
+        "ItemPasses"
+    }
+
+    fn get_lints(&self) -> LintVec {
+        self.passes.iter().flat_map(|pass| pass.get_lints()).collect()
+    }
+}
+
+macro_rules! item_late_methods {
+    ([], [$($(#[$attr:meta])* fn $name:ident($($param:ident: $arg:ty),*);)*]) => {
+        $(fn $name(&mut self, cx: &LateContext<'tcx>, $($param: $arg),*) {
+            if self.enter(cx) {
+                for pass in &mut self.passes {
+                    pass.$name(cx, $($param),*);
+                }
+                CAPTURING.set(None);
+            }
+        })*
+    };
+}
+
+impl<'tcx> LateLintPass<'tcx> for ItemPasses<'tcx> {
+    rustc_lint::late_lint_methods!(item_late_methods, []);
+}
@@ -0,0 +1,294 @@
+//! Encoding of the diagnostics cached for a unit.
+//!
+//! The spans are encoded relative to the start of the unit, so the diagnostics are replayed at the
+//! right place when the unit moved in its file. Definitions are encoded with their `DefPathHash`.
+//! Whatever can't be encoded that way, like a span outside of the unit or in a macro expansion,
+//! makes the diagnostics of the unit uncacheable.
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_errors::{DiagInner, Level};
+use rustc_hir::def_id::{CRATE_DEF_ID, CRATE_DEF_INDEX, CrateNum, DefId, DefIndex, DefPathHash, LOCAL_CRATE};
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+use rustc_span::hygiene::{ExpnId, SyntaxContext};
+use rustc_span::{AttrId, BytePos, DUMMY_SP, Span, SpanDecoder, SpanEncoder, Symbol};
+use std::str;
+
+/// Written by `Encoder::emit_str` after the bytes of a string
+const STR_SENTINEL: u8 = 0xC1;
+
+/// Encodes the diagnostics emitted for the unit spanning `unit`, returns `None` if they can't be
+/// cached
+pub fn encode(tcx: TyCtxt<'_>, unit: Span, diags: &[DiagInner]) -> Option<Vec<u8>> {
+    let mut encoder = DiagEncoder {
+        tcx,
+        unit,
+        bytes: Vec::new(),
+        failed: false,
+    };
+    encoder.emit_usize(diags.len());
+    for diag in diags {
+        // The expectations of the early lint passes refer to attributes, which can't be encoded
+        if let Level::Expect(expectation) | Level::ForceWarning(Some(expectation)) = diag.level()
+            && !expectation.is_stable()
+        {
+            return None;
+        }
+        diag.encode(&mut encoder);
+    }
+    (!encoder.failed).then_some(encoder.bytes)
+}
+
+/// Decodes the diagnostics encoded by [`encode`] for the unit now spanning `unit`
+pub fn decode(tcx: TyCtxt<'_>, unit: Span, bytes: &[u8]) -> Option<Vec<DiagInner>> {
+    let mut decoder = DiagDecoder {
+        tcx,
+        crates: tcx
+            .crates(())
+            .iter()
+            .chain([&LOCAL_CRATE])
+            .map(|&krate| (tcx.stable_crate_id(krate).as_u64(), krate))
+            .collect(),
+        unit,
+        bytes,
+        position: 0,
+        zeros: Vec::new(),
+        failed: false,
+    };
+    let diags = (0..decoder.read_usize())
+        .map(|_| DiagInner::decode(&mut decoder))
+        .collect();
+    (!decoder.failed && decoder.position == bytes.len()).then_some(diags)
+}
+
+struct DiagEncoder<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    unit: Span,
+    bytes: Vec<u8>,
+    failed: bool,
+}
+
+macro_rules! emit_le_bytes {
+    ($($name:ident: $ty:ty),*) => {
+        $(fn $name(&mut self, value: $ty) {
+            self.bytes.extend_from_slice(&value.to_le_bytes());
+        })*
+    };
+}
+
+impl Encoder for DiagEncoder<'_> {
+    emit_le_bytes!(
+        emit_u128: u128,
+        emit_u64: u64,
+        emit_u32: u32,
+        emit_u16: u16,
+        emit_u8: u8,
+        emit_i128: i128,
+        emit_i64: i64,
+        emit_i32: i32,
+        emit_i16: i16
+    );
+
+    fn emit_usize(&mut self, value: usize) {
+        self.emit_u64(value as u64);
+    }
+
+    fn emit_isize(&mut self, value: isize) {
+        self.emit_i64(value as i64);
+    }
+
+    fn emit_raw_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+}
+
+impl SpanEncoder for DiagEncoder<'_> {
+    fn encode_span(&mut self, span: Span) {
+        if span.is_dummy() {
+            self.emit_u8(0);
+        } else if span.ctxt().is_root() && self.unit.contains(span) {
+            self.emit_u8(1);
+            self.emit_u32((span.lo() - self.unit.lo()).0);
+            self.emit_u32((span.hi() - self.unit.lo()).0);
+        } else {
+            self.failed = true;
+        }
+    }
+
+    fn encode_symbol(&mut self, symbol: Symbol) {
+        self.emit_str(symbol.as_str());
+    }
+
+    fn encode_expn_id(&mut self, _: ExpnId) {
+        self.failed = true;
+    }
+
+    fn encode_syntax_context(&mut self, _: SyntaxContext) {
+        self.failed = true;
+    }
+
+    fn encode_crate_num(&mut self, krate: CrateNum) {
+        self.emit_u64(self.tcx.stable_crate_id(krate).as_u64());
+    }
+
+    fn encode_def_index(&mut self, _: DefIndex) {
+        self.failed = true;
+    }
+
+    fn encode_def_id(&mut self, def_id: DefId) {
+        let hash = self.tcx.def_path_hash(def_id);
+        self.emit_raw_bytes(&hash.0.to_le_bytes());
+    }
+}
+
+struct DiagDecoder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The crates by their `StableCrateId`
+    crates: FxHashMap<u64, CrateNum>,
+    unit: Span,
+    bytes: &'a [u8],
+    position: usize,
+    /// Read instead of the bytes past the end
+    zeros: Vec<u8>,
+    /// Set when the bytes don't match the crate being compiled, the decoded values are then
+    /// meaningless
+    failed: bool,
+}
+
+impl DiagDecoder<'_, '_> {
+    fn read_array<const N: usize>(&mut self) -> [u8; N] {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_raw_bytes(N));
+        array
+    }
+}
+
+macro_rules! read_le_bytes {
+    ($($name:ident: $ty:ty),*) => {
+        $(fn $name(&mut self) -> $ty {
+            <$ty>::from_le_bytes(self.read_array())
+        })*
+    };
+}
+
+impl Decoder for DiagDecoder<'_, '_> {
+    read_le_bytes!(
+        read_u128: u128,
+        read_u64: u64,
+        read_u32: u32,
+        read_u16: u16,
+        read_u8: u8,
+        read_i128: i128,
+        read_i64: i64,
+        read_i32: i32,
+        read_i16: i16
+    );
+
+    fn read_usize(&mut self) -> usize {
+        usize::try_from(self.read_u64()).unwrap_or_else(|_| {
+            self.failed = true;
+            0
+        })
+    }
+
+    fn read_isize(&mut self) -> isize {
+        isize::try_from(self.read_i64()).unwrap_or_else(|_| {
+            self.failed = true;
+            0
+        })
+    }
+
+    fn read_raw_bytes(&mut self, len: usize) -> &[u8] {
+        if let Some(bytes) = self.bytes.get(self.position..self.position.saturating_add(len)) {
+            self.position += len;
+            bytes
+        } else {
+            self.failed = true;
+            self.position = self.bytes.len();
+            self.zeros.resize(len, 0);
+            &self.zeros
+        }
+    }
+
+    fn read_str(&mut self) -> &str {
+        let len = self.read_usize();
+        let bytes = self.bytes;
+        let start = self.position;
+        self.read_raw_bytes(len.saturating_add(1));
+        if let Some([str @ .., STR_SENTINEL]) = bytes.get(start..self.position)
+            && let Ok(str) = str::from_utf8(str)
+        {
+            str
+        } else {
+            self.failed = true;
+            ""
+        }
+    }
+
+    fn peek_byte(&self) -> u8 {
+        self.bytes.get(self.position).copied().unwrap_or_default()
+    }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl SpanDecoder for DiagDecoder<'_, '_> {
+    fn decode_span(&mut self) -> Span {
+        if self.read_u8() == 0 {
+            return DUMMY_SP;
+        }
+        let lo = self.unit.lo() + BytePos(self.read_u32());
+        let hi = self.unit.lo() + BytePos(self.read_u32());
+        if hi > self.unit.hi() {
+            self.failed = true;
+        }
+        self.unit.with_lo(lo).with_hi(hi)
+    }
+
+    fn decode_symbol(&mut self) -> Symbol {
+        Symbol::intern(self.read_str())
+    }
+
+    fn decode_expn_id(&mut self) -> ExpnId {
+        self.failed = true;
+        ExpnId::root()
+    }
+
+    fn decode_syntax_context(&mut self) -> SyntaxContext {
+        self.failed = true;
+        SyntaxContext::root()
+    }
+
+    fn decode_crate_num(&mut self) -> CrateNum {
+        let id = self.read_u64();
+        self.crates.get(&id).copied().unwrap_or_else(|| {
+            self.failed = true;
+            LOCAL_CRATE
+        })
+    }
+
+    fn decode_def_index(&mut self) -> DefIndex {
+        self.failed = true;
+        CRATE_DEF_INDEX
+    }
+
+    fn decode_def_id(&mut self) -> DefId {
+        let hash = DefPathHash(Fingerprint::from_le_bytes(self.read_array()));
+        if self.crates.contains_key(&hash.stable_crate_id().as_u64())
+            && let Some(def_id) = self.tcx.def_path_hash_to_def_id(hash)
+        {
+            def_id
+        } else {
+            self.failed = true;
+            CRATE_DEF_ID.to_def_id()
+        }
+    }
+
+    fn decode_attr_id(&mut self) -> AttrId {
+        self.failed = true;
+        AttrId::from_u32(0)
+    }
+}
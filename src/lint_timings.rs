@@ -0,0 +1,133 @@
+//! `cargo clippy --profile-lints` runs clippy with `CLIPPY_PROFILE` enabled on every crate of the
+//! workspace, and prints the time spent in the slowest lint passes over all of them.
+//!
+//! Each `clippy-driver` writes its timings to a file in `CLIPPY_PROFILE_DIR`, see
+//! `src/profile.rs`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::process::{self, Command};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+/// The number of passes printed
+const SLOWEST_PASSES: usize = 20;
+
+#[derive(Debug, Default, PartialEq)]
+struct PassTimings {
+    total: f64,
+    hooks: BTreeMap<String, f64>,
+}
+
+/// The timings of the passes summed over all crates
+#[derive(Default)]
+struct Report {
+    crates: usize,
+    passes: BTreeMap<String, PassTimings>,
+}
+
+pub fn run(mut cmd: Command) -> Result<(), i32> {
+    let dir = env::temp_dir().join(format!("clippy-profile-{}", process::id()));
+    fs::create_dir_all(&dir).expect("could not create the directory of the timings");
+
+    // A new value makes cargo run clippy again on the crates which were checked before
+    let run_id = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    let status = cmd
+        .env("CLIPPY_PROFILE", run_id.to_string())
+        .env("CLIPPY_PROFILE_DIR", &dir)
+        .status()
+        .expect("could not run cargo");
+
+    let mut report = Report::default();
+    for entry in fs::read_dir(&dir).expect("could not read the timings") {
+        report.add(&fs::read_to_string(entry.unwrap().path()).expect("could not read the timings"));
+    }
+    let _ = fs::remove_dir_all(&dir);
+
+    eprint!("{}", report.table());
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(status.code().unwrap_or(-1))
+    }
+}
+
+impl Report {
+    /// Adds the timings a `clippy-driver` wrote, see `src/profile.rs`
+    fn add(&mut self, contents: &str) {
+        let Ok(timings) = serde_json::from_str::<serde_json::Value>(contents) else {
+            return;
+        };
+        self.crates += 1;
+        for (pass, seconds) in timings["passes"].as_object().into_iter().flatten() {
+            let pass_timings = self.passes.entry(pass.clone()).or_default();
+            pass_timings.total += seconds.as_f64().unwrap_or_default();
+            for (hook, seconds) in timings["hooks"][pass].as_object().into_iter().flatten() {
+                *pass_timings.hooks.entry(hook.clone()).or_default() += seconds.as_f64().unwrap_or_default();
+            }
+        }
+    }
+
+    /// The total time, followed by the table of the slowest passes
+    fn table(&self) -> String {
+        let total: f64 = self.passes.values().map(|pass| pass.total).sum();
+        let mut table = format!(
+            "\nTime spent in lint passes: {total:.3}s in {} crate(s)\n\n",
+            self.crates
+        );
+        if self.passes.is_empty() {
+            return table;
+        }
+
+        let mut slowest: Vec<_> = self.passes.iter().collect();
+        slowest.sort_by(|(_, a), (_, b)| b.total.total_cmp(&a.total));
+        writeln!(table, "{:<40} {:>10}  Slowest method", "Pass", "Time").unwrap();
+        for (pass, timings) in slowest.into_iter().take(SLOWEST_PASSES) {
+            let slowest_hook = timings
+                .hooks
+                .iter()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map_or(String::new(), |(hook, seconds)| format!("{hook} ({seconds:.3}s)"));
+            writeln!(table, "{pass:<40} {:>9.3}s  {slowest_hook}", timings.total).unwrap();
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_the_crates() {
+        let mut report = Report::default();
+        report.add(r#"{"$message_type":"clippy_profile","crate_name":"a","hooks":{"Casts":{"check_expr":0.5}},"passes":{"Casts":0.5}}"#);
+        report.add(
+            r#"{"$message_type":"clippy_profile","crate_name":"b","hooks":{"Casts":{"check_expr":0.25,"check_item":1.0},"Loops":{"check_expr":0.125}},"passes":{"Casts":1.25,"Loops":0.125}}"#,
+        );
+        // Not written by `clippy-driver`
+        report.add("");
+
+        assert_eq!(report.crates, 2);
+        assert_eq!(report.passes["Casts"], PassTimings {
+            total: 1.75,
+            hooks: BTreeMap::from([("check_expr".into(), 0.75), ("check_item".into(), 1.0)]),
+        });
+        assert_eq!(report.passes["Loops"], PassTimings {
+            total: 0.125,
+            hooks: BTreeMap::from([("check_expr".into(), 0.125)]),
+        });
+
+        assert_eq!(
+            report.table(),
+            "
+Time spent in lint passes: 1.875s in 2 crate(s)
+
+Pass                                           Time  Slowest method
+Casts                                        1.750s  check_item (1.000s)
+Loops                                        0.125s  check_expr (0.125s)
+"
+        );
+    }
+}
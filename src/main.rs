@@ -1,6 +1,7 @@
 // We need this feature as it changes `dylib` linking behavior and allows us to link to
 // `rustc_driver`.
 #![feature(rustc_private)]
+#![feature(let_chains)]
 // warn on lints, that are included in `rust-lang/rust`s bootstrap
 #![warn(rust_2018_idioms, unused_lifetimes)]
 
@@ -10,6 +11,9 @@ use std::process::{self, Command};
 
 use anstream::println;
 
+mod fix;
+mod lint_timings;
+
 #[allow(clippy::ignored_unit_patterns)]
 fn show_help() {
     println!("{}", help_message());
@@ -50,31 +54,92 @@ pub fn main() {
     }
 }
 
+#[derive(Clone)]
 struct ClippyCmd {
     cargo_subcommand: &'static str,
     args: Vec<String>,
     clippy_args: Vec<String>,
+    /// The revision passed to `--diff-base`
+    diff_base: Option<String>,
+    fix_dry_run: bool,
+    fix_unsafe_verified: bool,
+    /// The clippy lints passed to `--fix-only`, without the `clippy::` prefix
+    fix_only: Vec<String>,
+    profile_lints: bool,
 }
 
 impl ClippyCmd {
-    fn new<I>(mut old_args: I) -> Self
+    /// Parses the arguments of `cargo clippy`, returns a usage error if they are invalid
+    fn new<I>(mut old_args: I) -> Result<Self, String>
     where
         I: Iterator<Item = String>,
     {
         let mut cargo_subcommand = "check";
         let mut args = vec![];
         let mut clippy_args: Vec<String> = vec![];
+        let mut diff_base = None;
+        let mut fix_dry_run = false;
+        let mut fix_unsafe_verified = false;
+        let mut fix_only = vec![];
+        let mut profile_lints = false;
 
-        for arg in old_args.by_ref() {
+        while let Some(arg) = old_args.next() {
             match arg.as_str() {
                 "--fix" => {
                     cargo_subcommand = "fix";
                     continue;
                 },
+                "--fix-dry-run" => {
+                    fix_dry_run = true;
+                    continue;
+                },
+                "--fix-unsafe-verified" => {
+                    fix_unsafe_verified = true;
+                    continue;
+                },
+                _ if arg.starts_with("--fix-only=") => {
+                    fix_only.extend(
+                        arg["--fix-only=".len()..]
+                            .split(',')
+                            .filter(|lint| !lint.is_empty())
+                            .map(|lint| {
+                                let lint = lint.to_ascii_lowercase().replace('-', "_");
+                                lint.strip_prefix("clippy::").map_or(lint.clone(), ToString::to_string)
+                            }),
+                    );
+                    continue;
+                },
                 "--no-deps" => {
                     clippy_args.push("--no-deps".into());
                     continue;
                 },
+                "--report-unused-allows" => {
+                    clippy_args.push("-Wclippy::unused_clippy_allows".into());
+                    continue;
+                },
+                "--diff-base" => {
+                    diff_base = old_args.next();
+                    if diff_base.as_ref().is_none_or(|base| base.starts_with('-')) {
+                        return Err("`--diff-base` requires a revision, e.g. `--diff-base origin/main`".into());
+                    }
+                    continue;
+                },
+                "--diff-items" => {
+                    clippy_args.push("--diff-items".into());
+                    continue;
+                },
+                "--profile-lints" => {
+                    profile_lints = true;
+                    continue;
+                },
+                _ if arg.starts_with("--diff-base=") => {
+                    let base = &arg["--diff-base=".len()..];
+                    if base.is_empty() {
+                        return Err("`--diff-base` requires a revision, e.g. `--diff-base=origin/main`".into());
+                    }
+                    diff_base = Some(base.to_string());
+                    continue;
+                },
                 "--" => break,
                 _ => {},
             }
@@ -83,15 +148,60 @@ impl ClippyCmd {
         }
 
         clippy_args.append(&mut (old_args.collect()));
-        if cargo_subcommand == "fix" && !clippy_args.iter().any(|arg| arg == "--no-deps") {
+        if fix_dry_run || fix_unsafe_verified {
+            // Like `cargo fix`, check all targets unless some are selected
+            if !args.iter().any(|arg| is_target_selection(arg)) {
+                args.push("--all-targets".into());
+            }
+            args.push("--message-format=json".into());
+            if fix_unsafe_verified {
+                // The denied lints must not be counted as errors introduced by the suggestions
+                clippy_args.push("--cap-lints=warn".into());
+            }
+        } else if !fix_only.is_empty() {
+            cargo_subcommand = "fix";
+            clippy_args.push(format!("--fix-only={}", fix_only.join(",")));
+        }
+        if (cargo_subcommand == "fix" || fix_dry_run || fix_unsafe_verified)
+            && !clippy_args.iter().any(|arg| arg == "--no-deps")
+        {
             clippy_args.push("--no-deps".into());
         }
 
-        Self {
+        Ok(Self {
             cargo_subcommand,
             args,
             clippy_args,
+            diff_base,
+            fix_dry_run,
+            fix_unsafe_verified,
+            fix_only,
+            profile_lints,
+        })
+    }
+
+    /// Passes the commit `--diff-base` refers to on to the driver, so that cargo lints the crates
+    /// again when the revision is moved to another commit
+    fn resolve_diff_base(&mut self) -> Result<(), i32> {
+        let Some(base) = &self.diff_base else {
+            return Ok(());
+        };
+        let output = Command::new("git")
+            .args([
+                "rev-parse",
+                "--verify",
+                "--end-of-options",
+                &format!("{base}^{{commit}}"),
+            ])
+            .stderr(process::Stdio::inherit())
+            .output()
+            .expect("could not run git");
+        if !output.status.success() {
+            return Err(output.status.code().unwrap_or(-1));
         }
+        let commit = String::from_utf8_lossy(&output.stdout);
+        self.clippy_args.push(format!("--diff-base={}", commit.trim_end()));
+        Ok(())
     }
 
     fn path() -> PathBuf {
@@ -130,7 +240,25 @@ fn process<I>(old_args: I) -> Result<(), i32>
 where
     I: Iterator<Item = String>,
 {
-    let cmd = ClippyCmd::new(old_args);
+    let mut cmd = ClippyCmd::new(old_args).map_err(|message| {
+        eprintln!("error: {message}");
+        1
+    })?;
+    cmd.resolve_diff_base()?;
+
+    let manifest_path = arg_value(&cmd.args, "--manifest-path").map(ToString::to_string);
+    if cmd.fix_dry_run {
+        let fix_only = std::mem::take(&mut cmd.fix_only);
+        return fix::dry_run(cmd.into_std_cmd(), manifest_path.as_deref(), &fix_only);
+    }
+    if cmd.fix_unsafe_verified {
+        let fix_only = std::mem::take(&mut cmd.fix_only);
+        return fix::apply_verified(|| cmd.clone().into_std_cmd(), manifest_path.as_deref(), &fix_only);
+    }
+
+    if cmd.profile_lints {
+        return lint_timings::run(cmd.into_std_cmd());
+    }
 
     let mut cmd = cmd.into_std_cmd();
 
@@ -147,6 +275,36 @@ where
     }
 }
 
+fn is_target_selection(arg: &str) -> bool {
+    [
+        "--lib",
+        "--bin",
+        "--bins",
+        "--example",
+        "--examples",
+        "--test",
+        "--tests",
+        "--bench",
+        "--benches",
+        "--all-targets",
+    ]
+    .iter()
+    .any(|target| arg == *target || arg.strip_prefix(target).is_some_and(|rest| rest.starts_with('=')))
+}
+
+/// Returns the value of the cargo option `name`, passed either as `name value` or `name=value`
+fn arg_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let mut args = args.iter().map(String::as_str);
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        } else if let Some(value) = arg.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+            return Some(value);
+        }
+    }
+    None
+}
+
 #[must_use]
 pub fn help_message() -> &'static str {
     color_print::cstr!(
@@ -158,9 +316,18 @@ pub fn help_message() -> &'static str {
 <green,bold>Common options:</>
     <cyan,bold>--no-deps</>                Run Clippy only on the given crate, without linting the dependencies
     <cyan,bold>--fix</>                    Automatically apply lint suggestions. This flag implies <cyan>--no-deps</> and <cyan>--all-targets</>
+    <cyan,bold>--fix-only</>=<cyan><<LINTS>></>       Only apply the suggestions of the given comma separated Clippy lints. Implies <cyan>--fix</>
+    <cyan,bold>--fix-dry-run</>            Print the edits of all suggestions as a unified diff instead of applying them
+    <cyan,bold>--fix-unsafe-verified</>    Also apply the suggestions which may be incorrect, reverting those of the lints which
+                             introduce errors
+    <cyan,bold>--report-unused-allows</>   Report <yellow>#[allow(clippy::..)]</> attributes which don't suppress any lint
+    <cyan,bold>--diff-base</> <cyan><<REV>></>        Only report the lints on the lines changed since the git revision <cyan><<REV>></>
+    <cyan,bold>--diff-items</>             With <cyan>--diff-base</>, also report the lints in the items containing a changed line
+    <cyan,bold>--profile-lints</>          Print the time spent in the slowest lint passes over all the checked crates
     <cyan,bold>-h</>, <cyan,bold>--help</>               Print this message
     <cyan,bold>-V</>, <cyan,bold>--version</>            Print version info and exit
-    <cyan,bold>--explain [LINT]</>         Print the documentation for a given lint
+    <cyan,bold>--explain [LINT]</>         Print the documentation for a given lint, and its configuration and level in the
+                             package in the current directory
 
 See all options with <cyan,bold>cargo check --help</>.
 
@@ -191,7 +358,7 @@ mod tests {
     #[test]
     fn fix() {
         let args = "cargo clippy --fix".split_whitespace().map(ToString::to_string);
-        let cmd = ClippyCmd::new(args);
+        let cmd = ClippyCmd::new(args).unwrap();
         assert_eq!("fix", cmd.cargo_subcommand);
         assert!(!cmd.args.iter().any(|arg| arg.ends_with("unstable-options")));
     }
@@ -199,7 +366,7 @@ mod tests {
     #[test]
     fn fix_implies_no_deps() {
         let args = "cargo clippy --fix".split_whitespace().map(ToString::to_string);
-        let cmd = ClippyCmd::new(args);
+        let cmd = ClippyCmd::new(args).unwrap();
         assert!(cmd.clippy_args.iter().any(|arg| arg == "--no-deps"));
     }
 
@@ -208,14 +375,120 @@ mod tests {
         let args = "cargo clippy --fix -- --no-deps"
             .split_whitespace()
             .map(ToString::to_string);
-        let cmd = ClippyCmd::new(args);
+        let cmd = ClippyCmd::new(args).unwrap();
         assert_eq!(cmd.clippy_args.iter().filter(|arg| *arg == "--no-deps").count(), 1);
     }
 
+    #[test]
+    fn report_unused_allows() {
+        let args = "cargo clippy --report-unused-allows -- -D warnings"
+            .split_whitespace()
+            .skip(2)
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(!cmd.args.iter().any(|arg| arg == "--report-unused-allows"));
+        assert_eq!(cmd.clippy_args, ["-Wclippy::unused_clippy_allows", "-D", "warnings"]);
+    }
+
+    #[test]
+    fn diff_base() {
+        let args = "cargo clippy --diff-base origin/main --diff-items -- -D warnings"
+            .split_whitespace()
+            .skip(2)
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert_eq!(cmd.diff_base.as_deref(), Some("origin/main"));
+        assert!(
+            cmd.args
+                .iter()
+                .all(|arg| !arg.starts_with("--diff") && arg != "origin/main")
+        );
+        assert_eq!(cmd.clippy_args, ["--diff-items", "-D", "warnings"]);
+
+        let args = "cargo clippy --diff-base=HEAD~1"
+            .split_whitespace()
+            .skip(2)
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert_eq!(cmd.diff_base.as_deref(), Some("HEAD~1"));
+        assert!(cmd.clippy_args.is_empty());
+
+        for args in [
+            "--diff-base",
+            "--diff-base --fix",
+            "--diff-base=",
+            "--diff-base -- -D warnings",
+        ] {
+            let args = args.split_whitespace().map(ToString::to_string);
+            assert!(ClippyCmd::new(args).is_err());
+        }
+    }
+
+    #[test]
+    fn fix_only() {
+        let args = "cargo clippy --fix-only=needless_return,clippy::Let-And-Return"
+            .split_whitespace()
+            .skip(2)
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert_eq!("fix", cmd.cargo_subcommand);
+        assert_eq!(cmd.fix_only, ["needless_return", "let_and_return"]);
+        assert_eq!(cmd.clippy_args, [
+            "--fix-only=needless_return,let_and_return",
+            "--no-deps"
+        ]);
+    }
+
+    #[test]
+    fn fix_dry_run() {
+        let args = "cargo clippy --fix-dry-run --fix-only=needless_return"
+            .split_whitespace()
+            .skip(2)
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(cmd.fix_dry_run);
+        assert_eq!(cmd.args, ["--all-targets", "--message-format=json"]);
+        assert_eq!(cmd.clippy_args, ["--no-deps"]);
+
+        let args = "cargo clippy --fix-dry-run --lib"
+            .split_whitespace()
+            .skip(2)
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert_eq!(cmd.args, ["--lib", "--message-format=json"]);
+    }
+
+    #[test]
+    fn fix_unsafe_verified() {
+        let args = "cargo clippy --fix-unsafe-verified --fix-only=needless_return"
+            .split_whitespace()
+            .skip(2)
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert_eq!("check", cmd.cargo_subcommand);
+        assert!(cmd.fix_unsafe_verified);
+        assert_eq!(cmd.fix_only, ["needless_return"]);
+        assert_eq!(cmd.args, ["--all-targets", "--message-format=json"]);
+        assert_eq!(cmd.clippy_args, ["--cap-lints=warn", "--no-deps"]);
+    }
+
+    #[test]
+    fn profile_lints() {
+        let args = "cargo clippy --profile-lints -p foo"
+            .split_whitespace()
+            .skip(2)
+            .map(ToString::to_string);
+        let cmd = ClippyCmd::new(args).unwrap();
+        assert!(cmd.profile_lints);
+        assert_eq!(cmd.args, ["-p", "foo"]);
+    }
+
     #[test]
     fn check() {
         let args = "cargo clippy".split_whitespace().map(ToString::to_string);
-        let cmd = ClippyCmd::new(args);
+        let cmd = ClippyCmd::new(args).unwrap();
         assert_eq!("check", cmd.cargo_subcommand);
     }
 }
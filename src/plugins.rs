@@ -0,0 +1,75 @@
+//! Registration of the plugins, the lints maintained outside of clippy. See `clippy_utils::plugin`
+//! for how they are declared.
+//!
+//! A companion crate is linked into `clippy-driver` by adding it to the dependencies of clippy and
+//! its plugin to [`LINKED`].
+
+use clippy_utils::plugin::{BUILD_ID_SYMBOL, BuildIdFn, ENTRY_POINT, EntryPoint, Host, Plugin, build_id};
+use rustc_lint::LintStore;
+use rustc_metadata::DylibError;
+use rustc_session::Session;
+use std::env;
+use std::ffi::{CStr, OsStr, OsString};
+use std::path::Path;
+
+/// The plugins linked into `clippy-driver`, which are registered whether `CLIPPY_PLUGINS` is set
+/// or not.
+const LINKED: &[&Plugin] = &[];
+
+/// Returns the paths of the dylib plugins to load, separated like `PATH`.
+pub fn dylib_paths() -> Option<OsString> {
+    env::var_os("CLIPPY_PLUGINS").filter(|paths| !paths.is_empty())
+}
+
+/// Registers the lints of the [`LINKED`] plugins and of the dylibs listed in `CLIPPY_PLUGINS`.
+pub fn register(sess: &Session, store: &mut LintStore) {
+    for plugin in LINKED {
+        (plugin.register)(store);
+    }
+    if let Some(paths) = dylib_paths() {
+        register_dylibs(sess, store, &paths);
+    }
+}
+
+/// Loads the dylib plugins at `paths`, separated like `PATH`, and registers their lints.
+fn register_dylibs(sess: &Session, store: &mut LintStore, paths: &OsStr) {
+    let host = Host::current();
+    for path in env::split_paths(paths).filter(|path| !path.as_os_str().is_empty()) {
+        if !path.is_file() {
+            sess.dcx()
+                .fatal(format!("could not find the clippy plugin `{}`", path.display()));
+        }
+
+        // SAFETY: the symbol is declared with this type by `export_clippy_plugin!`, and uses the C
+        // ABI so it can be called whatever the plugin was compiled with
+        let plugin_build_id = unsafe { CStr::from_ptr(load_symbol::<BuildIdFn>(sess, &path, BUILD_ID_SYMBOL)()) };
+        if plugin_build_id != build_id() {
+            sess.dcx().fatal(format!(
+                "the clippy plugin `{}` was compiled with {}, but clippy-driver with {}, the plugin has to be compiled again",
+                path.display(),
+                plugin_build_id.to_string_lossy(),
+                build_id().to_string_lossy(),
+            ));
+        }
+
+        // SAFETY: the plugin was compiled with the same toolchain and `clippy_utils` as this
+        // `clippy-driver`, which declares the entry point with this type in `export_clippy_plugin!`
+        let plugin = unsafe { load_symbol::<EntryPoint>(sess, &path, ENTRY_POINT) }(&host);
+        (plugin.register)(store);
+    }
+}
+
+/// Loads the symbol `name` of the dylib at `path`.
+///
+/// # Safety
+///
+/// The symbol must have the type `T`.
+unsafe fn load_symbol<T: Copy>(sess: &Session, path: &Path, name: &str) -> T {
+    match unsafe { rustc_metadata::load_symbol_from_dylib::<T>(path, name) } {
+        Ok(symbol) => symbol,
+        Err(DylibError::DlOpen(path, err) | DylibError::DlSym(path, err)) => {
+            sess.dcx()
+                .fatal(format!("could not load the clippy plugin `{path}`: {err}"));
+        },
+    }
+}
@@ -0,0 +1,210 @@
+//! `CLIPPY_PROFILE=1`, or any other value but `0`, measures the time spent in each lint pass, as
+//! does the `-Zclippy-profile` flag. Once the compilation is done the timings are printed to stderr
+//! as a single JSON line, with the total of each pass and of each of its `check_*` methods, e.g.
+//!
+//! ```json
+//! {"$message_type":"clippy_profile","crate_name":"foo",
+//!  "hooks":{"Attributes":{"check_attribute":0.0009,"check_item":0.0003}},"passes":{"Attributes":0.0012}}
+//! ```
+//!
+//! When `CLIPPY_PROFILE_DIR` is set, the line is written to a file in that directory instead, which
+//! is how `cargo clippy --profile-lints` collects the timings of the whole workspace. This is also
+//! used by `cargo lintcheck --perf` to find the lints which got slower.
+
+use rustc_lint::{EarlyContext, EarlyLintPass, LateContext, LateLintPass, LintPass, LintStore, LintVec};
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use std::{env, fs, mem, process};
+
+/// The time spent in a pass
+#[derive(Default)]
+struct PassTimings {
+    total: Duration,
+    /// The time spent in each `check_*` method of the pass, by its name
+    hooks: BTreeMap<&'static str, Duration>,
+}
+
+/// The timings of each pass, by the name of the pass
+static TIMINGS: Mutex<BTreeMap<&'static str, PassTimings>> = Mutex::new(BTreeMap::new());
+
+/// Set by `-Zclippy-profile`
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) || env::var("CLIPPY_PROFILE").is_ok_and(|value| value != "0")
+}
+
+struct Timed<P> {
+    pass: P,
+    name: &'static str,
+    timings: PassTimings,
+}
+
+impl<P: Deref<Target: LintPass>> Timed<P> {
+    fn new(pass: P) -> Self {
+        Self {
+            name: pass.name(),
+            pass,
+            timings: PassTimings::default(),
+        }
+    }
+
+    fn record(&mut self, hook: &'static str, elapsed: Duration) {
+        self.timings.total += elapsed;
+        *self.timings.hooks.entry(hook).or_default() += elapsed;
+    }
+}
+
+// The name and lints are the ones of the wrapped pass
+#[allow(rustc::lint_pass_impl_without_macro)]
+impl<P: Deref<Target: LintPass>> LintPass for Timed<P> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn get_lints(&self) -> LintVec {
+        self.pass.get_lints()
+    }
+}
+
+// The passes are dropped once they have run over the whole crate, or module for module passes
+impl<P> Drop for Timed<P> {
+    fn drop(&mut self) {
+        let mut timings = TIMINGS.lock().unwrap();
+        let pass = timings.entry(self.name).or_default();
+        pass.total += self.timings.total;
+        for (&hook, &elapsed) in &self.timings.hooks {
+            *pass.hooks.entry(hook).or_default() += elapsed;
+        }
+    }
+}
+
+macro_rules! timed_late_methods {
+    ([], [$($(#[$attr:meta])* fn $name:ident($($param:ident: $arg:ty),*);)*]) => {
+        $(fn $name(&mut self, cx: &LateContext<'tcx>, $($param: $arg),*) {
+            let start = Instant::now();
+            self.pass.$name(cx, $($param),*);
+            self.record(stringify!($name), start.elapsed());
+        })*
+    };
+}
+
+macro_rules! timed_early_methods {
+    ([], [$($(#[$attr:meta])* fn $name:ident($($param:ident: $arg:ty),*);)*]) => {
+        $(fn $name(&mut self, cx: &EarlyContext<'_>, $($param: $arg),*) {
+            let start = Instant::now();
+            self.pass.$name(cx, $($param),*);
+            self.record(stringify!($name), start.elapsed());
+        })*
+    };
+}
+
+impl<'tcx> LateLintPass<'tcx> for Timed<Box<dyn LateLintPass<'tcx> + 'tcx>> {
+    rustc_lint::late_lint_methods!(timed_late_methods, []);
+}
+
+impl EarlyLintPass for Timed<Box<dyn EarlyLintPass>> {
+    rustc_lint::early_lint_methods!(timed_early_methods, []);
+}
+
+/// Wraps all the registered lint passes to measure the time spent in them
+pub fn wrap_passes(store: &mut LintStore) {
+    for factory in mem::take(&mut store.pre_expansion_passes) {
+        store
+            .pre_expansion_passes
+            .push(Box::new(move || Box::new(Timed::new(factory()))));
+    }
+    for factory in mem::take(&mut store.early_passes) {
+        store
+            .early_passes
+            .push(Box::new(move || Box::new(Timed::new(factory()))));
+    }
+    for factory in mem::take(&mut store.late_passes) {
+        store
+            .late_passes
+            .push(Box::new(move |tcx| Box::new(Timed::new(factory(tcx)))));
+    }
+    for factory in mem::take(&mut store.late_module_passes) {
+        store
+            .late_module_passes
+            .push(Box::new(move |tcx| Box::new(Timed::new(factory(tcx)))));
+    }
+}
+
+/// Prints the timings of the passes which have run, or writes them to `CLIPPY_PROFILE_DIR`
+pub fn print(crate_name: &str) {
+    let timings = TIMINGS.lock().unwrap();
+    if timings.is_empty() {
+        return;
+    }
+    let line = to_json(crate_name, &timings);
+
+    if let Some(dir) = env::var_os("CLIPPY_PROFILE_DIR") {
+        // A crate may be compiled more than once, e.g. as a library and for its tests
+        let path = Path::new(&dir).join(format!("{crate_name}-{}.json", process::id()));
+        if let Err(e) = fs::write(&path, line) {
+            eprintln!("error: could not write the timings to `{}`: {e}", path.display());
+        }
+    } else {
+        eprintln!("{line}");
+    }
+}
+
+/// The JSON line of the timings, see the module documentation
+fn to_json(crate_name: &str, timings: &BTreeMap<&'static str, PassTimings>) -> String {
+    let passes: BTreeMap<_, _> = timings
+        .iter()
+        .map(|(&name, timings)| (name, timings.total.as_secs_f64()))
+        .collect();
+    let hooks: BTreeMap<_, BTreeMap<_, _>> = timings
+        .iter()
+        .map(|(&name, timings)| {
+            let hooks = timings
+                .hooks
+                .iter()
+                .map(|(&hook, elapsed)| (hook, elapsed.as_secs_f64()))
+                .collect();
+            (name, hooks)
+        })
+        .collect();
+    serde_json::json!({
+        "$message_type": "clippy_profile",
+        "crate_name": crate_name,
+        "passes": passes,
+        "hooks": hooks,
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let mut timings = BTreeMap::new();
+        timings.insert("Attributes", PassTimings {
+            total: Duration::from_millis(3),
+            hooks: BTreeMap::from([
+                ("check_attribute", Duration::from_millis(2)),
+                ("check_item", Duration::from_millis(1)),
+            ]),
+        });
+        timings.insert("Casts", PassTimings {
+            total: Duration::from_millis(500),
+            hooks: BTreeMap::from([("check_expr", Duration::from_millis(500))]),
+        });
+        assert_eq!(
+            to_json("foo\"bar", &timings),
+            r#"{"$message_type":"clippy_profile","crate_name":"foo\"bar","hooks":{"Attributes":{"check_attribute":0.002,"check_item":0.001},"Casts":{"check_expr":0.5}},"passes":{"Attributes":0.003,"Casts":0.5}}"#
+        );
+    }
+}
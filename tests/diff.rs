@@ -0,0 +1,115 @@
+//! Runs `cargo clippy --diff-base` on a git repository, see `clippy_utils/src/diff.rs`
+
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
+use test_utils::{CARGO_CLIPPY_PATH, IS_RUSTC_TEST_SUITE};
+
+mod test_utils;
+
+const LIB: &str = "\
+pub mod other;
+
+pub fn unchanged() -> u32 {
+    return 1;
+}
+
+pub fn edited(x: bool) -> bool {
+    let y = x == true;
+    y && x == true
+}
+";
+
+/// Creates a git repository with a package `name` committed, outside of the clippy repository so
+/// its `clippy.toml` isn't found
+fn repository(name: &str) -> PathBuf {
+    let dir = env::temp_dir()
+        .join(format!("clippy_diff_test_{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"diff\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("src/lib.rs"), LIB).unwrap();
+    fs::write(dir.join("src/other.rs"), "").unwrap();
+    fs::write(dir.join(".gitignore"), "/target\n").unwrap();
+    git(&dir, &["init", "--quiet"]);
+    commit(&dir, "base");
+    dir
+}
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git").current_dir(dir).args(args).status().unwrap();
+    assert!(status.success());
+}
+
+fn commit(dir: &Path, message: &str) {
+    git(dir, &["add", "."]);
+    git(dir, &[
+        "-c",
+        "user.name=clippy",
+        "-c",
+        "user.email=clippy@localhost",
+        "commit",
+        "--quiet",
+        "-m",
+        message,
+    ]);
+}
+
+/// Runs `cargo clippy` and returns its stderr
+fn cargo_clippy(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(&*CARGO_CLIPPY_PATH)
+        .current_dir(dir)
+        .env("CARGO_INCREMENTAL", "0")
+        .env_remove("CARGO_TARGET_DIR")
+        .env_remove("CARGO_MANIFEST_DIR")
+        .env_remove("CLIPPY_CONF_DIR")
+        .arg("clippy")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn only_changed_lines() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let dir = repository("changed_lines");
+    fs::write(dir.join("src/lib.rs"), LIB.replace("y && x == true", "y || x == false")).unwrap();
+    // Untracked files are changed entirely
+    fs::write(dir.join("src/other.rs"), "pub mod new;\n").unwrap();
+    fs::create_dir(dir.join("src/other")).unwrap();
+    fs::write(
+        dir.join("src/other/new.rs"),
+        "pub fn new() -> u32 {\n    return 1;\n}\n",
+    )
+    .unwrap();
+
+    let stderr = cargo_clippy(&dir, &["--diff-base", "HEAD"]);
+    assert!(stderr.contains("src/lib.rs:9:10"), "{stderr}");
+    assert!(stderr.contains("equality checks against false"), "{stderr}");
+    assert!(stderr.contains("src/other/new.rs:2:5"), "{stderr}");
+    assert!(!stderr.contains("src/lib.rs:4:5"), "{stderr}");
+    assert!(!stderr.contains("equality checks against true"), "{stderr}");
+
+    // The lints in the items containing a changed line are reported as well
+    let stderr = cargo_clippy(&dir, &["--diff-base", "HEAD", "--diff-items"]);
+    assert!(stderr.contains("src/lib.rs:8:13"), "{stderr}");
+    assert!(stderr.contains("equality checks against true"), "{stderr}");
+    assert!(!stderr.contains("src/lib.rs:4:5"), "{stderr}");
+
+    // Without changes, nothing is reported
+    commit(&dir, "edit");
+    let stderr = cargo_clippy(&dir, &["--diff-base", "HEAD"]);
+    assert!(!stderr.contains("warning"), "{stderr}");
+    fs::remove_dir_all(dir).unwrap();
+}
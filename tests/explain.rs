@@ -0,0 +1,93 @@
+//! Checks the state of the lint printed by `cargo clippy --explain` in a package
+
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
+use test_utils::{CARGO_CLIPPY_PATH, IS_RUSTC_TEST_SUITE};
+
+mod test_utils;
+
+/// Creates a package `name` outside of the clippy repository, so its `clippy.toml` isn't found
+fn package(name: &str, clippy_toml: Option<&str>) -> PathBuf {
+    let dir = env::temp_dir()
+        .join(format!("clippy_explain_test_{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"explained\"\nversion = \"0.1.0\"\n\n[lints.clippy]\ntoo_many_arguments = \"deny\"\n",
+    )
+    .unwrap();
+    if let Some(clippy_toml) = clippy_toml {
+        fs::write(dir.join("clippy.toml"), clippy_toml).unwrap();
+    }
+    dir
+}
+
+fn explain(dir: &Path, lint: &str) -> String {
+    let output = Command::new(&*CARGO_CLIPPY_PATH)
+        .current_dir(dir)
+        .env_remove("CARGO_MANIFEST_DIR")
+        .env_remove("CLIPPY_CONF_DIR")
+        .args(["clippy", "--explain", lint])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn explain_with_clippy_toml() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let dir = package("with_clippy_toml", Some("too-many-arguments-threshold = 3\n"));
+    let stdout = explain(&dir, "too_many_arguments");
+    let clippy_toml = dir.join("clippy.toml").canonicalize().unwrap();
+    assert!(
+        stdout.contains(&format!("(set to `3` in `{}`)", clippy_toml.display())),
+        "{stdout}"
+    );
+    assert!(stdout.contains("### Status in package `explained`:"), "{stdout}");
+    assert!(
+        stdout.contains("- level: `deny`, set for `too_many_arguments` in the `[lints.clippy]` table of"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("- the lint is enabled"), "{stdout}");
+
+    let stdout = explain(&dir, "needless_pass_by_ref_mut");
+    assert!(
+        stdout.contains(&format!(
+            "(the default value `true` is used, it is not set in `{}`)",
+            clippy_toml.display()
+        )),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("- level: `allow`, the default level of the lint"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("- the lint is not enabled"), "{stdout}");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn explain_without_clippy_toml() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let dir = package("without_clippy_toml", None);
+    let stdout = explain(&dir, "too_many_arguments");
+    assert!(
+        stdout.contains("(the default value `7` is used, no `clippy.toml` was found)"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("- level: `deny`, set for `too_many_arguments` in the `[lints.clippy]` table of"),
+        "{stdout}"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,123 @@
+//! Runs `cargo clippy --fix-dry-run` and `cargo clippy --fix-unsafe-verified` on packages, see
+//! `src/fix.rs`
+
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::{env, fs};
+use test_utils::{CARGO_CLIPPY_PATH, IS_RUSTC_TEST_SUITE};
+
+mod test_utils;
+
+/// Creates a package `name` with the source `lib`, outside of the clippy repository so its
+/// `clippy.toml` isn't found
+fn package(name: &str, lib: &str) -> PathBuf {
+    let dir = env::temp_dir()
+        .join(format!("clippy_fix_test_{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"fix\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("src/lib.rs"), lib).unwrap();
+    dir
+}
+
+fn cargo_clippy(dir: &Path, args: &[&str]) -> Output {
+    let output = Command::new(&*CARGO_CLIPPY_PATH)
+        .current_dir(dir)
+        .env("CARGO_INCREMENTAL", "0")
+        .env_remove("CARGO_TARGET_DIR")
+        .env_remove("CARGO_MANIFEST_DIR")
+        .env_remove("CLIPPY_CONF_DIR")
+        .arg("clippy")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    output
+}
+
+#[test]
+fn dry_run() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let lib = "\
+pub fn needless_return() -> u32 {
+    return 1;
+}
+
+pub fn bool_comparison(x: bool) -> bool {
+    x == true
+}
+";
+    let dir = package("dry_run", lib);
+
+    let output = cargo_clippy(&dir, &["--fix-dry-run", "--fix-only=needless_return"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "\
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -2,1 +2,1 @@ clippy::needless_return: remove `return`
+-    return 1;
++    1
+"
+    );
+    // The suggestions are only printed
+    assert_eq!(fs::read_to_string(dir.join("src/lib.rs")).unwrap(), lib);
+
+    let output = cargo_clippy(&dir, &["--fix-dry-run"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout
+            .contains("@@ -6,1 +6,1 @@ clippy::bool_comparison: try simplifying it as shown\n-    x == true\n+    x\n"),
+        "{stdout}"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn apply_verified() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    // The suggestion of `unnecessary_wraps` doesn't change the callers of `wrapped`
+    let lib = "\
+#![warn(clippy::unnecessary_wraps)]
+
+fn wrapped(x: u8) -> Option<u8> {
+    Some(x)
+}
+
+pub fn caller() -> u8 {
+    wrapped(1).unwrap_or(0)
+}
+
+pub fn needless_return() -> u32 {
+    return 1;
+}
+";
+    let dir = package("apply_verified", lib);
+
+    let output = cargo_clippy(&dir, &["--fix-unsafe-verified"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("applied the suggestions of `clippy::needless_return`"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("warning: the suggestions of `clippy::unnecessary_wraps` introduced errors and were reverted"),
+        "{stderr}"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("src/lib.rs")).unwrap(),
+        lib.replace("return 1;", "1")
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
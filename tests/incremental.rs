@@ -0,0 +1,110 @@
+//! Checks that the diagnostics of the items which did not change are replayed when compiling
+//! incrementally, see `src/incremental.rs`
+
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
+use test_utils::{CARGO_CLIPPY_PATH, IS_RUSTC_TEST_SUITE};
+
+mod test_utils;
+
+const LIB: &str = "\
+pub fn edited() -> u32 {
+    1
+}
+
+pub fn needless_return() -> u32 {
+    return 1;
+}
+
+pub fn arguments(a: u8, b: u8, c: u8, d: u8) -> u8 {
+    a + b + c + d
+}
+";
+
+/// Creates a package `name` outside of the clippy repository, so its `clippy.toml` isn't found
+fn package(name: &str) -> PathBuf {
+    let dir = env::temp_dir()
+        .join(format!("clippy_incremental_test_{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"incremental\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("src/lib.rs"), LIB).unwrap();
+    dir
+}
+
+/// Runs `cargo clippy`, returns its stderr and the number of items replayed
+fn clippy(dir: &Path) -> (String, usize) {
+    let output = Command::new(&*CARGO_CLIPPY_PATH)
+        .current_dir(dir)
+        .env("CARGO_INCREMENTAL", "1")
+        .env_remove("CARGO_TARGET_DIR")
+        .env_remove("CARGO_MANIFEST_DIR")
+        .env_remove("CLIPPY_CONF_DIR")
+        .args(["clippy", "--", "-Zincremental-info"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let replayed = stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("[incremental] clippy: "))
+        .and_then(|line| line.split(", ").nth(1)?.strip_suffix(" items replayed")?.parse().ok())
+        .unwrap_or_else(|| panic!("no statistics of clippy's cache in:\n{stderr}"));
+    (stderr, replayed)
+}
+
+#[test]
+fn replays_unchanged_items() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let dir = package("unchanged_items");
+
+    let (stderr, replayed) = clippy(&dir);
+    assert_eq!(replayed, 0, "{stderr}");
+    assert!(stderr.contains("src/lib.rs:6:5"), "{stderr}");
+    assert!(stderr.contains("unneeded `return` statement"), "{stderr}");
+
+    // The items after the edited function moved, their diagnostics are replayed where they are now
+    let lib = LIB.replacen("    1\n", "    let one = 1;\n    one\n", 1);
+    fs::write(dir.join("src/lib.rs"), lib).unwrap();
+    let (stderr, replayed) = clippy(&dir);
+    assert_eq!(replayed, 2, "{stderr}");
+    assert!(stderr.contains("src/lib.rs:7:5"), "{stderr}");
+    assert!(stderr.contains("unneeded `return` statement"), "{stderr}");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn configuration_change_invalidates_the_cache() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let dir = package("configuration_change");
+    fs::write(dir.join("clippy.toml"), "too-many-arguments-threshold = 7\n").unwrap();
+
+    let (stderr, _) = clippy(&dir);
+    assert!(!stderr.contains("too many arguments"), "{stderr}");
+    // Rewritten so cargo runs clippy again
+    fs::write(dir.join("src/lib.rs"), LIB).unwrap();
+    let (stderr, replayed) = clippy(&dir);
+    assert_eq!(replayed, 3, "{stderr}");
+
+    fs::write(dir.join("clippy.toml"), "too-many-arguments-threshold = 3\n").unwrap();
+    let (stderr, replayed) = clippy(&dir);
+    assert_eq!(replayed, 0, "{stderr}");
+    assert!(
+        stderr.contains("this function has too many arguments (4/3)"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("unneeded `return` statement"), "{stderr}");
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,60 @@
+//! The passes registered with `register_late_mod_pass` look at one item at a time, their
+//! diagnostics are cached for each item and `check_crate` and `check_crate_post` are never called
+//! on them.
+
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+use regex::Regex;
+use std::fs;
+use walkdir::WalkDir;
+
+#[test]
+fn module_passes_dont_check_crate() {
+    let lib = fs::read_to_string("clippy_lints/src/lib.rs").unwrap();
+    let module_pass = Regex::new(
+        r"register_late_mod_pass\((?:move )?\|\w+\|\s*\{?\s*Box::(?:new\(|<)(\w+(?:::\w+)+?)(?:::new)?(?:[^\w:]|$)",
+    )
+    .unwrap();
+
+    let files: Vec<(String, String)> = WalkDir::new("clippy_lints/src")
+        .into_iter()
+        .map(Result::unwrap)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "rs"))
+        .map(|entry| {
+            let module = entry
+                .path()
+                .strip_prefix("clippy_lints/src")
+                .unwrap()
+                .with_extension("")
+                .to_str()
+                .unwrap()
+                .trim_end_matches("/mod")
+                .replace('/', "::");
+            (module, fs::read_to_string(entry.path()).unwrap())
+        })
+        .collect();
+
+    let mut passes = 0;
+    for captures in module_pass.captures_iter(&lib) {
+        let (module, name) = captures[1].rsplit_once("::").unwrap();
+        let late_lint_pass = Regex::new(&format!(
+            r"impl(<[^>]*>)? LateLintPass<[^>]*> for {name}(<[^>]*>)? \{{\n"
+        ))
+        .unwrap();
+        let body = files
+            .iter()
+            .filter(|(file_module, _)| file_module == module || file_module.starts_with(&format!("{module}::")))
+            .find_map(|(_, contents)| {
+                let start = late_lint_pass.find(contents)?.end();
+                let len = contents[start..].find("\n}\n").unwrap_or(contents.len() - start);
+                Some(&contents[start..start + len])
+            })
+            .unwrap_or_else(|| panic!("no `LateLintPass` impl found for `{module}::{name}`"));
+        assert!(
+            !body.contains("fn check_crate"),
+            "`{module}::{name}` implements `check_crate` or `check_crate_post`, register it with `register_late_pass`",
+        );
+        passes += 1;
+    }
+    assert!(passes > 0, "no module passes found in `clippy_lints/src/lib.rs`");
+}
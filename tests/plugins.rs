@@ -0,0 +1,103 @@
+//! Loads the dylib plugins of `tests/plugins` with `CLIPPY_PLUGINS`
+
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::{env, fs};
+use test_utils::IS_RUSTC_TEST_SUITE;
+
+mod test_utils;
+
+/// The directory of the dependencies of this test, which contains the builds of `clippy_utils`
+fn deps_dir() -> PathBuf {
+    env::current_exe().unwrap().parent().unwrap().to_path_buf()
+}
+
+/// The directory of the files compiled for the plugin `name`
+fn out_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("target/plugins_test")
+        .join(name);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Compiles `tests/plugins/{name}.rs` as a dylib, returns its path
+fn compile_plugin(name: &str, crate_type: &str) -> PathBuf {
+    let clippy_utils = fs::read_dir(deps_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| {
+            let path = entry.path();
+            path.extension().is_some_and(|ext| ext == "rlib")
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_str().unwrap().starts_with("libclippy_utils-"))
+        })
+        .max_by_key(|entry| entry.metadata().unwrap().modified().unwrap())
+        .expect("`clippy_utils` is built")
+        .path();
+
+    let output = Command::new("rustc")
+        .args(["--edition=2021", "--crate-type", crate_type, "--crate-name", name])
+        .arg("-Ldependency=".to_string() + deps_dir().to_str().unwrap())
+        .arg("--extern=clippy_utils=".to_string() + clippy_utils.to_str().unwrap())
+        .arg("--out-dir")
+        .arg(out_dir(name))
+        .arg(Path::new("tests/plugins").join(name).with_extension("rs"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "could not compile the plugin `{name}`:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    out_dir(name).join(format!("{}{name}{}", env::consts::DLL_PREFIX, env::consts::DLL_SUFFIX))
+}
+
+/// Runs `clippy-driver` on `tests/plugins/checked.rs` with the plugin at `plugin`
+fn run_clippy(plugin: &Path) -> Output {
+    let mut driver = deps_dir();
+    driver.set_file_name(format!("clippy-driver{}", env::consts::EXE_SUFFIX));
+    Command::new(driver)
+        .args(["--edition=2021", "--crate-type=lib", "--emit=metadata", "--out-dir"])
+        .arg(plugin.parent().unwrap())
+        .arg("tests/plugins/checked.rs")
+        .env("CLIPPY_PLUGINS", plugin)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn load_plugin() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let output = run_clippy(&compile_plugin("forbidden_name", "dylib"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(
+        stderr.contains("warning: this function is named `forbidden`"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("`#[warn(clippy::forbidden_name)]` on by default"),
+        "{stderr}"
+    );
+    assert_eq!(stderr.matches("warning: ").count(), 2, "{stderr}");
+}
+
+#[test]
+fn reject_mismatched_plugin() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let output = run_clippy(&compile_plugin("mismatched", "cdylib"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stderr}");
+    assert!(
+        stderr.contains("was compiled with rustc 1.0.0 (000000000 2015-05-15), clippy_utils 0.0.1 (plugin API 1)"),
+        "{stderr}"
+    );
+}
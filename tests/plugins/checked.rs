@@ -0,0 +1,3 @@
+pub fn allowed() {}
+
+pub fn forbidden() {}
@@ -0,0 +1,42 @@
+//! A plugin linting the functions named `forbidden`, loaded by `tests/plugins.rs`
+
+#![feature(let_chains, rustc_private)]
+
+extern crate rustc_hir;
+extern crate rustc_lint;
+extern crate rustc_session;
+
+use clippy_utils::plugin::v1::{Plugin, export_clippy_plugin, span_lint};
+use rustc_hir::{Item, ItemKind};
+use rustc_lint::{LateContext, LateLintPass, LintStore};
+use rustc_session::{declare_lint_pass, declare_tool_lint};
+
+declare_tool_lint! {
+    pub clippy::FORBIDDEN_NAME, Warn, "functions named `forbidden`"
+}
+
+declare_lint_pass!(ForbiddenName => [FORBIDDEN_NAME]);
+
+impl LateLintPass<'_> for ForbiddenName {
+    fn check_item(&mut self, cx: &LateContext<'_>, item: &Item<'_>) {
+        if let ItemKind::Fn(..) = item.kind
+            && item.ident.name.as_str() == "forbidden"
+        {
+            span_lint(
+                cx,
+                FORBIDDEN_NAME,
+                item.ident.span,
+                "this function is named `forbidden`",
+            );
+        }
+    }
+}
+
+fn register(store: &mut LintStore) {
+    store.register_lints(&[FORBIDDEN_NAME]);
+    store.register_late_pass(|_| Box::new(ForbiddenName));
+}
+
+pub const PLUGIN: Plugin = Plugin::new(register);
+
+export_clippy_plugin!(PLUGIN);
@@ -0,0 +1,7 @@
+//! Stands in for a plugin compiled with another toolchain, which `clippy-driver` must refuse to
+//! load, see `tests/plugins.rs`
+
+#[unsafe(no_mangle)]
+pub extern "C" fn __clippy_plugin_build_id() -> *const std::ffi::c_char {
+    c"rustc 1.0.0 (000000000 2015-05-15), clippy_utils 0.0.1 (plugin API 1)".as_ptr()
+}
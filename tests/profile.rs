@@ -0,0 +1,77 @@
+//! Checks the timings printed by `clippy-driver` with `CLIPPY_PROFILE`, and their report by
+//! `cargo clippy --profile-lints`, see `src/profile.rs` and `src/lint_timings.rs`
+
+#![warn(rust_2018_idioms, unused_lifetimes)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
+use test_utils::{CARGO_CLIPPY_PATH, IS_RUSTC_TEST_SUITE};
+
+mod test_utils;
+
+/// Creates a package `name` outside of the clippy repository, so its `clippy.toml` isn't found
+fn package(name: &str) -> PathBuf {
+    let dir = env::temp_dir()
+        .join(format!("clippy_profile_test_{}", std::process::id()))
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"profile\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    )
+    .unwrap();
+    fs::write(dir.join("src/lib.rs"), "pub fn one() -> u32 {\n    1\n}\n").unwrap();
+    dir
+}
+
+/// Runs `cargo clippy`, returns its output
+fn cargo_clippy(dir: &Path, cmd: impl FnOnce(&mut Command) -> &mut Command) -> (String, String) {
+    let mut clippy = Command::new(&*CARGO_CLIPPY_PATH);
+    clippy
+        .current_dir(dir)
+        .env("CARGO_INCREMENTAL", "0")
+        .env_remove("CARGO_TARGET_DIR")
+        .env_remove("CARGO_MANIFEST_DIR")
+        .env_remove("CLIPPY_CONF_DIR")
+        .env_remove("CLIPPY_PROFILE")
+        .env_remove("CLIPPY_PROFILE_DIR")
+        .arg("clippy");
+    let output = cmd(&mut clippy).output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn timings() {
+    if IS_RUSTC_TEST_SUITE {
+        return;
+    }
+    let dir = package("timings");
+
+    // Cargo wraps the line printed by `clippy-driver` in a `compiler-message`
+    let (stdout, _) = cargo_clippy(&dir, |cmd| cmd.env("CLIPPY_PROFILE", "1").arg("--message-format=json"));
+    let message = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .find(|message| message["message"]["$message_type"] == "clippy_profile")
+        .unwrap_or_else(|| panic!("no timings in:\n{stdout}"));
+    let timings = &message["message"];
+    assert_eq!(timings["crate_name"], "profile");
+    let passes = timings["passes"].as_object().unwrap();
+    assert!(passes.contains_key("Attributes"), "{timings}");
+    for (pass, total) in passes {
+        let hooks = timings["hooks"][pass].as_object().unwrap();
+        let sum: f64 = hooks.values().map(|seconds| seconds.as_f64().unwrap()).sum();
+        assert!((sum - total.as_f64().unwrap()).abs() < 1e-6, "{timings}");
+    }
+
+    let (_, stderr) = cargo_clippy(&dir, |cmd| cmd.arg("--profile-lints"));
+    assert!(stderr.contains("s in 1 crate(s)\n"), "{stderr}");
+    assert!(stderr.contains("Slowest method\n"), "{stderr}");
+    fs::remove_dir_all(dir).unwrap();
+}
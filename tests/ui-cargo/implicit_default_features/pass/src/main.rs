@@ -0,0 +1,3 @@
+#![warn(clippy::implicit_default_features)]
+
+fn main() {}
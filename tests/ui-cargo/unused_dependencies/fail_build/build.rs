@@ -0,0 +1,3 @@
+#![warn(clippy::unused_dependencies)]
+
+fn main() {}
@@ -0,0 +1,5 @@
+#![warn(clippy::unused_dependencies)]
+
+fn main() {
+    dep_b::f();
+}
@@ -0,0 +1,21 @@
+#![warn(clippy::unused_dependencies)]
+
+#[cfg(windows)]
+fn platform() {
+    dep_a::f();
+}
+
+#[cfg(feature = "extra")]
+fn feature() {
+    dep_b::f();
+}
+
+fn main() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test() {
+        dep_c::f();
+    }
+}
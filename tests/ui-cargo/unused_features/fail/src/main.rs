@@ -0,0 +1,13 @@
+#![warn(clippy::unused_features)]
+#![allow(dead_code)]
+
+#[cfg(feature = "used")]
+fn used() {}
+
+#[cfg(not(feature = "negated"))]
+fn negated() {}
+
+// feature = "unused"
+fn main() {
+    let _feature = "unused";
+}
@@ -0,0 +1,18 @@
+#![warn(clippy::unused_features)]
+#![allow(dead_code)]
+
+#[cfg(feature = "used")]
+fn used() {}
+
+#[cfg_attr(feature = "in-cfg-attr", inline)]
+fn in_cfg_attr() {}
+
+#[cfg(not(feature = "mixed"))]
+fn mixed() {}
+
+#[cfg(all(unix, feature = "mixed"))]
+fn mixed() {}
+
+fn main() {
+    if cfg!(feature = "in-macro") {}
+}
@@ -0,0 +1,39 @@
+#![allow(
+    clippy::eq_op,
+    clippy::unnecessary_literal_unwrap,
+    clippy::unwrap_or_default,
+    clippy::useless_vec
+)]
+
+fn vec() -> Option<Vec<u8>> {
+    None
+}
+
+fn main() {
+    let _ = vec().unwrap_or(Vec::new());
+    //~^ unwrap_or_new_vec
+    let _ = Some(vec![1]).unwrap_or(std::vec::Vec::new());
+    //~^ unwrap_or_new_vec
+    let _ = Some(String::new()).unwrap_or(String::new());
+    let _ = Ok::<_, ()>(vec![1u8]).unwrap_or(Vec::new());
+
+    let x = 1;
+    let _ = x - x;
+    //~^ self_sub
+    let _ = (x + 1) - (x + 1);
+    //~^ self_sub
+    let _ = x - 1;
+
+    let _ = String::from("");
+    //~^ string_from_empty
+    let _ = String::from("a");
+
+    let v = &vec![1u8, 2, 3];
+    let _ = v[1..2].len();
+    //~^ len_of_slice_of
+}
+
+#[allow(clippy::self_sub)]
+fn allowed(x: i32) -> i32 {
+    x - x
+}
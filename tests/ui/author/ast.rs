@@ -0,0 +1,18 @@
+#![allow(clippy::no_effect, clippy::double_parens)]
+#![feature(stmt_expr_attributes)]
+
+fn main() {
+    #[clippy::author(ast)]
+    let x = (1 + 2) * 3;
+
+    #[clippy::author(ast)]
+    match Some(x) {
+        Some(1..=3) | None => {},
+        Some(_) => {},
+    }
+}
+
+#[clippy::author(ast)]
+fn is_positive(n: i32) -> bool {
+    n > 0
+}
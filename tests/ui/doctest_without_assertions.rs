@@ -0,0 +1,95 @@
+#![warn(clippy::doctest_without_assertions)]
+//@no-rustfix: the suggestions have placeholders
+
+/// This only checks that the code compiles
+///
+/// ```
+/// let x = double(2);
+/// ```
+///
+/// ```
+/// double(2);
+/// ```
+///
+/// This does not call anything, so there is nothing to check
+///
+/// ```
+/// let x = 2;
+/// ```
+///
+/// Constructors and the functions defined in the example don't need to be checked
+///
+/// ```
+/// let v: Vec<u32> = Vec::new();
+/// let s = String::from("two");
+/// let o = Some(2);
+/// ```
+///
+/// ```
+/// fn triple(x: u32) -> u32 {
+///     x * 3
+/// }
+/// let x = triple(2);
+/// ```
+///
+/// Checks with assertions, `?`, `unwrap` and printing are fine
+///
+/// ```
+/// assert_eq!(double(2), 4);
+/// ```
+///
+/// ```
+/// # fn main() -> Result<(), std::num::ParseIntError> {
+/// let x: u32 = "2".parse()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// ```
+/// let x: u32 = "2".parse().unwrap();
+/// ```
+///
+/// ```
+/// println!("{}", double(2));
+/// ```
+///
+/// Examples which are not run are not linted
+///
+/// ```no_run
+/// let x = double(2);
+/// ```
+///
+/// ```should_panic
+/// let x = double(u32::MAX);
+/// ```
+///
+/// ```ignore
+/// let x = double(2);
+/// ```
+///
+/// ```text
+/// let x = double(2);
+/// ```
+pub fn double(x: u32) -> u32 {
+    x * 2
+}
+
+/// `?Sized` does not propagate an error
+///
+/// ```
+/// fn size<T: ?Sized>(_: &T) {}
+/// size("");
+/// let x = double(2);
+/// ```
+pub fn sized() {}
+
+/// The end of the example is hidden
+///
+/// ```
+/// # fn main() {
+/// let x = double(2);
+/// # }
+/// ```
+pub fn hidden() {}
+
+fn main() {}
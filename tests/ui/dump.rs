@@ -0,0 +1,22 @@
+//@check-pass
+
+#![allow(clippy::no_effect, clippy::identity_op)]
+#![feature(stmt_expr_attributes)]
+
+const TWO: u32 = 2;
+
+#[clippy::dump(types, consts, mir)]
+fn sum(values: &[u32]) -> u32 {
+    values.len() as u32 + TWO * 3
+}
+
+fn main() {
+    let s = String::from("dump");
+    #[clippy::dump(types)]
+    let _ = s.trim();
+
+    #[clippy::dump(consts)]
+    [1u8, 2].len();
+
+    let _ = sum(&[1, 2]);
+}
@@ -0,0 +1,18 @@
+//@revisions: default optimized
+//@[optimized] compile-flags: -Zmir-opt-level=4 -Copt-level=3
+// The MIR optimization level of the crate is kept, the MIR lints analyze the MIR from before the
+// optimization passes which inline the calls and remove the unused locals
+#![warn(clippy::redundant_clone)]
+
+#[inline]
+fn len(s: String) -> usize {
+    s.len()
+}
+
+fn main() {
+    let s = String::from("foo");
+    let _ = len(s.clone());
+
+    let s = String::from("foo");
+    let _s = s.clone();
+}
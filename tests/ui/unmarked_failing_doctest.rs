@@ -0,0 +1,53 @@
+#![warn(clippy::unmarked_failing_doctest)]
+
+/// ```
+/// let v: Vec<u8> = Vec::new();
+/// let x = v[0]; // this panics
+/// ```
+///
+/// ```
+/// let x: u32 = "a"; // error: mismatched types
+/// ```
+///
+/// ```
+/// let s = String::new();
+/// let t = s;
+/// let u = s; /* doesn't compile, `s` was moved */
+/// ```
+///
+/// ```
+/// // This fails
+/// let x: u8 = 256;
+/// ```
+///
+/// `no_run` examples are compiled, but not run
+///
+/// ```no_run
+/// let x: u32 = "a"; // error[E0308]: mismatched types
+/// ```
+///
+/// ```no_run
+/// let v: Vec<u8> = Vec::new();
+/// let x = v[0]; // this panics
+/// ```
+///
+/// Correctly marked examples are fine
+///
+/// ```compile_fail
+/// let x: u32 = "a"; // error: mismatched types
+/// ```
+///
+/// ```should_panic
+/// let v: Vec<u8> = Vec::new();
+/// let x = v[0]; // this panics
+/// ```
+///
+/// Comments mentioning errors in other ways are fine
+///
+/// ```
+/// // error handling is omitted for brevity
+/// let x: u32 = "2".parse().unwrap();
+/// ```
+pub fn failing() {}
+
+fn main() {}
@@ -94,8 +94,49 @@ mod issue_6001 {
     }
 }
 
+// `Reverse` is imported in the module of the suggestion, unless it is already
+mod imports {
+    pub mod imported {
+        use std::cmp::{Ordering, Reverse};
+
+        pub fn test(vec: &mut [i32]) {
+            vec.sort_by(|a, b| b.abs().cmp(&a.abs()));
+            let _ = Reverse(Ordering::Less);
+        }
+    }
+
+    pub mod other_reverse {
+        struct Reverse;
+
+        pub fn test(vec: &mut [i32]) {
+            vec.sort_by(|a, b| b.abs().cmp(&a.abs()));
+            let _ = Reverse;
+        }
+    }
+
+    // The `use` is inserted in the order of rustfmt
+    pub mod ordered {
+        use self::inner::Inner;
+        use super::imported;
+        use crate::issue_6001;
+        use std::{fmt, io};
+
+        mod inner {
+            pub struct Inner;
+        }
+
+        pub fn test(vec: &mut [i32]) {
+            vec.sort_by(|a, b| b.abs().cmp(&a.abs()));
+            let _: Option<(Inner, fmt::Error, io::Error)> = None;
+        }
+    }
+}
+
 fn main() {
     unnecessary_sort_by();
     issue_5754::test();
     issue_6001::test();
+    imports::imported::test(&mut []);
+    imports::other_reverse::test(&mut []);
+    imports::ordered::test(&mut []);
 }
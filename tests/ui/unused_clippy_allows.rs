@@ -0,0 +1,83 @@
+//@aux-build:proc_macros.rs
+#![warn(clippy::unused_clippy_allows)]
+
+extern crate proc_macros;
+use proc_macros::external;
+
+#[allow(clippy::needless_return)]
+fn used() -> u32 {
+    return 1;
+}
+
+#[allow(clippy::needless_return)]
+fn unused() -> u32 {
+    1
+}
+
+#[allow(clippy::needless_return, reason = "it used to return early")]
+fn unused_with_reason() -> u32 {
+    1
+}
+
+#[allow(clippy::needless_return, clippy::len_zero)]
+fn partially_used() -> u32 {
+    return 1;
+}
+
+#[allow(clippy::len_zero, clippy::unit_arg, clippy::needless_return)]
+fn unused_at_start() -> u32 {
+    return 1;
+}
+
+#[allow(clippy::needless_return, dead_code)]
+fn other_lints_are_kept() -> u32 {
+    1
+}
+
+fn statements() {
+    #[allow(clippy::len_zero)]
+    let v: Vec<u8> = Vec::new();
+    #[allow(clippy::len_zero)]
+    let _ = v.len() == 0;
+}
+
+// The innermost `allow` suppresses the lint
+#[allow(clippy::len_zero)]
+fn nested(v: &[u8]) -> bool {
+    #[allow(clippy::len_zero)]
+    let empty = v.len() == 0;
+    empty
+}
+
+#[allow(clippy::len_zero)]
+mod module {
+    pub fn empty(v: &[u8]) -> bool {
+        v.len() == 0
+    }
+}
+
+// The lint only checks if it is allowed, it would not be emitted
+#[allow(clippy::len_without_is_empty)]
+pub struct HasIsEmpty;
+
+impl HasIsEmpty {
+    pub fn len(&self) -> usize {
+        0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        true
+    }
+}
+
+// Lint groups are not checked
+#[allow(clippy::style)]
+fn group() {}
+
+// `allow`s inside of macros are not checked
+external! {
+    #[allow(clippy::len_zero)]
+    fn external() {}
+}
+
+fn main() {}
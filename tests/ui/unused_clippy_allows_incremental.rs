@@ -0,0 +1,31 @@
+//@compile-flags: -C incremental=target/debug/test/incr
+#![warn(clippy::unused_clippy_allows)]
+
+// The diagnostics of `zero_divided_by_zero` and `excessive_precision` are cached for each item
+// when compiling incrementally, unless `unused_clippy_allows` is enabled
+
+#[allow(clippy::eq_op, clippy::zero_divided_by_zero)]
+fn used() -> f64 {
+    0.0 / 0.0
+}
+
+#[allow(clippy::zero_divided_by_zero)]
+fn unused() -> f64 {
+    1.0 / 2.0
+}
+
+#[allow(clippy::excessive_precision)]
+mod module {
+    pub const USED: f32 = 1.123_456_789_012;
+}
+
+#[allow(clippy::excessive_precision)]
+mod unused_module {
+    pub const UNUSED: f32 = 1.5;
+}
+
+fn main() {
+    used();
+    unused();
+    let _ = module::USED + unused_module::UNUSED;
+}
@@ -0,0 +1,15 @@
+//@no-rustfix
+#![warn(clippy::unused_clippy_allows)]
+// The passes of lints which are allowed everywhere may not run at all, so these are not checked
+#![allow(clippy::needless_return)]
+
+// The attribute can't be removed without touching the `cfg_attr`
+#[cfg_attr(all(), allow(clippy::len_zero))]
+fn cfg_attr() {}
+
+#[allow(clippy::needless_return)]
+fn allowed_everywhere() -> u32 {
+    1
+}
+
+fn main() {}